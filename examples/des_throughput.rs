@@ -0,0 +1,60 @@
+//! Throughput of bitsliced DES against the scalar implementation, run with
+//! `cargo run --release --example des_throughput`
+
+use krypto::bitslice::{BitslicedDes, Lanes};
+use krypto::des::Des;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const BLOCKS: usize = 1 << 16;
+const KEY: [u8; 8] = [0x13, 0x34, 0x57, 0x79, 0x9B, 0xBC, 0xDF, 0xF1];
+
+fn blocks() -> Vec<[u8; 8]> {
+    (0..BLOCKS as u64)
+        .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15).to_be_bytes())
+        .collect()
+}
+
+/// The best of a few runs, to keep other processes out of the figure
+fn time(mut run: impl FnMut()) -> Duration {
+    (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, elapsed: Duration, scalar: Duration) {
+    let megabytes = (BLOCKS * 8) as f64 / 1e6;
+    println!(
+        "{name:<20} {:>8.1} MB/s {:>6.1}x",
+        megabytes / elapsed.as_secs_f64(),
+        scalar.as_secs_f64() / elapsed.as_secs_f64()
+    );
+}
+
+fn bitsliced<L: Lanes>(plaintext: &[[u8; 8]]) -> Duration {
+    let des = BitslicedDes::<L>::new(&KEY);
+    time(|| {
+        let mut blocks = plaintext.to_vec();
+        des.encrypt_blocks(black_box(&mut blocks));
+        black_box(blocks);
+    })
+}
+
+fn main() {
+    let plaintext = blocks();
+    let des = Des::new(&KEY);
+    let scalar = time(|| {
+        for block in &plaintext {
+            black_box(des.encrypt_block(black_box(block)));
+        }
+    });
+
+    report("Des", scalar, scalar);
+    report("BitslicedDes<u64>", bitsliced::<u64>(&plaintext), scalar);
+    report("BitslicedDes<u128>", bitsliced::<u128>(&plaintext), scalar);
+}
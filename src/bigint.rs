@@ -0,0 +1,827 @@
+//! Arbitrary precision unsigned integers for the schemes whose moduli do not
+//! fit in a `u128`
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Shl, Shr, Sub};
+
+/// Unsigned integer stored as little endian 64 bit limbs with no leading
+/// (most significant) zero limbs, so zero is the empty vector
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    limbs: Vec<u64>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        Self { limbs: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        Self::from(1u64)
+    }
+
+    fn from_limbs(mut limbs: Vec<u64>) -> Self {
+        while limbs.last() == Some(&0) {
+            limbs.pop();
+        }
+        Self { limbs }
+    }
+
+    pub fn from_be_bytes(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .rchunks(8)
+            .map(|chunk| chunk.iter().fold(0, |acc, &b| (acc << 8) | b as u64))
+            .collect();
+        Self::from_limbs(limbs)
+    }
+
+    pub fn from_le_bytes(bytes: &[u8]) -> Self {
+        let limbs = bytes
+            .chunks(8)
+            .map(|chunk| {
+                chunk.iter().rev().fold(0, |acc, &b| (acc << 8) | b as u64)
+            })
+            .collect();
+        Self::from_limbs(limbs)
+    }
+
+    /// Parses a hexadecimal string, whitespace is ignored so values can be
+    /// split across lines
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let digits: Vec<u8> = hex
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        if digits.is_empty() {
+            return None;
+        }
+
+        let limbs = digits
+            .rchunks(16)
+            .map(|chunk| chunk.iter().fold(0, |acc, &d| (acc << 4) | d as u64))
+            .collect();
+        Some(Self::from_limbs(limbs))
+    }
+
+    /// Minimal big endian encoding, zero encodes to no bytes
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        self.to_be_bytes_padded(self.bits().div_ceil(8))
+    }
+
+    /// Big endian encoding left padded with zeros to `len` bytes
+    pub fn to_be_bytes_padded(&self, len: usize) -> Vec<u8> {
+        assert!(self.bits() <= len * 8, "value does not fit in {len} bytes");
+        let mut bytes = self.to_le_bytes_padded(len);
+        bytes.reverse();
+        bytes
+    }
+
+    /// Little endian encoding right padded with zeros to `len` bytes
+    pub fn to_le_bytes_padded(&self, len: usize) -> Vec<u8> {
+        assert!(self.bits() <= len * 8, "value does not fit in {len} bytes");
+        let mut bytes: Vec<u8> = self
+            .limbs
+            .iter()
+            .flat_map(|limb| limb.to_le_bytes())
+            .collect();
+        bytes.resize(len, 0);
+        bytes
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.limbs.first().is_some_and(|limb| limb & 1 == 1)
+    }
+
+    pub fn is_even(&self) -> bool {
+        !self.is_odd()
+    }
+
+    /// Number of significant bits
+    pub fn bits(&self) -> usize {
+        match self.limbs.last() {
+            Some(top) => self.limbs.len() * 64 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub fn bit(&self, index: usize) -> bool {
+        self.limbs
+            .get(index / 64)
+            .is_some_and(|limb| (limb >> (index % 64)) & 1 == 1)
+    }
+
+    /// The lowest 64 bits
+    pub fn low_u64(&self) -> u64 {
+        self.limbs.first().copied().unwrap_or(0)
+    }
+
+    /// Keeps only the lowest `bits` bits, i.e. reduces mod `2^bits`
+    pub fn truncate(&self, bits: usize) -> Self {
+        let mut limbs: Vec<u64> =
+            self.limbs.iter().copied().take(bits.div_ceil(64)).collect();
+        if !bits.is_multiple_of(64) {
+            if let Some(top) = limbs.get_mut(bits / 64) {
+                *top &= (1 << (bits % 64)) - 1;
+            }
+        }
+        Self::from_limbs(limbs)
+    }
+
+    pub fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        assert!(!divisor.is_zero(), "attempt to divide by zero");
+        if self < divisor {
+            return (Self::zero(), self.clone());
+        }
+        if divisor.limbs.len() == 1 {
+            let (quotient, remainder) =
+                div_rem_limb(&self.limbs, divisor.limbs[0]);
+            return (Self::from_limbs(quotient), Self::from(remainder));
+        }
+        let (quotient, remainder) = div_rem_knuth(&self.limbs, &divisor.limbs);
+        (Self::from_limbs(quotient), Self::from_limbs(remainder))
+    }
+
+    /// `self^exp mod modulus` by square and multiply, in Montgomery form
+    /// when the modulus is odd
+    pub fn pow_mod(&self, exp: &Self, modulus: &Self) -> Self {
+        if modulus.is_odd() && modulus > &Self::one() {
+            return Montgomery::new(modulus).pow(self, exp);
+        }
+
+        let mut result = Self::one() % modulus;
+        let mut base = self % modulus;
+        for bit in 0..exp.bits() {
+            if exp.bit(bit) {
+                result = &(&result * &base) % modulus;
+            }
+            base = &(&base * &base) % modulus;
+        }
+        result
+    }
+
+    /// `self^-1 mod modulus` by the extended Euclidean algorithm, `None` when
+    /// `self` and `modulus` are not coprime
+    pub fn mod_inverse(&self, modulus: &Self) -> Option<Self> {
+        // The Bezout coefficient of self is tracked mod `modulus` so it never
+        // goes negative
+        let (mut old_r, mut r) = (self % modulus, modulus.clone());
+        let (mut old_s, mut s) = (Self::one(), Self::zero());
+        while !r.is_zero() {
+            let (quotient, remainder) = old_r.div_rem(&r);
+            old_r = std::mem::replace(&mut r, remainder);
+            let qs = &(&quotient * &s) % modulus;
+            let next_s = (&old_s + &(modulus - &qs)) % modulus;
+            old_s = std::mem::replace(&mut s, next_s);
+        }
+        (old_r == Self::one()).then(|| old_s % modulus)
+    }
+
+    pub fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = std::mem::replace(&mut b, r);
+        }
+        a
+    }
+
+    /// `(self + other) mod modulus` for already reduced operands
+    pub fn add_mod(&self, other: &Self, modulus: &Self) -> Self {
+        let sum = self + other;
+        if &sum >= modulus {
+            &sum - modulus
+        } else {
+            sum
+        }
+    }
+
+    /// `(self - other) mod modulus` for already reduced operands
+    pub fn sub_mod(&self, other: &Self, modulus: &Self) -> Self {
+        if self >= other {
+            self - other
+        } else {
+            &(self + modulus) - other
+        }
+    }
+
+    pub fn mul_mod(&self, other: &Self, modulus: &Self) -> Self {
+        &(self * other) % modulus
+    }
+
+    /// Miller-Rabin with the first `rounds` primes as witnesses, preceded by
+    /// trial division by the small primes
+    pub fn is_probable_prime(&self, rounds: usize) -> bool {
+        let small_primes = small_primes(rounds.max(SMALL_PRIME_LIMIT));
+        for &prime in &small_primes {
+            let prime = Self::from(prime);
+            if self == &prime {
+                return true;
+            }
+            if (self % &prime).is_zero() {
+                return false;
+            }
+        }
+        if self < &Self::from(2u64) {
+            return false;
+        }
+
+        let one = Self::one();
+        let minus_one = self - &one;
+        let mut odd = minus_one.clone();
+        let mut twos = 0;
+        while odd.is_even() {
+            odd = odd >> 1;
+            twos += 1;
+        }
+
+        'witness: for &witness in small_primes.iter().take(rounds) {
+            let mut x = Self::from(witness).pow_mod(&odd, self);
+            if x == one || x == minus_one {
+                continue;
+            }
+            for _ in 1..twos {
+                x = x.mul_mod(&x, self);
+                if x == minus_one {
+                    continue 'witness;
+                }
+            }
+            return false;
+        }
+        true
+    }
+}
+
+/// Arithmetic mod an odd modulus `m` on values scaled by `R = 2^(64 * limbs)`,
+/// which turns the reduction after each multiplication into shifts
+struct Montgomery<'a> {
+    modulus: &'a BigUint,
+    /// `-m^-1 mod 2^64`
+    m_inv: u64,
+}
+
+impl<'a> Montgomery<'a> {
+    fn new(modulus: &'a BigUint) -> Self {
+        // Newton's iteration doubles the correct low bits of the inverse
+        let m0 = modulus.limbs[0];
+        let mut inverse: u64 = 1;
+        for _ in 0..6 {
+            inverse = inverse
+                .wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inverse)));
+        }
+        Self {
+            modulus,
+            m_inv: inverse.wrapping_neg(),
+        }
+    }
+
+    fn encode(&self, value: &BigUint) -> Vec<u64> {
+        let n = self.modulus.limbs.len();
+        let mut limbs = (&(value % self.modulus) << (64 * n)) % self.modulus;
+        limbs.limbs.resize(n, 0);
+        limbs.limbs
+    }
+
+    fn decode(&self, limbs: &[u64]) -> BigUint {
+        let mut one = vec![0; limbs.len()];
+        one[0] = 1;
+        BigUint::from_limbs(self.mul(limbs, &one))
+    }
+
+    /// `a * b / R mod m` by coarsely integrated operand scanning
+    fn mul(&self, a: &[u64], b: &[u64]) -> Vec<u64> {
+        let m = &self.modulus.limbs;
+        let n = m.len();
+        let mut t = vec![0u64; n + 2];
+        for &b_limb in b {
+            let mut carry = 0;
+            for j in 0..n {
+                let sum = t[j] as u128 + a[j] as u128 * b_limb as u128 + carry;
+                t[j] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128 + carry;
+            t[n] = sum as u64;
+            t[n + 1] = (sum >> 64) as u64;
+
+            // Add the multiple of m that clears the lowest limb, then drop it
+            let u = t[0].wrapping_mul(self.m_inv);
+            let mut carry = (t[0] as u128 + u as u128 * m[0] as u128) >> 64;
+            for j in 1..n {
+                let sum = t[j] as u128 + u as u128 * m[j] as u128 + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128 + carry;
+            t[n - 1] = sum as u64;
+            t[n] = t[n + 1] + (sum >> 64) as u64;
+            t[n + 1] = 0;
+        }
+
+        let overflow = t[n] != 0;
+        t.truncate(n);
+        if overflow || t.iter().rev().cmp(m.iter().rev()) != Ordering::Less {
+            let mut borrow = 0;
+            for (limb, &m_limb) in t.iter_mut().zip(m) {
+                let (diff, b1) = limb.overflowing_sub(m_limb);
+                let (diff, b2) = diff.overflowing_sub(borrow);
+                *limb = diff;
+                borrow = (b1 | b2) as u64;
+            }
+        }
+        t
+    }
+
+    /// Left to right exponentiation with a fixed four bit window
+    fn pow(&self, base: &BigUint, exp: &BigUint) -> BigUint {
+        let mut table = vec![self.encode(&BigUint::one())];
+        let base = self.encode(base);
+        for i in 1..16 {
+            let next = self.mul(&table[i - 1], &base);
+            table.push(next);
+        }
+
+        let mut result = table[0].clone();
+        for window in (0..exp.bits().div_ceil(4)).rev() {
+            for _ in 0..4 {
+                result = self.mul(&result, &result);
+            }
+            let digit = (0..4).fold(0, |acc, bit| {
+                acc | (exp.bit(window * 4 + bit) as usize) << bit
+            });
+            if digit != 0 {
+                result = self.mul(&result, &table[digit]);
+            }
+        }
+        self.decode(&result)
+    }
+}
+
+/// How many primes are used for trial division before Miller-Rabin
+const SMALL_PRIME_LIMIT: usize = 256;
+
+fn small_primes(count: usize) -> Vec<u64> {
+    let mut primes: Vec<u64> = Vec::with_capacity(count);
+    let mut candidate = 2;
+    while primes.len() < count {
+        if primes
+            .iter()
+            .take_while(|&&p| p * p <= candidate)
+            .all(|&p| candidate % p != 0)
+        {
+            primes.push(candidate);
+        }
+        candidate += 1;
+    }
+    primes
+}
+
+fn div_rem_limb(limbs: &[u64], divisor: u64) -> (Vec<u64>, u64) {
+    let mut quotient = vec![0; limbs.len()];
+    let mut remainder: u128 = 0;
+    for (i, &limb) in limbs.iter().enumerate().rev() {
+        let current = (remainder << 64) | limb as u128;
+        quotient[i] = (current / divisor as u128) as u64;
+        remainder = current % divisor as u128;
+    }
+    (quotient, remainder as u64)
+}
+
+/// Knuth's Algorithm D (TAOCP vol. 2, 4.3.1) for divisors of two or more limbs
+fn div_rem_knuth(dividend: &[u64], divisor: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    const BASE: u128 = 1 << 64;
+    let n = divisor.len();
+    let m = dividend.len();
+
+    // Normalise so the top bit of the divisor is set, that keeps the quotient
+    // estimate within two of the real digit
+    let shift = divisor[n - 1].leading_zeros();
+    let vn = shl_limbs(divisor, shift, n);
+    let mut un = shl_limbs(dividend, shift, m + 1);
+
+    let mut quotient = vec![0; m - n + 1];
+    for j in (0..=m - n).rev() {
+        let numerator = (un[j + n] as u128) << 64 | un[j + n - 1] as u128;
+        let mut qhat = numerator / vn[n - 1] as u128;
+        let mut rhat = numerator % vn[n - 1] as u128;
+        while qhat >= BASE
+            || qhat * vn[n - 2] as u128 > (rhat << 64 | un[j + n - 2] as u128)
+        {
+            qhat -= 1;
+            rhat += vn[n - 1] as u128;
+            if rhat >= BASE {
+                break;
+            }
+        }
+
+        // un[j..=j + n] -= qhat * vn
+        let mut borrow = 0;
+        let mut carry = 0;
+        for i in 0..n {
+            let product = qhat * vn[i] as u128 + carry;
+            carry = product >> 64;
+            let (diff, b1) = un[i + j].overflowing_sub(product as u64);
+            let (diff, b2) = diff.overflowing_sub(borrow);
+            un[i + j] = diff;
+            borrow = (b1 | b2) as u64;
+        }
+        let (diff, b1) = un[j + n].overflowing_sub(carry as u64);
+        let (diff, b2) = diff.overflowing_sub(borrow);
+        un[j + n] = diff;
+
+        // The estimate was one too big, add the divisor back
+        if b1 | b2 {
+            qhat -= 1;
+            let mut carry = 0;
+            for i in 0..n {
+                let sum = un[i + j] as u128 + vn[i] as u128 + carry;
+                un[i + j] = sum as u64;
+                carry = sum >> 64;
+            }
+            un[j + n] = un[j + n].wrapping_add(carry as u64);
+        }
+        quotient[j] = qhat as u64;
+    }
+
+    let remainder = shr_limbs(&un[..n], shift);
+    (quotient, remainder)
+}
+
+/// Shifts left by less than a limb into a buffer of `len` limbs
+fn shl_limbs(limbs: &[u64], shift: u32, len: usize) -> Vec<u64> {
+    let mut out = vec![0; len];
+    let mut carry = 0;
+    for (i, &limb) in limbs.iter().enumerate() {
+        out[i] = (limb << shift) | carry;
+        carry = if shift == 0 { 0 } else { limb >> (64 - shift) };
+    }
+    if limbs.len() < len {
+        out[limbs.len()] = carry;
+    }
+    out
+}
+
+/// Shifts right by less than a limb
+fn shr_limbs(limbs: &[u64], shift: u32) -> Vec<u64> {
+    let mut out = vec![0; limbs.len()];
+    for i in 0..limbs.len() {
+        let high = match limbs.get(i + 1) {
+            Some(next) if shift != 0 => next << (64 - shift),
+            _ => 0,
+        };
+        out[i] = (limbs[i] >> shift) | high;
+    }
+    out
+}
+
+impl From<u64> for BigUint {
+    fn from(value: u64) -> Self {
+        Self::from_limbs(vec![value])
+    }
+}
+
+impl From<u32> for BigUint {
+    fn from(value: u32) -> Self {
+        Self::from(value as u64)
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(value: u128) -> Self {
+        Self::from_limbs(vec![value as u64, (value >> 64) as u64])
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs
+            .len()
+            .cmp(&other.limbs.len())
+            .then_with(|| self.limbs.iter().rev().cmp(other.limbs.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let (long, short) = if self.limbs.len() >= other.limbs.len() {
+            (&self.limbs, &other.limbs)
+        } else {
+            (&other.limbs, &self.limbs)
+        };
+        let mut limbs = Vec::with_capacity(long.len() + 1);
+        let mut carry = 0;
+        for (i, &limb) in long.iter().enumerate() {
+            let sum =
+                limb as u128 + *short.get(i).unwrap_or(&0) as u128 + carry;
+            limbs.push(sum as u64);
+            carry = sum >> 64;
+        }
+        limbs.push(carry as u64);
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Sub<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        assert!(self >= other, "attempt to subtract with overflow");
+        let mut limbs = Vec::with_capacity(self.limbs.len());
+        let mut borrow = 0;
+        for (i, &limb) in self.limbs.iter().enumerate() {
+            let (diff, b1) =
+                limb.overflowing_sub(*other.limbs.get(i).unwrap_or(&0));
+            let (diff, b2) = diff.overflowing_sub(borrow);
+            limbs.push(diff);
+            borrow = (b1 | b2) as u64;
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        if self.is_zero() || other.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0; self.limbs.len() + other.limbs.len()];
+        for (i, &a) in self.limbs.iter().enumerate() {
+            let mut carry = 0;
+            for (j, &b) in other.limbs.iter().enumerate() {
+                let product =
+                    a as u128 * b as u128 + limbs[i + j] as u128 + carry;
+                limbs[i + j] = product as u64;
+                carry = product >> 64;
+            }
+            limbs[i + other.limbs.len()] = carry as u64;
+        }
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+macro_rules! forward_binop {
+    ($imp:ident, $method:ident) => {
+        impl $imp<BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $imp<&BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: &BigUint) -> BigUint {
+                (&self).$method(other)
+            }
+        }
+
+        impl $imp<BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add);
+forward_binop!(Sub, sub);
+forward_binop!(Mul, mul);
+forward_binop!(Div, div);
+forward_binop!(Rem, rem);
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, bits: usize) -> BigUint {
+        if self.is_zero() {
+            return BigUint::zero();
+        }
+        let mut limbs = vec![0; bits / 64];
+        limbs.extend(shl_limbs(
+            &self.limbs,
+            (bits % 64) as u32,
+            self.limbs.len() + 1,
+        ));
+        BigUint::from_limbs(limbs)
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, bits: usize) -> BigUint {
+        match self.limbs.get(bits / 64..) {
+            Some(limbs) => {
+                BigUint::from_limbs(shr_limbs(limbs, (bits % 64) as u32))
+            }
+            None => BigUint::zero(),
+        }
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, bits: usize) -> BigUint {
+        &self << bits
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, bits: usize) -> BigUint {
+        &self >> bits
+    }
+}
+
+impl fmt::UpperHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut limbs = self.limbs.iter().rev();
+        match limbs.next() {
+            Some(top) => write!(f, "{top:X}")?,
+            None => write!(f, "0")?,
+        }
+        limbs.try_for_each(|limb| write!(f, "{limb:016X}"))
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut limbs = self.limbs.iter().rev();
+        match limbs.next() {
+            Some(top) => write!(f, "{top:x}")?,
+            None => write!(f, "0")?,
+        }
+        limbs.try_for_each(|limb| write!(f, "{limb:016x}"))
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{self:X}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> BigUint {
+        BigUint::from_hex(s).unwrap()
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let bytes =
+            [0x00, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0xfe, 0xdc];
+        let n = BigUint::from_be_bytes(&bytes);
+        assert_eq!(n, hex("0023456789abcdeffedc"));
+        assert_eq!(n.to_be_bytes(), bytes[1..]);
+        assert_eq!(n.to_be_bytes_padded(10), bytes);
+        assert_eq!(BigUint::from_le_bytes(&n.to_le_bytes_padded(12)), n);
+        assert_eq!(format!("{n:x}"), "23456789abcdeffedc");
+    }
+
+    #[test]
+    fn test_arithmetic_matches_u128() {
+        let values: [u128; 6] = [
+            0,
+            1,
+            0xffff_ffff_ffff_ffff,
+            0x1_0000_0000_0000_0000,
+            0x1234_5678_9abc_def0_1122_3344,
+            u64::MAX as u128 * 3 + 7,
+        ];
+        for &a in &values {
+            for &b in &values {
+                let (x, y) = (BigUint::from(a), BigUint::from(b));
+                assert_eq!(&x + &y, BigUint::from(a + b));
+                if a >= b {
+                    assert_eq!(&x - &y, BigUint::from(a - b));
+                }
+                if let Some(product) = a.checked_mul(b) {
+                    assert_eq!(&x * &y, BigUint::from(product));
+                }
+                if let Some(quotient) = a.checked_div(b) {
+                    assert_eq!(
+                        x.div_rem(&y),
+                        (BigUint::from(quotient), BigUint::from(a % b))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_knuth_division() {
+        let a = hex(
+            "86F5CA03DCFEB225063FF830A0C769B9DD9D6153AD91D7CE27F787C43278B447",
+        );
+        let b = hex("996F967F6C8E388D9E28D01E205FBA957A5698B1");
+        let (q, r) = a.div_rem(&b);
+        assert!(r < b);
+        assert_eq!(&(&q * &b) + &r, a);
+
+        // Forces the add back step, the first quotient estimate is too big
+        let a = hex("7fffffff800000010000000000000000");
+        let b = hex("800000008000000200000005");
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(&(&q * &b) + &r, a);
+        assert!(r < b);
+    }
+
+    #[test]
+    fn test_shifts() {
+        let n = hex("123456789abcdef0fedcba9876543210");
+        assert_eq!(
+            &n << 68,
+            hex("123456789abcdef0fedcba987654321000000000000000000")
+        );
+        assert_eq!(&(&n << 68) >> 68, n);
+        assert_eq!(&n >> 200, BigUint::zero());
+        assert_eq!(n.truncate(12), hex("210"));
+    }
+
+    #[test]
+    fn test_pow_mod() {
+        assert_eq!(
+            BigUint::from(4u64)
+                .pow_mod(&BigUint::from(16u64), &BigUint::from(23u64)),
+            BigUint::from(12u64)
+        );
+        // Fermat: a^(p-1) = 1 mod p for the prime 2^127 - 1
+        let p = hex("7fffffffffffffffffffffffffffffff");
+        let a = hex("1234567890abcdef1234567890abcdef");
+        assert_eq!(a.pow_mod(&(&p - &BigUint::one()), &p), BigUint::one());
+    }
+
+    #[test]
+    fn test_pow_mod_even_and_odd_moduli_agree() {
+        let base = hex(
+            "86F5CA03DCFEB225063FF830A0C769B9DD9D6153AD91D7CE27F787C43278B447",
+        );
+        let exp = hex("996F967F6C8E388D9E28D01E205FBA957A5698B1");
+        let odd = hex(
+            "E6533B86B18BED6E8A48B784A14C252C5BE0DBF60B86D6385BD2F12FB763ED89",
+        );
+        // x^e mod m from x^e mod 2m, the square and multiply path
+        let even = &odd << 1;
+        assert_eq!(base.pow_mod(&exp, &odd), base.pow_mod(&exp, &even) % &odd);
+        assert_eq!(base.pow_mod(&BigUint::zero(), &odd), BigUint::one());
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        let m = BigUint::from(3120u64);
+        let inverse = BigUint::from(17u64).mod_inverse(&m).unwrap();
+        assert_eq!(inverse, BigUint::from(2753u64));
+        assert_eq!(BigUint::from(12u64).mod_inverse(&m), None);
+    }
+
+    #[test]
+    fn test_is_probable_prime() {
+        assert!(hex("7fffffffffffffffffffffffffffffff").is_probable_prime(20));
+        assert!(BigUint::from(1_000_000_007u64).is_probable_prime(20));
+        assert!(!BigUint::from(1u64).is_probable_prime(20));
+        // Carmichael number
+        assert!(!BigUint::from(3_215_031_751u64).is_probable_prime(20));
+        // 2^128 + 1 is composite
+        assert!(!hex("100000000000000000000000000000001").is_probable_prime(20));
+    }
+}
@@ -0,0 +1,301 @@
+//! BIP-340 Schnorr signatures on [`crate::secp256k1`]
+//!
+//! Public keys are x-only, 32 bytes standing for the point with that x and
+//! an even y. Signing negates the private key and the nonce whenever their
+//! points have an odd y so the equation `[s]G = R + [e]P` still holds for
+//! the even points. Each hash is tagged with a prefix of SHA-256(tag) twice,
+//! so a hash made for one purpose is never valid for another
+
+use crate::bigint::BigUint;
+use crate::hash::Digest;
+use crate::primefield::FieldElement;
+use crate::secp256k1::{secp256k1, Secp256k1};
+use crate::sha2::Sha256;
+use crate::weierstrass::{AffinePoint, JacobianPoint};
+
+type Point = AffinePoint<FieldElement<Secp256k1>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bip340Error {
+    /// The private key is not in `[1, n - 1]`
+    InvalidPrivateKey,
+    /// The public key is not the x-coordinate of a curve point
+    InvalidPublicKey,
+}
+
+impl std::fmt::Display for Bip340Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::InvalidPrivateKey => "invalid private key",
+            Self::InvalidPublicKey => "invalid public key",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for Bip340Error {}
+
+fn tagged_hash(tag: &str, parts: &[&[u8]]) -> Vec<u8> {
+    let tag = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(&tag);
+    hasher.update(&tag);
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize()
+}
+
+fn scalar_bytes(scalar: &BigUint) -> Vec<u8> {
+    scalar.to_le_bytes_padded(32)
+}
+
+/// The point with x-coordinate `bytes` and an even y, `None` when the
+/// bytes are not below p or no point has that x
+fn lift_x(bytes: &[u8; 32]) -> Option<Point> {
+    let x = FieldElement::from_be_bytes(bytes)?;
+    let mut point = secp256k1().lift_x(x)?;
+    if point.y.is_odd() {
+        point.y = -point.y;
+    }
+    Some(point)
+}
+
+fn x_bytes(point: &Point) -> [u8; 32] {
+    point.x.to_be_bytes().try_into().unwrap()
+}
+
+/// e, the challenge hash of R, P and the message mod n
+fn challenge(r: &[u8; 32], public: &[u8; 32], message: &[u8]) -> BigUint {
+    let hash = tagged_hash("BIP0340/challenge", &[r, public, message]);
+    &BigUint::from_be_bytes(&hash) % &secp256k1().order
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature {
+    /// The x-coordinate of the nonce point R
+    pub r: [u8; 32],
+    /// The scalar s, big endian
+    pub s: [u8; 32],
+}
+
+impl Signature {
+    pub fn from_bytes(bytes: &[u8; 64]) -> Self {
+        Self {
+            r: bytes[..32].try_into().unwrap(),
+            s: bytes[32..].try_into().unwrap(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&self.r);
+        bytes[32..].copy_from_slice(&self.s);
+        bytes
+    }
+}
+
+pub struct SigningKey {
+    secret: [u8; 32],
+    /// The secret, negated if its point has an odd y
+    d: BigUint,
+    verifying_key: VerifyingKey,
+}
+
+impl SigningKey {
+    pub fn from_bytes(secret: &[u8; 32]) -> Result<Self, Bip340Error> {
+        let curve = secp256k1();
+        let d = BigUint::from_be_bytes(secret);
+        if d.is_zero() || d >= curve.order {
+            return Err(Bip340Error::InvalidPrivateKey);
+        }
+        let mut point = curve
+            .mul_base(&scalar_bytes(&d))
+            .to_affine()
+            .expect("d is below the order");
+        let d = if point.y.is_odd() {
+            point.y = -point.y;
+            &curve.order - &d
+        } else {
+            d
+        };
+        Ok(Self {
+            secret: *secret,
+            d,
+            verifying_key: VerifyingKey {
+                bytes: x_bytes(&point),
+                point,
+            },
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.secret
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.verifying_key
+    }
+
+    /// Signs `message` of any length. The nonce is a hash of the key, the
+    /// message and `aux_rand`, fresh randomness that protects against side
+    /// channels but may be all zeros without weakening the signature
+    pub fn sign(&self, message: &[u8], aux_rand: &[u8; 32]) -> Signature {
+        let curve = secp256k1();
+        let n = &curve.order;
+        let mask = tagged_hash("BIP0340/aux", &[aux_rand]);
+        let t: Vec<u8> = self
+            .d
+            .to_be_bytes_padded(32)
+            .iter()
+            .zip(&mask)
+            .map(|(a, b)| a ^ b)
+            .collect();
+        let public = &self.verifying_key.bytes;
+        let nonce = tagged_hash("BIP0340/nonce", &[&t, public, message]);
+        let k = &BigUint::from_be_bytes(&nonce) % n;
+
+        let big_r = curve
+            .mul_base(&scalar_bytes(&k))
+            .to_affine()
+            .expect("k is zero with negligible probability");
+        let k = if big_r.y.is_odd() { n - &k } else { k };
+        let r = x_bytes(&big_r);
+        let e = challenge(&r, public, message);
+        let s = k.add_mod(&e.mul_mod(&self.d, n), n);
+        Signature {
+            r,
+            s: s.to_be_bytes_padded(32).try_into().unwrap(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    bytes: [u8; 32],
+    point: Point,
+}
+
+impl VerifyingKey {
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, Bip340Error> {
+        let point = lift_x(bytes).ok_or(Bip340Error::InvalidPublicKey)?;
+        Ok(Self {
+            bytes: *bytes,
+            point,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.bytes
+    }
+
+    /// Accepts when `R = [s]G - [e]P` is a point with an even y and the
+    /// signature's x-coordinate
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+        let curve = secp256k1();
+        let n = &curve.order;
+        let Some(r) = FieldElement::<Secp256k1>::from_be_bytes(&signature.r)
+        else {
+            return false;
+        };
+        let s = BigUint::from_be_bytes(&signature.s);
+        if &s >= n {
+            return false;
+        }
+        let e = challenge(&signature.r, &self.bytes, message);
+        let minus_e = BigUint::zero().sub_mod(&e, n);
+        let sum = curve.add(
+            &curve.mul_base(&scalar_bytes(&s)),
+            &curve.scalar_mult(
+                &scalar_bytes(&minus_e),
+                &JacobianPoint::from_affine(&self.point),
+            ),
+        );
+        match sum.to_affine() {
+            Some(point) => !point.y.is_odd() && point.x == r,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hex;
+
+    /// The test-vectors.csv of the BIP, signing cases 0 to 3 and 15 to 18
+    /// and verification only cases 4 to 14
+    const VECTORS: &str = include_str!("../test_vectors/bip340_vectors.csv");
+
+    #[test]
+    fn test_bip340_vectors() {
+        let mut count = 0;
+        for line in VECTORS.lines().skip(1) {
+            let mut fields = line.split(',');
+            let mut next = || fields.next().unwrap();
+            let (index, secret, public) = (next(), next(), next());
+            let (aux_rand, message, signature) = (next(), next(), next());
+            let result = next();
+            let public: [u8; 32] = hex(public).try_into().unwrap();
+            let message = hex(message);
+            let signature =
+                Signature::from_bytes(&hex(signature).try_into().unwrap());
+
+            if !secret.is_empty() {
+                let key =
+                    SigningKey::from_bytes(&hex(secret).try_into().unwrap())
+                        .unwrap();
+                assert_eq!(key.verifying_key().to_bytes(), public, "{index}");
+                let aux_rand = hex(aux_rand).try_into().unwrap();
+                assert_eq!(key.sign(&message, &aux_rand), signature, "{index}");
+            }
+            let valid = match VerifyingKey::from_bytes(&public) {
+                Ok(key) => key.verify(&message, &signature),
+                Err(_) => false,
+            };
+            assert_eq!(valid, result == "TRUE", "{index}");
+            count += 1;
+        }
+        assert_eq!(count, 19);
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        assert!(matches!(
+            SigningKey::from_bytes(&[0; 32]),
+            Err(Bip340Error::InvalidPrivateKey)
+        ));
+        let order: [u8; 32] =
+            secp256k1().order.to_be_bytes().try_into().unwrap();
+        assert!(matches!(
+            SigningKey::from_bytes(&order),
+            Err(Bip340Error::InvalidPrivateKey)
+        ));
+        // x = 5 has no point on the curve
+        let mut five = [0; 32];
+        five[31] = 5;
+        assert_eq!(
+            VerifyingKey::from_bytes(&five),
+            Err(Bip340Error::InvalidPublicKey)
+        );
+    }
+
+    #[test]
+    fn test_odd_key_is_negated() {
+        // [6]G has an odd y, its x-only key is shared with [n - 6]G
+        let mut six = [0; 32];
+        six[31] = 6;
+        let key = SigningKey::from_bytes(&six).unwrap();
+        let n = secp256k1().order;
+        let negated: [u8; 32] = (&n - &BigUint::from(6u64))
+            .to_be_bytes()
+            .try_into()
+            .unwrap();
+        let other = SigningKey::from_bytes(&negated).unwrap();
+        assert_eq!(key.verifying_key(), other.verifying_key());
+
+        let signature = other.sign(b"message", &[7; 32]);
+        assert!(key.verifying_key().verify(b"message", &signature));
+        assert!(!key.verifying_key().verify(b"massage", &signature));
+    }
+}
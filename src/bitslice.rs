@@ -0,0 +1,915 @@
+//! Bitsliced DES. Bit `j` of every word belongs to block `j`, so one pass
+//! of boolean operations runs 64 blocks through DES at once, or 128 with
+//! `u128`. Nothing indexes a table with data, which also makes it constant
+//! time, and the key schedule is the one of [`Des`]
+//!
+//! Each S-box is a circuit of 62 to 84 gates. They were found by Shannon
+//! expansion of the tables over the best order of the six input bits,
+//! reusing any earlier gate that already computes a needed function, and
+//! `test_circuits` checks them against the tables
+
+use crate::des::{Des, E, FP, IP, P};
+use crate::feistel::Feistel;
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+/// A word used as parallel one bit lanes, one per block
+pub trait Lanes:
+    Copy
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+{
+    const COUNT: usize;
+
+    /// `bit`, which is 0 or 1, in every lane
+    fn splat(bit: u64) -> Self;
+
+    /// `bits` in lanes `64 * index` to `64 * index + 63` and zeros elsewhere
+    fn from_chunk(bits: u64, index: usize) -> Self;
+
+    /// Lanes `64 * index` to `64 * index + 63`
+    fn chunk(self, index: usize) -> u64;
+}
+
+macro_rules! impl_lanes {
+    ($ty:ty) => {
+        impl Lanes for $ty {
+            const COUNT: usize = <$ty>::BITS as usize;
+
+            fn splat(bit: u64) -> Self {
+                (0 as $ty).wrapping_sub(bit as $ty)
+            }
+
+            fn from_chunk(bits: u64, index: usize) -> Self {
+                (bits as $ty) << (64 * index)
+            }
+
+            fn chunk(self, index: usize) -> u64 {
+                (self >> (64 * index)) as u64
+            }
+        }
+    };
+}
+
+impl_lanes!(u64);
+impl_lanes!(u128);
+
+/// Half of each block, word `i` holding bit `i + 1` in the numbering of
+/// FIPS 46-3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlicedHalf<L>([L; 32]);
+
+impl<L: Lanes> BitXor for SlicedHalf<L> {
+    type Output = Self;
+
+    fn bitxor(self, rhs: Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] ^ rhs.0[i]))
+    }
+}
+
+/// DES on [`Lanes::COUNT`] blocks at a time, all under the same key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitslicedDes<L> {
+    subkeys: [u64; 16],
+    lanes: std::marker::PhantomData<L>,
+}
+
+impl<L: Lanes> BitslicedDes<L> {
+    /// The key is as for [`Des::new`], parity bits ignored
+    pub fn new(key: &[u8; 8]) -> Self {
+        Self::from(&Des::new(key))
+    }
+
+    /// Encrypts the blocks in place. Any number of blocks is accepted, the
+    /// last group simply leaves some lanes unused
+    pub fn encrypt_blocks(&self, blocks: &mut [[u8; 8]]) {
+        for group in blocks.chunks_mut(L::COUNT) {
+            let (left, right) = slice(group);
+            unslice(self.encrypt_halves(left, right), group);
+        }
+    }
+
+    pub fn decrypt_blocks(&self, blocks: &mut [[u8; 8]]) {
+        for group in blocks.chunks_mut(L::COUNT) {
+            let (left, right) = slice(group);
+            unslice(self.decrypt_halves(left, right), group);
+        }
+    }
+}
+
+impl<L: Lanes> From<&Des> for BitslicedDes<L> {
+    fn from(des: &Des) -> Self {
+        Self {
+            subkeys: des.subkeys,
+            lanes: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<L: Lanes> Feistel for BitslicedDes<L> {
+    type Half = SlicedHalf<L>;
+    type RoundKey = u64;
+
+    fn rounds(&self) -> usize {
+        16
+    }
+
+    fn round_key(&self, round: usize) -> u64 {
+        self.subkeys[round]
+    }
+
+    /// `P(S(E(R) ^ K))` with E and P being only a choice of words
+    fn round(&self, right: SlicedHalf<L>, subkey: &u64) -> SlicedHalf<L> {
+        let circuits: [fn([L; 6]) -> [L; 4]; 8] =
+            [s1, s2, s3, s4, s5, s6, s7, s8];
+        let mut substituted = [L::splat(0); 32];
+        for (i, circuit) in circuits.iter().enumerate() {
+            let input = std::array::from_fn(|j| {
+                let bit = 6 * i + j;
+                let key = L::splat((subkey >> (47 - bit)) & 1);
+                right.0[E[bit] as usize - 1] ^ key
+            });
+            substituted[4 * i..4 * i + 4].copy_from_slice(&circuit(input));
+        }
+        SlicedHalf(std::array::from_fn(|i| substituted[P[i] as usize - 1]))
+    }
+}
+
+/// Transposes the block in place, with bit 63 as column 0. Each step swaps
+/// the off-diagonal quarters of ever smaller squares
+fn transpose(rows: &mut [u64; 64]) {
+    let mut width = 32;
+    let mut mask = 0x0000_0000_FFFF_FFFFu64;
+    while width != 0 {
+        for k in (0..64).filter(|k| k & width == 0) {
+            let swapped = (rows[k] ^ (rows[k + width] >> width)) & mask;
+            rows[k] ^= swapped;
+            rows[k + width] ^= swapped << width;
+        }
+        width /= 2;
+        mask ^= mask << width;
+    }
+}
+
+/// Transposes the blocks into words, applying the initial permutation.
+/// Block `lane` of each chunk of 64 goes in row `63 - lane`, so that its
+/// bits land on bit `lane` of the columns
+fn slice<L: Lanes>(blocks: &[[u8; 8]]) -> (SlicedHalf<L>, SlicedHalf<L>) {
+    let mut words = [L::splat(0); 64];
+    for (index, chunk) in blocks.chunks(64).enumerate() {
+        let mut rows = [0; 64];
+        for (lane, block) in chunk.iter().enumerate() {
+            rows[63 - lane] = u64::from_be_bytes(*block);
+        }
+        transpose(&mut rows);
+        for (word, &bit) in words.iter_mut().zip(&IP) {
+            *word = *word | L::from_chunk(rows[bit as usize - 1], index);
+        }
+    }
+    let (left, right) = words.split_at(32);
+    (
+        SlicedHalf(left.try_into().unwrap()),
+        SlicedHalf(right.try_into().unwrap()),
+    )
+}
+
+/// Transposes back with the final permutation, filling as many blocks as
+/// there are
+fn unslice<L: Lanes>(
+    (left, right): (SlicedHalf<L>, SlicedHalf<L>),
+    blocks: &mut [[u8; 8]],
+) {
+    let words = |i: usize| match i {
+        0..32 => left.0[i],
+        _ => right.0[i - 32],
+    };
+    for (index, chunk) in blocks.chunks_mut(64).enumerate() {
+        let mut rows: [u64; 64] =
+            std::array::from_fn(|i| words(FP[i] as usize - 1).chunk(index));
+        transpose(&mut rows);
+        for (lane, block) in chunk.iter_mut().enumerate() {
+            *block = rows[63 - lane].to_be_bytes();
+        }
+    }
+}
+
+fn s1<L: Lanes>([x1, x2, x3, x4, x5, x6]: [L; 6]) -> [L; 4] {
+    let t1 = !x5;
+    let t2 = !x4;
+    let t3 = t1 | t2;
+    let t4 = t3 & x6;
+    let t5 = t1 ^ t4;
+    let t6 = t5 ^ x2;
+    let t7 = x4 ^ t1;
+    let t8 = x4 & x6;
+    let t9 = t7 ^ t8;
+    let t10 = t2 ^ t3;
+    let t11 = !t7;
+    let t12 = t11 & x6;
+    let t13 = t10 ^ t12;
+    let t14 = t13 & x2;
+    let t15 = t9 ^ t14;
+    let t16 = t15 & x1;
+    let t17 = t6 ^ t16;
+    let t18 = t2 | t9;
+    let t19 = t2 & x2;
+    let t20 = t18 ^ t19;
+    let t21 = t1 ^ t18;
+    let t22 = t2 ^ t12;
+    let t23 = t22 & x2;
+    let t24 = t21 ^ t23;
+    let t25 = t24 & x1;
+    let t26 = t20 ^ t25;
+    let t27 = t26 & x3;
+    let t28 = t17 ^ t27;
+    let t29 = t7 & x6;
+    let t30 = t3 ^ t29;
+    let t31 = x6 ^ t18;
+    let t32 = t31 & x2;
+    let t33 = t30 ^ t32;
+    let t34 = t9 ^ t31;
+    let t35 = t5 & x2;
+    let t36 = t34 ^ t35;
+    let t37 = t36 & x1;
+    let t38 = t33 ^ t37;
+    let t39 = t5 ^ t29;
+    let t40 = x6 & x2;
+    let t41 = t39 ^ t40;
+    let t42 = t2 & t22;
+    let t43 = t7 ^ t29;
+    let t44 = t43 & x2;
+    let t45 = t42 ^ t44;
+    let t46 = t45 & x1;
+    let t47 = t41 ^ t46;
+    let t48 = t47 & x3;
+    let t49 = t38 ^ t48;
+    let t50 = t5 ^ t10;
+    let t51 = t10 | t34;
+    let t52 = t51 & x2;
+    let t53 = t50 ^ t52;
+    let t54 = x6 | t1;
+    let t55 = x5 | t31;
+    let t56 = t55 & x2;
+    let t57 = t54 ^ t56;
+    let t58 = t57 & x1;
+    let t59 = t53 ^ t58;
+    let t60 = t3 & t51;
+    let t61 = t60 | x2;
+    let t62 = t30 ^ t55;
+    let t63 = t62 ^ t44;
+    let t64 = t63 & x1;
+    let t65 = t61 ^ t64;
+    let t66 = t65 & x3;
+    let t67 = t59 ^ t66;
+    let t68 = t7 ^ t54;
+    let t69 = t11 ^ t31;
+    let t70 = t69 & x2;
+    let t71 = t68 ^ t70;
+    let t72 = t3 ^ t43;
+    let t73 = t3 ^ t54;
+    let t74 = t73 & x2;
+    let t75 = t72 ^ t74;
+    let t76 = t75 & x1;
+    let t77 = t71 ^ t76;
+    let t78 = x5 | t40;
+    let t79 = t1 & t9;
+    let t80 = t79 ^ t23;
+    let t81 = t80 & x1;
+    let t82 = t78 ^ t81;
+    let t83 = t82 & x3;
+    let t84 = t77 ^ t83;
+    [t28, t49, t67, t84]
+}
+
+fn s2<L: Lanes>([x1, x2, x3, x4, x5, x6]: [L; 6]) -> [L; 4] {
+    let t1 = !x6;
+    let t2 = t1 ^ x1;
+    let t3 = t2 ^ x5;
+    let t4 = t3 ^ x2;
+    let t5 = !x5;
+    let t6 = x6 | t2;
+    let t7 = t6 | t5;
+    let t8 = t7 & x2;
+    let t9 = x6 ^ t8;
+    let t10 = t9 & x3;
+    let t11 = t4 ^ t10;
+    let t12 = t1 | t5;
+    let t13 = x6 ^ t7;
+    let t14 = t13 & x2;
+    let t15 = t12 ^ t14;
+    let t16 = !t12;
+    let t17 = t16 & x3;
+    let t18 = t15 ^ t17;
+    let t19 = t18 & x4;
+    let t20 = t11 ^ t19;
+    let t21 = x1 & x6;
+    let t22 = t21 & x5;
+    let t23 = t6 ^ t22;
+    let t24 = t2 ^ t7;
+    let t25 = t24 & x2;
+    let t26 = t23 ^ t25;
+    let t27 = x6 ^ t6;
+    let t28 = t6 & x5;
+    let t29 = t27 ^ t28;
+    let t30 = x1 ^ t27;
+    let t31 = t30 & x5;
+    let t32 = t21 ^ t31;
+    let t33 = t32 & x2;
+    let t34 = t29 ^ t33;
+    let t35 = t34 & x3;
+    let t36 = t26 ^ t35;
+    let t37 = t5 ^ t31;
+    let t38 = t22 ^ t32;
+    let t39 = t38 & x2;
+    let t40 = t37 ^ t39;
+    let t41 = t40 & x4;
+    let t42 = t36 ^ t41;
+    let t43 = t3 ^ t22;
+    let t44 = t2 ^ t23;
+    let t45 = t44 & x2;
+    let t46 = t43 ^ t45;
+    let t47 = t15 | t24;
+    let t48 = t47 & x3;
+    let t49 = t46 ^ t48;
+    let t50 = x2 | t28;
+    let t51 = t50 & x4;
+    let t52 = t49 ^ t51;
+    let t53 = x6 ^ t43;
+    let t54 = t2 | t53;
+    let t55 = t54 & x2;
+    let t56 = t53 ^ t55;
+    let t57 = t7 ^ t53;
+    let t58 = t57 ^ t25;
+    let t59 = t58 & x3;
+    let t60 = t56 ^ t59;
+    let t61 = t7 ^ t22;
+    let t62 = t13 ^ t54;
+    let t63 = t62 & x2;
+    let t64 = t61 ^ t63;
+    let t65 = t22 ^ t29;
+    let t66 = x1 & x2;
+    let t67 = t65 ^ t66;
+    let t68 = t67 & x3;
+    let t69 = t64 ^ t68;
+    let t70 = t69 & x4;
+    let t71 = t60 ^ t70;
+    [t52, t20, t71, t42]
+}
+
+fn s3<L: Lanes>([x1, x2, x3, x4, x5, x6]: [L; 6]) -> [L; 4] {
+    let t1 = !x5;
+    let t2 = t1 ^ x2;
+    let t3 = x5 | x6;
+    let t4 = x6 | t1;
+    let t5 = t4 & x2;
+    let t6 = t3 ^ t5;
+    let t7 = t6 & x4;
+    let t8 = t2 ^ t7;
+    let t9 = t2 ^ t5;
+    let t10 = x5 ^ t4;
+    let t11 = t10 ^ x2;
+    let t12 = t11 & x4;
+    let t13 = t9 ^ t12;
+    let t14 = t13 & x3;
+    let t15 = t8 ^ t14;
+    let t16 = x2 ^ x6;
+    let t17 = !t6;
+    let t18 = t17 & x4;
+    let t19 = t16 ^ t18;
+    let t20 = t11 ^ t12;
+    let t21 = t20 & x3;
+    let t22 = t19 ^ t21;
+    let t23 = t22 & x1;
+    let t24 = t15 ^ t23;
+    let t25 = t3 & t16;
+    let t26 = !x6;
+    let t27 = t26 & x2;
+    let t28 = t3 ^ t27;
+    let t29 = t28 & x4;
+    let t30 = t25 ^ t29;
+    let t31 = t9 ^ t27;
+    let t32 = x2 & x4;
+    let t33 = t31 ^ t32;
+    let t34 = t33 & x3;
+    let t35 = t30 ^ t34;
+    let t36 = x5 ^ t31;
+    let t37 = !t11;
+    let t38 = t37 & x4;
+    let t39 = t36 ^ t38;
+    let t40 = t39 | x3;
+    let t41 = t40 & x1;
+    let t42 = t35 ^ t41;
+    let t43 = t6 ^ t10;
+    let t44 = x5 | t17;
+    let t45 = t44 & x4;
+    let t46 = t43 ^ t45;
+    let t47 = x4 | t28;
+    let t48 = t47 & x3;
+    let t49 = t46 ^ t48;
+    let t50 = !t25;
+    let t51 = t2 & t44;
+    let t52 = t51 & x4;
+    let t53 = t50 ^ t52;
+    let t54 = !t4;
+    let t55 = x5 ^ t28;
+    let t56 = t55 & x4;
+    let t57 = t54 ^ t56;
+    let t58 = t57 & x3;
+    let t59 = t53 ^ t58;
+    let t60 = t59 & x1;
+    let t61 = t49 ^ t60;
+    let t62 = t1 & x4;
+    let t63 = t16 ^ t62;
+    let t64 = x5 & x3;
+    let t65 = t63 ^ t64;
+    let t66 = x6 ^ t31;
+    let t67 = x5 ^ x6;
+    let t68 = t67 & x4;
+    let t69 = t66 ^ t68;
+    let t70 = t17 | t31;
+    let t71 = x2 ^ t27;
+    let t72 = t71 & x4;
+    let t73 = t70 ^ t72;
+    let t74 = t73 & x3;
+    let t75 = t69 ^ t74;
+    let t76 = t75 & x1;
+    let t77 = t65 ^ t76;
+    [t24, t42, t61, t77]
+}
+
+fn s4<L: Lanes>([x1, x2, x3, x4, x5, x6]: [L; 6]) -> [L; 4] {
+    let t1 = !x3;
+    let t2 = x5 | t1;
+    let t3 = !x5;
+    let t4 = t3 | x3;
+    let t5 = t4 & x1;
+    let t6 = t2 ^ t5;
+    let t7 = x5 & x4;
+    let t8 = t6 ^ t7;
+    let t9 = x3 ^ t3;
+    let t10 = t9 & x1;
+    let t11 = x3 ^ t10;
+    let t12 = t11 & x4;
+    let t13 = t1 ^ t12;
+    let t14 = t13 & x2;
+    let t15 = t8 ^ t14;
+    let t16 = !t9;
+    let t17 = !t4;
+    let t18 = t17 & x1;
+    let t19 = t16 ^ t18;
+    let t20 = t2 & x1;
+    let t21 = t3 ^ t20;
+    let t22 = t21 & x4;
+    let t23 = t19 ^ t22;
+    let t24 = t2 ^ t19;
+    let t25 = t16 & x4;
+    let t26 = t24 ^ t25;
+    let t27 = t26 & x2;
+    let t28 = t23 ^ t27;
+    let t29 = t28 & x6;
+    let t30 = t15 ^ t29;
+    let t31 = t1 ^ t20;
+    let t32 = t3 & x4;
+    let t33 = t31 ^ t32;
+    let t34 = x5 ^ t11;
+    let t35 = x3 ^ t34;
+    let t36 = t35 & x4;
+    let t37 = t34 ^ t36;
+    let t38 = t37 & x2;
+    let t39 = t33 ^ t38;
+    let t40 = x3 ^ t6;
+    let t41 = t40 & x4;
+    let t42 = t35 ^ t41;
+    let t43 = t5 | t9;
+    let t44 = t43 ^ t25;
+    let t45 = t44 & x2;
+    let t46 = t42 ^ t45;
+    let t47 = t46 & x6;
+    let t48 = t39 ^ t47;
+    let t49 = x1 ^ t17;
+    let t50 = !t20;
+    let t51 = t50 & x4;
+    let t52 = t49 ^ t51;
+    let t53 = t13 ^ t26;
+    let t54 = t53 & x2;
+    let t55 = t52 ^ t54;
+    let t56 = !t28;
+    let t57 = t56 & x6;
+    let t58 = t55 ^ t57;
+    let t59 = t39 ^ t46;
+    let t60 = !t46;
+    let t61 = t60 & x6;
+    let t62 = t59 ^ t61;
+    [t58, t30, t62, t48]
+}
+
+fn s5<L: Lanes>([x1, x2, x3, x4, x5, x6]: [L; 6]) -> [L; 4] {
+    let t1 = x3 | x6;
+    let t2 = !x3;
+    let t3 = !x6;
+    let t4 = t3 | t2;
+    let t5 = t4 & x4;
+    let t6 = t1 ^ t5;
+    let t7 = !t4;
+    let t8 = t7 | x4;
+    let t9 = t8 & x2;
+    let t10 = t6 ^ t9;
+    let t11 = x4 | t4;
+    let t12 = t11 & x5;
+    let t13 = t10 ^ t12;
+    let t14 = !x2;
+    let t15 = !t1;
+    let t16 = t15 | x4;
+    let t17 = t16 | t14;
+    let t18 = x3 ^ t3;
+    let t19 = t18 & x4;
+    let t20 = x6 ^ t19;
+    let t21 = t20 & x5;
+    let t22 = t17 ^ t21;
+    let t23 = t22 & x1;
+    let t24 = t13 ^ t23;
+    let t25 = t2 ^ t11;
+    let t26 = x4 | x6;
+    let t27 = t26 & x2;
+    let t28 = t25 ^ t27;
+    let t29 = x3 ^ t4;
+    let t30 = t29 & x4;
+    let t31 = t1 ^ t30;
+    let t32 = x6 & x4;
+    let t33 = t18 ^ t32;
+    let t34 = t33 & x2;
+    let t35 = t31 ^ t34;
+    let t36 = t35 & x5;
+    let t37 = t28 ^ t36;
+    let t38 = t6 | t34;
+    let t39 = x4 ^ t15;
+    let t40 = t39 & x2;
+    let t41 = t30 ^ t40;
+    let t42 = t41 & x5;
+    let t43 = t38 ^ t42;
+    let t44 = t43 & x1;
+    let t45 = t37 ^ t44;
+    let t46 = t2 ^ t16;
+    let t47 = t3 & x4;
+    let t48 = t4 ^ t47;
+    let t49 = t48 & x2;
+    let t50 = t46 ^ t49;
+    let t51 = t3 ^ t30;
+    let t52 = x4 ^ t7;
+    let t53 = t52 & x2;
+    let t54 = t51 ^ t53;
+    let t55 = t54 & x5;
+    let t56 = t50 ^ t55;
+    let t57 = t1 & t51;
+    let t58 = t7 ^ t19;
+    let t59 = t58 & x2;
+    let t60 = t57 ^ t59;
+    let t61 = t8 ^ t51;
+    let t62 = t61 ^ t27;
+    let t63 = t62 & x5;
+    let t64 = t60 ^ t63;
+    let t65 = t64 & x1;
+    let t66 = t56 ^ t65;
+    let t67 = t11 ^ t19;
+    let t68 = t1 ^ t16;
+    let t69 = t68 & x2;
+    let t70 = t67 ^ t69;
+    let t71 = t18 ^ t41;
+    let t72 = t71 & x5;
+    let t73 = t70 ^ t72;
+    let t74 = !t31;
+    let t75 = t2 ^ t51;
+    let t76 = t75 & x2;
+    let t77 = t74 ^ t76;
+    let t78 = x4 ^ t48;
+    let t79 = t78 & x2;
+    let t80 = t75 ^ t79;
+    let t81 = t80 & x5;
+    let t82 = t77 ^ t81;
+    let t83 = t82 & x1;
+    let t84 = t73 ^ t83;
+    [t66, t24, t84, t45]
+}
+
+fn s6<L: Lanes>([x1, x2, x3, x4, x5, x6]: [L; 6]) -> [L; 4] {
+    let t1 = !x1;
+    let t2 = !x3;
+    let t3 = t2 | t1;
+    let t4 = x1 ^ t3;
+    let t5 = t4 & x5;
+    let t6 = t3 ^ t5;
+    let t7 = x3 | x5;
+    let t8 = t7 & x4;
+    let t9 = t6 ^ t8;
+    let t10 = x1 | t7;
+    let t11 = !x5;
+    let t12 = x1 ^ t2;
+    let t13 = t12 & t11;
+    let t14 = t13 & x4;
+    let t15 = t10 ^ t14;
+    let t16 = t15 & x6;
+    let t17 = t9 ^ t16;
+    let t18 = x1 & t13;
+    let t19 = t2 ^ t4;
+    let t20 = x1 & x5;
+    let t21 = t19 ^ t20;
+    let t22 = t21 & x4;
+    let t23 = t18 ^ t22;
+    let t24 = t23 & x6;
+    let t25 = t2 ^ t24;
+    let t26 = t25 & x2;
+    let t27 = t17 ^ t26;
+    let t28 = t4 ^ t7;
+    let t29 = !t19;
+    let t30 = t29 | t11;
+    let t31 = t30 & x4;
+    let t32 = t28 ^ t31;
+    let t33 = !t18;
+    let t34 = t20 & x4;
+    let t35 = t33 ^ t34;
+    let t36 = t35 & x6;
+    let t37 = t32 ^ t36;
+    let t38 = x4 ^ t35;
+    let t39 = t3 ^ t11;
+    let t40 = t39 & x4;
+    let t41 = t18 ^ t40;
+    let t42 = t41 & x6;
+    let t43 = t38 ^ t42;
+    let t44 = t43 & x2;
+    let t45 = t37 ^ t44;
+    let t46 = t3 ^ t30;
+    let t47 = t46 ^ x4;
+    let t48 = x1 ^ t33;
+    let t49 = x5 ^ t20;
+    let t50 = t49 & x4;
+    let t51 = t48 ^ t50;
+    let t52 = t51 & x6;
+    let t53 = t47 ^ t52;
+    let t54 = t5 ^ t10;
+    let t55 = t54 ^ t50;
+    let t56 = t5 ^ t20;
+    let t57 = t56 ^ t34;
+    let t58 = t57 & x6;
+    let t59 = t55 ^ t58;
+    let t60 = t59 & x2;
+    let t61 = t53 ^ t60;
+    let t62 = t13 ^ t30;
+    let t63 = x3 ^ t46;
+    let t64 = t63 & x4;
+    let t65 = t62 ^ t64;
+    let t66 = x1 | t8;
+    let t67 = t66 & x6;
+    let t68 = t65 ^ t67;
+    let t69 = x3 | x4;
+    let t70 = !t4;
+    let t71 = t21 ^ t39;
+    let t72 = t71 & x4;
+    let t73 = t70 ^ t72;
+    let t74 = t73 & x6;
+    let t75 = t69 ^ t74;
+    let t76 = t75 & x2;
+    let t77 = t68 ^ t76;
+    [t27, t45, t61, t77]
+}
+
+fn s7<L: Lanes>([x1, x2, x3, x4, x5, x6]: [L; 6]) -> [L; 4] {
+    let t1 = x2 ^ x4;
+    let t2 = !x4;
+    let t3 = t2 | x2;
+    let t4 = t3 & x5;
+    let t5 = t1 ^ t4;
+    let t6 = t5 ^ x3;
+    let t7 = x4 & x5;
+    let t8 = t3 ^ t7;
+    let t9 = t8 & x3;
+    let t10 = t4 ^ t9;
+    let t11 = t10 & x6;
+    let t12 = t6 ^ t11;
+    let t13 = t2 ^ t3;
+    let t14 = t13 | x5;
+    let t15 = !x5;
+    let t16 = !x2;
+    let t17 = t16 & t15;
+    let t18 = t17 & x3;
+    let t19 = t14 ^ t18;
+    let t20 = t8 ^ t17;
+    let t21 = t20 & x3;
+    let t22 = t15 ^ t21;
+    let t23 = t22 & x6;
+    let t24 = t19 ^ t23;
+    let t25 = t24 & x1;
+    let t26 = t12 ^ t25;
+    let t27 = x5 ^ t13;
+    let t28 = t1 ^ t8;
+    let t29 = t28 & x3;
+    let t30 = t27 ^ t29;
+    let t31 = !x3;
+    let t32 = t28 | t31;
+    let t33 = t32 & x6;
+    let t34 = t30 ^ t33;
+    let t35 = t1 ^ t14;
+    let t36 = x4 ^ t20;
+    let t37 = t36 & x3;
+    let t38 = t35 ^ t37;
+    let t39 = t7 ^ t15;
+    let t40 = t39 ^ t29;
+    let t41 = t40 & x6;
+    let t42 = t38 ^ t41;
+    let t43 = t42 & x1;
+    let t44 = t34 ^ t43;
+    let t45 = t16 ^ t39;
+    let t46 = !t1;
+    let t47 = t46 ^ t7;
+    let t48 = t47 & x3;
+    let t49 = t45 ^ t48;
+    let t50 = t14 ^ t15;
+    let t51 = t50 & x6;
+    let t52 = t49 ^ t51;
+    let t53 = !x6;
+    let t54 = t45 | t47;
+    let t55 = !t47;
+    let t56 = t55 & x3;
+    let t57 = t54 ^ t56;
+    let t58 = t57 | t53;
+    let t59 = t58 & x1;
+    let t60 = t52 ^ t59;
+    let t61 = t8 ^ t45;
+    let t62 = x2 & x3;
+    let t63 = t61 ^ t62;
+    let t64 = t4 ^ t45;
+    let t65 = t7 & x3;
+    let t66 = t64 ^ t65;
+    let t67 = t66 & x6;
+    let t68 = t63 ^ t67;
+    let t69 = t30 ^ t63;
+    let t70 = t50 ^ t62;
+    let t71 = t70 & x6;
+    let t72 = t69 ^ t71;
+    let t73 = t72 & x1;
+    let t74 = t68 ^ t73;
+    [t44, t74, t26, t60]
+}
+
+fn s8<L: Lanes>([x1, x2, x3, x4, x5, x6]: [L; 6]) -> [L; 4] {
+    let t1 = !x5;
+    let t2 = t1 ^ x3;
+    let t3 = x3 & x4;
+    let t4 = t2 ^ t3;
+    let t5 = t2 & x4;
+    let t6 = x5 ^ t5;
+    let t7 = t6 & x2;
+    let t8 = t4 ^ t7;
+    let t9 = !t5;
+    let t10 = !x3;
+    let t11 = t10 & x4;
+    let t12 = t1 ^ t11;
+    let t13 = t12 & x2;
+    let t14 = t9 ^ t13;
+    let t15 = t14 & x6;
+    let t16 = t8 ^ t15;
+    let t17 = t1 | t10;
+    let t18 = !t2;
+    let t19 = t18 & x4;
+    let t20 = t17 ^ t19;
+    let t21 = t3 & x2;
+    let t22 = t20 ^ t21;
+    let t23 = t2 ^ t19;
+    let t24 = t10 ^ t17;
+    let t25 = t24 ^ t5;
+    let t26 = t25 & x2;
+    let t27 = t23 ^ t26;
+    let t28 = t27 & x6;
+    let t29 = t22 ^ t28;
+    let t30 = t29 & x1;
+    let t31 = t16 ^ t30;
+    let t32 = t6 ^ t20;
+    let t33 = t4 ^ t5;
+    let t34 = t33 & x2;
+    let t35 = t32 ^ t34;
+    let t36 = t35 ^ x6;
+    let t37 = t4 ^ t32;
+    let t38 = x3 ^ t6;
+    let t39 = t38 & x2;
+    let t40 = t37 ^ t39;
+    let t41 = t12 ^ t17;
+    let t42 = t41 ^ t21;
+    let t43 = t42 & x6;
+    let t44 = t40 ^ t43;
+    let t45 = t44 & x1;
+    let t46 = t36 ^ t45;
+    let t47 = t4 ^ t20;
+    let t48 = t47 ^ x2;
+    let t49 = x2 ^ t39;
+    let t50 = t49 & x6;
+    let t51 = t48 ^ t50;
+    let t52 = t27 ^ t40;
+    let t53 = t3 ^ t6;
+    let t54 = t6 ^ t41;
+    let t55 = t54 & x2;
+    let t56 = t53 ^ t55;
+    let t57 = t56 & x6;
+    let t58 = t52 ^ t57;
+    let t59 = t58 & x1;
+    let t60 = t51 ^ t59;
+    let t61 = t6 ^ t12;
+    let t62 = t61 & x2;
+    let t63 = t33 ^ t62;
+    let t64 = t5 | t41;
+    let t65 = t18 & x2;
+    let t66 = t64 ^ t65;
+    let t67 = t66 & x6;
+    let t68 = t63 ^ t67;
+    let t69 = t22 ^ t27;
+    let t70 = t41 & x2;
+    let t71 = t20 ^ t70;
+    let t72 = t71 & x6;
+    let t73 = t69 ^ t72;
+    let t74 = t73 & x1;
+    let t75 = t68 ^ t74;
+    [t31, t46, t60, t75]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::des::S_BOXES;
+
+    fn blocks(count: usize) -> Vec<[u8; 8]> {
+        (0..count as u64)
+            .map(|i| i.wrapping_mul(0x9e37_79b9_7f4a_7c15).to_be_bytes())
+            .collect()
+    }
+
+    fn cross_check<L: Lanes>(key: &[u8; 8], count: usize) {
+        let des = Des::new(key);
+        let sliced = BitslicedDes::<L>::new(key);
+        let plaintext = blocks(count);
+
+        let mut ciphertext = plaintext.clone();
+        sliced.encrypt_blocks(&mut ciphertext);
+        for (p, c) in plaintext.iter().zip(&ciphertext) {
+            assert_eq!(*c, des.encrypt_block(p));
+        }
+
+        sliced.decrypt_blocks(&mut ciphertext);
+        assert_eq!(ciphertext, plaintext);
+    }
+
+    #[test]
+    fn test_circuits() {
+        let circuits: [fn([u64; 6]) -> [u64; 4]; 8] =
+            [s1, s2, s3, s4, s5, s6, s7, s8];
+        for (circuit, sbox) in circuits.iter().zip(&S_BOXES) {
+            // lane j gets the six input bits of j, covering every input
+            let input = std::array::from_fn(|bit| {
+                (0..64).fold(0, |word, j| word | (j >> (5 - bit) & 1) << j)
+            });
+            let output = circuit(input);
+            for j in 0..64 {
+                let row = (j >> 4) & 0b10 | j & 1;
+                let column = (j >> 1) & 0b1111;
+                let entry = output
+                    .iter()
+                    .fold(0, |entry, word| entry << 1 | (word >> j) & 1);
+                assert_eq!(entry, sbox[(row * 16 + column) as usize] as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transpose() {
+        let mut rows: [u64; 64] =
+            std::array::from_fn(|i| (i as u64).wrapping_mul(0x9e37_79b9));
+        let original = rows;
+        transpose(&mut rows);
+        for (i, row) in rows.iter().enumerate() {
+            for (j, column) in original.iter().enumerate() {
+                assert_eq!(row >> (63 - j) & 1, column >> (63 - i) & 1);
+            }
+        }
+        transpose(&mut rows);
+        assert_eq!(rows, original);
+    }
+
+    #[test]
+    fn test_against_scalar_des() {
+        let key = [0x13, 0x34, 0x57, 0x79, 0x9B, 0xBC, 0xDF, 0xF1];
+        // one full group, a partial one and fewer blocks than lanes
+        cross_check::<u64>(&key, 100);
+        cross_check::<u128>(&key, 300);
+        cross_check::<u64>(&[0xFE; 8], 3);
+        cross_check::<u128>(&[0x01; 8], 0);
+    }
+
+    #[test]
+    fn test_textbook_vector() {
+        let key = [0x13, 0x34, 0x57, 0x79, 0x9B, 0xBC, 0xDF, 0xF1];
+        let mut blocks = [[0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF]; 2];
+        BitslicedDes::<u64>::new(&key).encrypt_blocks(&mut blocks);
+        assert_eq!(
+            blocks,
+            [[0x85, 0xE8, 0x13, 0x54, 0x0F, 0x0A, 0xB4, 0x05]; 2]
+        );
+    }
+}
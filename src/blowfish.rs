@@ -1,3 +1,5 @@
+use crate::feistel::Feistel;
+
 const P_ARRAY: [u32; 18] = [
     0x243f6a88, 0x85a308d3, 0x13198a2e, 0x03707344, 0xa4093822, 0x299f31d0,
     0x082efa98, 0xec4e6c89, 0x452821e6, 0x38d01377, 0xbe5466cf, 0x34e90c6c,
@@ -231,26 +233,18 @@ impl BlowFish {
         s
     }
 
+    /// Blowfish XORs `P[i]` into the left half just before `F`. Carrying
+    /// each of those XORs on into the next round's function output leaves a
+    /// plain Feistel network keyed by `P[1..=16]`, with `P[0]` and `P[17]`
+    /// as whitening
     fn encrypt(&self, lhs: &mut u32, rhs: &mut u32) {
-        for round in 0..16 {
-            *lhs ^= self.subkeys[round];
-            *rhs ^= self.f(*lhs);
-            swap(lhs, rhs);
-        }
-        swap(lhs, rhs);
-        *rhs ^= self.subkeys[16];
-        *lhs ^= self.subkeys[17];
+        let (left, right) = self.encrypt_halves(*rhs, *lhs ^ self.subkeys[0]);
+        (*lhs, *rhs) = (right ^ self.subkeys[17], left);
     }
 
     fn decrypt(&self, lhs: &mut u32, rhs: &mut u32) {
-        for round in (2..self.subkeys.len()).rev() {
-            *lhs ^= self.subkeys[round];
-            *rhs ^= self.f(*lhs);
-            swap(lhs, rhs);
-        }
-        swap(lhs, rhs);
-        *rhs ^= self.subkeys[1];
-        *lhs ^= self.subkeys[0];
+        let (left, right) = self.decrypt_halves(*rhs, *lhs ^ self.subkeys[17]);
+        (*lhs, *rhs) = (right ^ self.subkeys[0], left);
     }
 
     fn f(&self, mut lhs: u32) -> u32 {
@@ -269,11 +263,11 @@ impl BlowFish {
         let mut lhs = (block[0] as u32) << 24
             | (block[1] as u32) << 16
             | (block[2] as u32) << 8
-            | (block[3] as u32) << 0;
+            | (block[3] as u32);
         let mut rhs = (block[4] as u32) << 24
             | (block[5] as u32) << 16
             | (block[6] as u32) << 8
-            | (block[7] as u32) << 0;
+            | (block[7] as u32);
 
         self.encrypt(&mut lhs, &mut rhs);
         (lhs as u64) << 32 | rhs as u64
@@ -283,22 +277,32 @@ impl BlowFish {
         let mut lhs = (block[0] as u32) << 24
             | (block[1] as u32) << 16
             | (block[2] as u32) << 8
-            | (block[3] as u32) << 0;
+            | (block[3] as u32);
         let mut rhs = (block[4] as u32) << 24
             | (block[5] as u32) << 16
             | (block[6] as u32) << 8
-            | (block[7] as u32) << 0;
+            | (block[7] as u32);
 
         self.decrypt(&mut lhs, &mut rhs);
         (lhs as u64) << 32 | rhs as u64
     }
 }
 
-#[inline(always)]
-fn swap(lhs: &mut u32, rhs: &mut u32) {
-    let tmp = *lhs;
-    *lhs = *rhs;
-    *rhs = tmp;
+impl Feistel for BlowFish {
+    type Half = u32;
+    type RoundKey = u32;
+
+    fn rounds(&self) -> usize {
+        16
+    }
+
+    fn round_key(&self, round: usize) -> u32 {
+        self.subkeys[round + 1]
+    }
+
+    fn round(&self, right: u32, key: &u32) -> u32 {
+        self.f(right) ^ key
+    }
 }
 
 /// https://www.schneier.com/wp-content/uploads/2015/12/vectors-2.txt
@@ -335,7 +339,7 @@ mod tests {
         let key = [0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0];
 
         let bf = BlowFish::init(&key);
-        let mut ciphertext = u64::to_be_bytes(0x4ef997456198dd78);
+        let ciphertext = u64::to_be_bytes(0x4ef997456198dd78);
         let plaintext = bf.decrypt_block(&ciphertext);
         assert_eq!(plaintext, 0x00);
     }
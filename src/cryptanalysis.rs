@@ -0,0 +1,408 @@
+//! Attacks on the simplified DES, small enough to run in a test
+//!
+//! A 10 bit key falls to exhaustive search over 1024 keys. Encrypting
+//! twice under two keys should need 2^20 trials, but meeting in the middle
+//! finds the pair with about 2 * 2^10, which is why double DES was never
+//! adopted and Triple DES is the smallest useful cascade.
+//!
+//! The difference distribution and linear approximation tables describe
+//! how far a 4 to 2 bit S-box is from random, and the differential attack
+//! uses them to recover both round keys from 31 chosen plaintexts
+
+use crate::des::{
+    permutation_4, permutation_ep, permutation_inverse_ip, permutation_ip,
+    sbox_index, Sdes, SdesError, S0, S1,
+};
+use std::collections::HashMap;
+
+/// The number of simplified DES keys
+const KEYS: u16 = 1024;
+
+/// The outcome of a key search: every key consistent with the known pairs
+/// and how many single encryptions or decryptions it took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySearch<K> {
+    pub candidates: Vec<K>,
+    pub trials: usize,
+}
+
+/// Tries every key against the known plaintext and ciphertext pairs. More
+/// than one key may survive, as some keys have the same schedule and an 8
+/// bit block lets a wrong key match one pair by chance
+pub fn sdes_brute_force(pairs: &[(u8, u8)]) -> KeySearch<u16> {
+    let mut trials = 0;
+    let candidates = (0..KEYS)
+        .filter(|&key| {
+            let sdes = Sdes::schedule(key);
+            pairs.iter().all(|&(plaintext, ciphertext)| {
+                trials += 1;
+                sdes.encrypt(plaintext) == ciphertext
+            })
+        })
+        .collect();
+    KeySearch { candidates, trials }
+}
+
+/// Simplified DES twice, under `key1` then `key2`
+pub struct DoubleSdes {
+    first: Sdes,
+    second: Sdes,
+}
+
+impl DoubleSdes {
+    pub fn new(key1: u16, key2: u16) -> Result<Self, SdesError> {
+        Ok(Self {
+            first: Sdes::new(key1)?,
+            second: Sdes::new(key2)?,
+        })
+    }
+
+    pub fn encrypt(&self, bits: u8) -> u8 {
+        self.second.encrypt(self.first.encrypt(bits))
+    }
+
+    pub fn decrypt(&self, bits: u8) -> u8 {
+        self.first.decrypt(self.second.decrypt(bits))
+    }
+}
+
+/// Recovers the key pairs of [`DoubleSdes`] from known pairs. The first
+/// pair's plaintext is encrypted under every key1 and its ciphertext
+/// decrypted under every key2, and keys whose middle values meet are then
+/// checked against the remaining pairs
+pub fn double_sdes_meet_in_the_middle(
+    pairs: &[(u8, u8)],
+) -> KeySearch<(u16, u16)> {
+    let Some(&(plaintext, ciphertext)) = pairs.first() else {
+        return KeySearch {
+            candidates: Vec::new(),
+            trials: 0,
+        };
+    };
+    let mut trials = 0;
+
+    let mut middles: HashMap<u8, Vec<u16>> = HashMap::new();
+    for key1 in 0..KEYS {
+        trials += 1;
+        let middle = Sdes::schedule(key1).encrypt(plaintext);
+        middles.entry(middle).or_default().push(key1);
+    }
+
+    let mut candidates = Vec::new();
+    for key2 in 0..KEYS {
+        trials += 1;
+        let middle = Sdes::schedule(key2).decrypt(ciphertext);
+        for &key1 in middles.get(&middle).into_iter().flatten() {
+            let double = DoubleSdes {
+                first: Sdes::schedule(key1),
+                second: Sdes::schedule(key2),
+            };
+            let consistent = pairs[1..].iter().all(|&(p, c)| {
+                trials += 2;
+                double.encrypt(p) == c
+            });
+            if consistent {
+                candidates.push((key1, key2));
+            }
+        }
+    }
+    KeySearch { candidates, trials }
+}
+
+/// A 4 to 2 bit S-box as a lookup table indexed by its input
+pub type SBox = [u8; 16];
+
+/// S0 and S1 of the simplified DES, reordered so the input indexes them
+pub fn sdes_sboxes() -> [SBox; 2] {
+    [S0, S1].map(|table| {
+        std::array::from_fn(|input| table[sbox_index(input as u8)])
+    })
+}
+
+/// `table[a][b]` counts the inputs x with `S(x) ^ S(x ^ a) = b`, so it is
+/// the number of the 16 inputs that carry difference a to difference b
+pub fn difference_distribution_table(sbox: &SBox) -> [[u8; 4]; 16] {
+    let mut table = [[0; 4]; 16];
+    for (a, row) in table.iter_mut().enumerate() {
+        for x in 0..16 {
+            row[(sbox[x] ^ sbox[x ^ a]) as usize] += 1;
+        }
+    }
+    table
+}
+
+/// The inputs x with `S(x) ^ S(x ^ input) = output`, as many as the
+/// difference distribution table entry
+pub fn difference_solutions(sbox: &SBox, input: u8, output: u8) -> Vec<u8> {
+    (0..16)
+        .filter(|&x| sbox[x as usize] ^ sbox[(x ^ input) as usize] == output)
+        .collect()
+}
+
+fn parity(bits: u8) -> bool {
+    bits.count_ones() % 2 == 1
+}
+
+/// `table[a][b]` is the number of inputs x where the input bits under mask
+/// a and the output bits under mask b have the same parity, less 8. Zero
+/// means the approximation holds half the time, ±8 that it always or
+/// never does
+pub fn linear_approximation_table(sbox: &SBox) -> [[i8; 4]; 16] {
+    let mut table = [[0; 4]; 16];
+    for (a, row) in table.iter_mut().enumerate() {
+        for (b, entry) in row.iter_mut().enumerate() {
+            let agree = (0..16)
+                .filter(|&x| {
+                    parity(x & a as u8) == parity(sbox[x as usize] & b as u8)
+                })
+                .count();
+            *entry = agree as i8 - 8;
+        }
+    }
+    table
+}
+
+/// A one round differential through the simplified DES round function
+/// `P4(S(EP(R) ^ K))`: a difference in the right half going in and the
+/// difference it causes coming out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Characteristic {
+    pub input: u8,
+    pub output: u8,
+    /// Out of 256, the product of the two S-boxes' table entries. This is
+    /// the exact probability over a uniformly random round key
+    pub count: u32,
+}
+
+/// Every one round characteristic with a non-zero input difference and a
+/// non-zero probability, most likely first
+pub fn round_characteristics() -> Vec<Characteristic> {
+    let [ddt0, ddt1] = sdes_sboxes().map(|s| difference_distribution_table(&s));
+    let mut characteristics = Vec::new();
+    for input in 1..16 {
+        let expanded = permutation_ep(input);
+        let (a0, a1) = ((expanded >> 4) as usize, (expanded & 0b1111) as usize);
+        for (b0, &count0) in ddt0[a0].iter().enumerate() {
+            for (b1, &count1) in ddt1[a1].iter().enumerate() {
+                let count = count0 as u32 * count1 as u32;
+                if count > 0 {
+                    characteristics.push(Characteristic {
+                        input,
+                        output: permutation_4((b0 << 2 | b1) as u8),
+                        count,
+                    });
+                }
+            }
+        }
+    }
+    characteristics.sort_by_key(|c| std::cmp::Reverse(c.count));
+    characteristics
+}
+
+/// The S-box outputs behind a round function output, undoing P4
+fn inverse_permutation_4(bits: u8) -> u8 {
+    (0..16).find(|&x| permutation_4(x) == bits).unwrap()
+}
+
+/// The round key nibbles, high for S0 and low for S1, consistent with
+/// every pair. Each pair is the known 4 bit input to the round function
+/// for both texts and the difference of the two outputs: a key nibble
+/// survives if it puts the S-box inputs among the difference solutions
+fn round_key_candidates(pairs: &[(u8, u8, u8)]) -> Vec<u8> {
+    let sboxes = sdes_sboxes();
+    let mut nibbles = [(0..16).collect::<Vec<u8>>(), (0..16).collect()];
+    for &(x, x_prime, output_difference) in pairs {
+        let (expanded, expanded_prime) =
+            (permutation_ep(x), permutation_ep(x_prime));
+        let sbox_difference = inverse_permutation_4(output_difference);
+        for (j, candidates) in nibbles.iter_mut().enumerate() {
+            let shift = 4 - 4 * j;
+            let input = (expanded >> shift) & 0b1111;
+            let input_difference = input ^ (expanded_prime >> shift) & 0b1111;
+            let output = (sbox_difference >> (2 - 2 * j)) & 0b11;
+            let solutions =
+                difference_solutions(&sboxes[j], input_difference, output);
+            candidates.retain(|k| solutions.contains(&(input ^ k)));
+        }
+    }
+    let [high, low] = nibbles;
+    high.iter()
+        .flat_map(|h| low.iter().map(move |l| h << 4 | l))
+        .collect()
+}
+
+/// A chosen plaintext differential attack on the simplified DES, given an
+/// encryption oracle. Returns the candidate (K1, K2) pairs, in practice
+/// only the pair the key schedule produced.
+///
+/// Texts are chosen through IP so their halves after it are controlled.
+/// The ciphertext's right half after IP is the first round's left output
+/// L1, so for texts agreeing on R0 the second round sees the known inputs
+/// L1 and L1', whose output difference is the difference of the left
+/// halves. That filters K2 with the S-boxes' difference solutions. Texts
+/// agreeing on L0 give the first round's input R0 and output difference
+/// `L1 ^ L1'` the same way, filtering K1
+pub fn sdes_differential_attack(encrypt: impl Fn(u8) -> u8) -> Vec<(u8, u8)> {
+    // the cipher's state after IP for a chosen state before IP^-1
+    let query =
+        |state: u8| permutation_ip(encrypt(permutation_inverse_ip(state)));
+
+    let second_round: Vec<(u8, u8)> =
+        (0..16).map(|l0| (l0, query(l0 << 4))).collect();
+    let mut pairs = Vec::new();
+    for &(_, c) in &second_round {
+        for &(_, c_prime) in &second_round {
+            pairs.push((c & 0b1111, c_prime & 0b1111, (c ^ c_prime) >> 4));
+        }
+    }
+    let k2 = round_key_candidates(&pairs);
+
+    let first_round: Vec<(u8, u8)> =
+        (0..16).map(|r0| (r0, query(r0))).collect();
+    let mut pairs = Vec::new();
+    for &(r0, c) in &first_round {
+        for &(r0_prime, c_prime) in &first_round {
+            pairs.push((r0, r0_prime, (c ^ c_prime) & 0b1111));
+        }
+    }
+    let k1 = round_key_candidates(&pairs);
+
+    k1.iter()
+        .flat_map(|&a| k2.iter().map(move |&b| (a, b)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::des::round_function;
+
+    fn known_pairs(encrypt: impl Fn(u8) -> u8, count: u8) -> Vec<(u8, u8)> {
+        (0..count)
+            .map(|i| {
+                let plaintext = i.wrapping_mul(97).wrapping_add(13);
+                (plaintext, encrypt(plaintext))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sdes_brute_force() {
+        let key = 0b1010000010;
+        let sdes = Sdes::new(key).unwrap();
+        let pairs = known_pairs(|bits| sdes.encrypt(bits), 4);
+        let search = sdes_brute_force(&pairs);
+        assert!(search.candidates.contains(&key));
+        // every survivor is a key equivalent to the real one
+        for candidate in &search.candidates {
+            let other = Sdes::new(*candidate).unwrap();
+            for bits in 0..=255 {
+                assert_eq!(other.encrypt(bits), sdes.encrypt(bits));
+            }
+        }
+        assert!(search.trials >= 1024 && search.trials <= 4 * 1024);
+    }
+
+    #[test]
+    fn test_double_sdes() {
+        let double = DoubleSdes::new(0b1010000010, 0b0110011011).unwrap();
+        for bits in 0..=255 {
+            assert_eq!(double.decrypt(double.encrypt(bits)), bits);
+        }
+    }
+
+    #[test]
+    fn test_meet_in_the_middle() {
+        let (key1, key2) = (0b1010000010, 0b0110011011);
+        let double = DoubleSdes::new(key1, key2).unwrap();
+        let pairs = known_pairs(|bits| double.encrypt(bits), 6);
+        let search = double_sdes_meet_in_the_middle(&pairs);
+
+        assert!(search.candidates.contains(&(key1, key2)));
+        for &(a, b) in &search.candidates {
+            let other = DoubleSdes::new(a, b).unwrap();
+            for &(plaintext, ciphertext) in &pairs {
+                assert_eq!(other.encrypt(plaintext), ciphertext);
+            }
+        }
+        // far below the 2^20 key pairs of an exhaustive search
+        assert!(search.trials < 1 << 15, "{}", search.trials);
+    }
+
+    #[test]
+    fn test_meet_in_the_middle_without_pairs() {
+        let search = double_sdes_meet_in_the_middle(&[]);
+        assert!(search.candidates.is_empty());
+        assert_eq!(search.trials, 0);
+    }
+
+    #[test]
+    fn test_sdes_tables() {
+        let [s0, s1] = sdes_sboxes();
+        assert_eq!(s0, [1, 3, 0, 2, 3, 1, 2, 0, 0, 3, 2, 1, 1, 3, 3, 2]);
+        assert_eq!(s1, [0, 2, 1, 0, 2, 1, 3, 3, 3, 2, 0, 1, 1, 0, 0, 3]);
+
+        let ddt = difference_distribution_table(&s0);
+        assert_eq!(ddt[0], [16, 0, 0, 0]);
+        assert_eq!(ddt[6], [0, 2, 2, 12]);
+        assert_eq!(difference_distribution_table(&s1)[14], [10, 4, 2, 0]);
+        for row in ddt {
+            assert_eq!(row.iter().map(|&n| n as u32).sum::<u32>(), 16);
+        }
+        for a in 0..16 {
+            for b in 0..4 {
+                let solutions = difference_solutions(&s0, a, b);
+                assert_eq!(
+                    solutions.len(),
+                    ddt[a as usize][b as usize] as usize
+                );
+            }
+        }
+
+        let lat = linear_approximation_table(&s0);
+        assert_eq!(lat[0], [8, -1, -1, 0]);
+        assert_eq!(lat[15][3], -6);
+        assert_eq!(linear_approximation_table(&s1)[11][1], 6);
+    }
+
+    /// The counts agree with the round function run over every right half
+    /// and round key
+    #[test]
+    fn test_round_characteristics() {
+        let characteristics = round_characteristics();
+        let best = characteristics[0];
+        assert!(characteristics.iter().all(|c| c.count <= best.count));
+        for input in 1..16 {
+            let total: u32 = characteristics
+                .iter()
+                .filter(|c| c.input == input)
+                .map(|c| c.count)
+                .sum();
+            assert_eq!(total, 256);
+        }
+
+        for characteristic in characteristics.iter().take(5) {
+            let mut count = 0;
+            for right in 0..16u8 {
+                for key in 0..=255 {
+                    let output = round_function(right, key)
+                        ^ round_function(right ^ characteristic.input, key);
+                    if output == characteristic.output {
+                        count += 1;
+                    }
+                }
+            }
+            // 16 right halves by 256 keys, against the per key count of 256
+            assert_eq!(count, characteristic.count * 16, "{characteristic:?}");
+        }
+    }
+
+    #[test]
+    fn test_sdes_differential_attack() {
+        for key in [0b1010000010, 0b1100101001, 0b0111111101, 0] {
+            let sdes = Sdes::new(key).unwrap();
+            let subkeys = sdes_differential_attack(|bits| sdes.encrypt(bits));
+            assert_eq!(subkeys, [(sdes.key1, sdes.key2)], "{key:010b}");
+        }
+    }
+}
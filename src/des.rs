@@ -1,13 +1,50 @@
-#[derive(Debug, PartialEq)]
-struct Sdes {
-    key1: u8,
-    key2: u8,
+//! DES (FIPS 46-3), Triple DES (SP 800-67), DESX and Simplified DES, the
+//! 10 bit key, 8 bit block teaching cipher with the same structure
+
+use crate::feistel::Feistel;
+
+/// Simplified DES, whose block is a single byte
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sdes {
+    pub(crate) key1: u8,
+    pub(crate) key2: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SdesError {
+    /// The key does not fit in 10 bits
+    KeyOutOfRange,
+}
+
+impl std::fmt::Display for SdesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("S-DES key wider than 10 bits")
+    }
+}
+
+impl std::error::Error for SdesError {}
+
+/// How [`Sdes::encrypt_bytes`] chains its one byte blocks. The block is a
+/// byte, so no padding is ever needed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SdesMode {
+    /// Each byte on its own, equal bytes giving equal ciphertext
+    Ecb,
+    /// Each byte XORed with the previous ciphertext byte, the first with
+    /// `iv`, before encryption
+    Cbc { iv: u8 },
 }
 
 impl Sdes {
-    fn new(mut key: u16) -> Self {
-        assert!(key < 2048);
+    pub fn new(key: u16) -> Result<Self, SdesError> {
+        if key >= 1024 {
+            return Err(SdesError::KeyOutOfRange);
+        }
+        Ok(Self::schedule(key))
+    }
 
+    /// The key schedule, for callers that already know `key` is 10 bits
+    pub(crate) fn schedule(mut key: u16) -> Self {
         key = permutation_10(key);
 
         key = ls_1(key);
@@ -19,49 +56,114 @@ impl Sdes {
         Self { key1, key2 }
     }
 
-    fn encrypt(&self, bits: u8) -> u8 {
-        calculate(bits, self.key1, self.key2)
+    pub fn encrypt(&self, bits: u8) -> u8 {
+        let bits = permutation_ip(bits);
+        let (left, right) = self.encrypt_halves(bits >> 4, bits & 0b1111);
+        permutation_inverse_ip((left << 4) | right)
     }
 
-    fn decyrpt(&self, bits: u8) -> u8 {
-        calculate(bits, self.key2, self.key1)
+    pub fn decrypt(&self, bits: u8) -> u8 {
+        let bits = permutation_ip(bits);
+        let (left, right) = self.decrypt_halves(bits >> 4, bits & 0b1111);
+        permutation_inverse_ip((left << 4) | right)
+    }
+
+    pub fn encrypt_bytes(&self, data: &[u8], mode: SdesMode) -> Vec<u8> {
+        match mode {
+            SdesMode::Ecb => data.iter().map(|&b| self.encrypt(b)).collect(),
+            SdesMode::Cbc { iv } => data
+                .iter()
+                .scan(iv, |previous, &b| {
+                    *previous = self.encrypt(b ^ *previous);
+                    Some(*previous)
+                })
+                .collect(),
+        }
     }
-}
 
-fn calculate(mut bits: u8, first_key: u8, second_key: u8) -> u8 {
-    bits = permutation_ip(bits);
+    pub fn decrypt_bytes(&self, data: &[u8], mode: SdesMode) -> Vec<u8> {
+        match mode {
+            SdesMode::Ecb => data.iter().map(|&b| self.decrypt(b)).collect(),
+            SdesMode::Cbc { iv } => data
+                .iter()
+                .scan(iv, |previous, &b| {
+                    let plain = self.decrypt(b) ^ *previous;
+                    *previous = b;
+                    Some(plain)
+                })
+                .collect(),
+        }
+    }
 
-    bits = step(first_key, bits);
-    // reverse the nibbles
-    bits = (bits >> 4) | ((bits & 0b1111) << 4);
-    bits = step(second_key, bits);
+    /// The keys with equal round keys, for which encryption is its own
+    /// inverse. Besides all zeros and all ones there are six more, as P8
+    /// drops two bits and only the eight it keeps must survive the shifts
+    pub fn weak_keys() -> Vec<u16> {
+        (0..1024)
+            .filter(|&key| {
+                let sdes = Self::schedule(key);
+                sdes.key1 == sdes.key2
+            })
+            .collect()
+    }
 
-    bits = permutation_inverse_ip(bits);
-    bits
+    /// Pairs of keys whose round keys are each other's swapped, so that
+    /// encrypting under the first decrypts under the second. Each pair is
+    /// listed once, the smaller key first
+    pub fn semi_weak_key_pairs() -> Vec<(u16, u16)> {
+        let schedules: Vec<Self> = (0..1024).map(Self::schedule).collect();
+        let mut pairs = Vec::new();
+        for (a, first) in schedules.iter().enumerate() {
+            for (b, second) in schedules.iter().enumerate().skip(a + 1) {
+                if first.key1 != first.key2
+                    && first.key1 == second.key2
+                    && first.key2 == second.key1
+                {
+                    pairs.push((a as u16, b as u16));
+                }
+            }
+        }
+        pairs
+    }
 }
 
-fn step(key: u8, mut bits: u8) -> u8 {
-    let left = bits >> 4;
-    let right = bits & 0b1111;
+/// Two rounds on nibbles, the key schedule being the two round keys
+impl Feistel for Sdes {
+    type Half = u8;
+    type RoundKey = u8;
+
+    fn rounds(&self) -> usize {
+        2
+    }
 
-    bits = permutation_ep(right);
-    bits ^= key;
-    bits = sboxes(bits);
-    bits = permutation_4(bits);
-    bits ^= left;
-    (bits << 4) | right
+    fn round_key(&self, round: usize) -> u8 {
+        [self.key1, self.key2][round]
+    }
+
+    fn round(&self, right: u8, key: &u8) -> u8 {
+        round_function(right, *key)
+    }
 }
 
-fn sbox_index(bits: u8) -> usize {
+/// `P4(S(EP(R) ^ K))`
+pub(crate) fn round_function(right: u8, key: u8) -> u8 {
+    permutation_4(sboxes(permutation_ep(right) ^ key))
+}
+
+/// The two S-boxes, row by row. A 4 bit input picks the row from its outer
+/// bits and the column from its inner ones, see [`sbox_index`]
+pub(crate) const S0: [u8; 16] =
+    [1, 0, 3, 2, 3, 2, 1, 0, 0, 2, 1, 3, 3, 1, 3, 2];
+pub(crate) const S1: [u8; 16] =
+    [0, 1, 2, 3, 2, 0, 1, 3, 3, 0, 1, 0, 2, 1, 0, 3];
+
+pub(crate) fn sbox_index(bits: u8) -> usize {
     let row = (bits & 0b0001) | ((bits & 0b1000) >> 2);
     let col = (bits & 0b0110) >> 1;
     ((row * 4) + col) as usize
 }
 
 fn sboxes(bits: u8) -> u8 {
-    const S0: [u8; 16] = [1, 0, 3, 2, 3, 2, 1, 0, 0, 2, 1, 3, 3, 1, 3, 2];
-    const S1: [u8; 16] = [0, 1, 2, 3, 2, 0, 1, 3, 3, 0, 1, 0, 2, 1, 0, 3];
-
     let right = bits & 0b1111;
     let left = bits >> 4;
 
@@ -93,14 +195,14 @@ fn shl_u5(bits: u16) -> u16 {
     (bits << 1) & 0b11111 | overflow_bit
 }
 
-fn permutation_4(bits: u8) -> u8 {
+pub(crate) fn permutation_4(bits: u8) -> u8 {
     (bits & 0b1000) >> 3
         | (bits & 0b0100) << 1
         | (bits & 0b0010)
         | (bits & 0b0001) << 2
 }
 
-fn permutation_ep(bits: u8) -> u8 {
+pub(crate) fn permutation_ep(bits: u8) -> u8 {
     (bits & 0b0001) << 7
         | (bits & 0b1000) << 3
         | (bits & 0b0100) << 3
@@ -111,7 +213,7 @@ fn permutation_ep(bits: u8) -> u8 {
         | (bits & 0b1000) >> 3
 }
 
-fn permutation_inverse_ip(bits: u8) -> u8 {
+pub(crate) fn permutation_inverse_ip(bits: u8) -> u8 {
     (bits & 0b10000000) >> 1
         | (bits & 0b01000000) >> 4
         | (bits & 0b00100000)
@@ -122,7 +224,7 @@ fn permutation_inverse_ip(bits: u8) -> u8 {
         | (bits & 0b00000001) << 1
 }
 
-fn permutation_ip(bits: u8) -> u8 {
+pub(crate) fn permutation_ip(bits: u8) -> u8 {
     (bits & 0b10000000) >> 3
         | (bits & 0b01000000) << 1
         | (bits & 0b00100000)
@@ -134,7 +236,6 @@ fn permutation_ip(bits: u8) -> u8 {
 }
 
 fn permutation_8(bits: u8) -> u8 {
-    let bits = bits as u8;
     (bits & 0b10000000) >> 1
         | (bits & 0b01000000) >> 2
         | (bits & 0b00100000) >> 3
@@ -158,6 +259,392 @@ fn permutation_10(bits: u16) -> u16 {
         | (bits & 0b0000000001) << 4
 }
 
+/// Initial permutation. Tables here number bits from 1 at the most
+/// significant end, as FIPS 46-3 prints them
+pub(crate) const IP: [u8; 64] = [
+    58, 50, 42, 34, 26, 18, 10, 2, 60, 52, 44, 36, 28, 20, 12, 4, 62, 54, 46,
+    38, 30, 22, 14, 6, 64, 56, 48, 40, 32, 24, 16, 8, 57, 49, 41, 33, 25, 17,
+    9, 1, 59, 51, 43, 35, 27, 19, 11, 3, 61, 53, 45, 37, 29, 21, 13, 5, 63, 55,
+    47, 39, 31, 23, 15, 7,
+];
+
+/// Final permutation, the inverse of [`IP`]
+pub(crate) const FP: [u8; 64] = [
+    40, 8, 48, 16, 56, 24, 64, 32, 39, 7, 47, 15, 55, 23, 63, 31, 38, 6, 46,
+    14, 54, 22, 62, 30, 37, 5, 45, 13, 53, 21, 61, 29, 36, 4, 44, 12, 52, 20,
+    60, 28, 35, 3, 43, 11, 51, 19, 59, 27, 34, 2, 42, 10, 50, 18, 58, 26, 33,
+    1, 41, 9, 49, 17, 57, 25,
+];
+
+/// Expansion of the 32 bit half block to 48 bits
+pub(crate) const E: [u8; 48] = [
+    32, 1, 2, 3, 4, 5, 4, 5, 6, 7, 8, 9, 8, 9, 10, 11, 12, 13, 12, 13, 14, 15,
+    16, 17, 16, 17, 18, 19, 20, 21, 20, 21, 22, 23, 24, 25, 24, 25, 26, 27, 28,
+    29, 28, 29, 30, 31, 32, 1,
+];
+
+/// Permutation of the S-box outputs
+pub(crate) const P: [u8; 32] = [
+    16, 7, 20, 21, 29, 12, 28, 17, 1, 15, 23, 26, 5, 18, 31, 10, 2, 8, 24, 14,
+    32, 27, 3, 9, 19, 13, 30, 6, 22, 11, 4, 25,
+];
+
+/// Permuted choice 1, the 56 key bits without the parity bits
+const PC1: [u8; 56] = [
+    57, 49, 41, 33, 25, 17, 9, 1, 58, 50, 42, 34, 26, 18, 10, 2, 59, 51, 43,
+    35, 27, 19, 11, 3, 60, 52, 44, 36, 63, 55, 47, 39, 31, 23, 15, 7, 62, 54,
+    46, 38, 30, 22, 14, 6, 61, 53, 45, 37, 29, 21, 13, 5, 28, 20, 12, 4,
+];
+
+/// Permuted choice 2, the 48 bits of a round key from C and D
+const PC2: [u8; 48] = [
+    14, 17, 11, 24, 1, 5, 3, 28, 15, 6, 21, 10, 23, 19, 12, 4, 26, 8, 16, 7,
+    27, 20, 13, 2, 41, 52, 31, 37, 47, 55, 30, 40, 51, 45, 33, 48, 44, 49, 39,
+    56, 34, 53, 46, 42, 50, 36, 29, 32,
+];
+
+/// Left rotations of C and D before each round
+const ROTATIONS: [u32; 16] = [1, 1, 2, 2, 2, 2, 2, 2, 1, 2, 2, 2, 2, 2, 2, 1];
+
+/// S1 to S8, each as four rows of sixteen
+pub(crate) const S_BOXES: [[u8; 64]; 8] = [
+    [
+        14, 4, 13, 1, 2, 15, 11, 8, 3, 10, 6, 12, 5, 9, 0, 7, 0, 15, 7, 4, 14,
+        2, 13, 1, 10, 6, 12, 11, 9, 5, 3, 8, 4, 1, 14, 8, 13, 6, 2, 11, 15, 12,
+        9, 7, 3, 10, 5, 0, 15, 12, 8, 2, 4, 9, 1, 7, 5, 11, 3, 14, 10, 0, 6,
+        13,
+    ],
+    [
+        15, 1, 8, 14, 6, 11, 3, 4, 9, 7, 2, 13, 12, 0, 5, 10, 3, 13, 4, 7, 15,
+        2, 8, 14, 12, 0, 1, 10, 6, 9, 11, 5, 0, 14, 7, 11, 10, 4, 13, 1, 5, 8,
+        12, 6, 9, 3, 2, 15, 13, 8, 10, 1, 3, 15, 4, 2, 11, 6, 7, 12, 0, 5, 14,
+        9,
+    ],
+    [
+        10, 0, 9, 14, 6, 3, 15, 5, 1, 13, 12, 7, 11, 4, 2, 8, 13, 7, 0, 9, 3,
+        4, 6, 10, 2, 8, 5, 14, 12, 11, 15, 1, 13, 6, 4, 9, 8, 15, 3, 0, 11, 1,
+        2, 12, 5, 10, 14, 7, 1, 10, 13, 0, 6, 9, 8, 7, 4, 15, 14, 3, 11, 5, 2,
+        12,
+    ],
+    [
+        7, 13, 14, 3, 0, 6, 9, 10, 1, 2, 8, 5, 11, 12, 4, 15, 13, 8, 11, 5, 6,
+        15, 0, 3, 4, 7, 2, 12, 1, 10, 14, 9, 10, 6, 9, 0, 12, 11, 7, 13, 15, 1,
+        3, 14, 5, 2, 8, 4, 3, 15, 0, 6, 10, 1, 13, 8, 9, 4, 5, 11, 12, 7, 2,
+        14,
+    ],
+    [
+        2, 12, 4, 1, 7, 10, 11, 6, 8, 5, 3, 15, 13, 0, 14, 9, 14, 11, 2, 12, 4,
+        7, 13, 1, 5, 0, 15, 10, 3, 9, 8, 6, 4, 2, 1, 11, 10, 13, 7, 8, 15, 9,
+        12, 5, 6, 3, 0, 14, 11, 8, 12, 7, 1, 14, 2, 13, 6, 15, 0, 9, 10, 4, 5,
+        3,
+    ],
+    [
+        12, 1, 10, 15, 9, 2, 6, 8, 0, 13, 3, 4, 14, 7, 5, 11, 10, 15, 4, 2, 7,
+        12, 9, 5, 6, 1, 13, 14, 0, 11, 3, 8, 9, 14, 15, 5, 2, 8, 12, 3, 7, 0,
+        4, 10, 1, 13, 11, 6, 4, 3, 2, 12, 9, 5, 15, 10, 11, 14, 1, 7, 6, 0, 8,
+        13,
+    ],
+    [
+        4, 11, 2, 14, 15, 0, 8, 13, 3, 12, 9, 7, 5, 10, 6, 1, 13, 0, 11, 7, 4,
+        9, 1, 10, 14, 3, 5, 12, 2, 15, 8, 6, 1, 4, 11, 13, 12, 3, 7, 14, 10,
+        15, 6, 8, 0, 5, 9, 2, 6, 11, 13, 8, 1, 4, 10, 7, 9, 5, 0, 15, 14, 2, 3,
+        12,
+    ],
+    [
+        13, 2, 8, 4, 6, 15, 11, 1, 10, 9, 3, 14, 5, 0, 12, 7, 1, 15, 13, 8, 10,
+        3, 7, 4, 12, 5, 6, 11, 0, 14, 9, 2, 7, 11, 4, 1, 9, 12, 14, 2, 0, 6,
+        10, 13, 15, 3, 5, 8, 2, 1, 14, 7, 4, 10, 8, 13, 15, 12, 9, 0, 3, 5, 6,
+        11,
+    ],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DesError {
+    /// A key byte does not have an odd number of set bits
+    InvalidParity,
+    /// Two of the Triple DES keys are equal, which collapses it to single
+    /// DES or to the two key option
+    DegenerateKey,
+    /// One of the four weak or twelve semi-weak keys
+    WeakKey,
+}
+
+impl std::fmt::Display for DesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::InvalidParity => "key byte without odd parity",
+            Self::DegenerateKey => "repeated triple DES key",
+            Self::WeakKey => "weak or semi-weak DES key",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for DesError {}
+
+/// Whether a constructor accepts the weak and semi-weak keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeakKeys {
+    Reject,
+    Allow,
+}
+
+/// The keys whose sixteen round keys are all equal, so encryption and
+/// decryption are the same permutation
+pub const WEAK_KEYS: [[u8; 8]; 4] = [
+    [0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01],
+    [0xFE, 0xFE, 0xFE, 0xFE, 0xFE, 0xFE, 0xFE, 0xFE],
+    [0xE0, 0xE0, 0xE0, 0xE0, 0xF1, 0xF1, 0xF1, 0xF1],
+    [0x1F, 0x1F, 0x1F, 0x1F, 0x0E, 0x0E, 0x0E, 0x0E],
+];
+
+/// Pairs of keys with only two distinct round keys each, where encrypting
+/// under one decrypts under the other
+pub const SEMI_WEAK_KEY_PAIRS: [([u8; 8], [u8; 8]); 6] = [
+    (
+        [0x01, 0xFE, 0x01, 0xFE, 0x01, 0xFE, 0x01, 0xFE],
+        [0xFE, 0x01, 0xFE, 0x01, 0xFE, 0x01, 0xFE, 0x01],
+    ),
+    (
+        [0x1F, 0xE0, 0x1F, 0xE0, 0x0E, 0xF1, 0x0E, 0xF1],
+        [0xE0, 0x1F, 0xE0, 0x1F, 0xF1, 0x0E, 0xF1, 0x0E],
+    ),
+    (
+        [0x01, 0xE0, 0x01, 0xE0, 0x01, 0xF1, 0x01, 0xF1],
+        [0xE0, 0x01, 0xE0, 0x01, 0xF1, 0x01, 0xF1, 0x01],
+    ),
+    (
+        [0x1F, 0xFE, 0x1F, 0xFE, 0x0E, 0xFE, 0x0E, 0xFE],
+        [0xFE, 0x1F, 0xFE, 0x1F, 0xFE, 0x0E, 0xFE, 0x0E],
+    ),
+    (
+        [0x01, 0x1F, 0x01, 0x1F, 0x01, 0x0E, 0x01, 0x0E],
+        [0x1F, 0x01, 0x1F, 0x01, 0x0E, 0x01, 0x0E, 0x01],
+    ),
+    (
+        [0xE0, 0xFE, 0xE0, 0xFE, 0xF1, 0xFE, 0xF1, 0xFE],
+        [0xFE, 0xE0, 0xFE, 0xE0, 0xFE, 0xF1, 0xFE, 0xF1],
+    ),
+];
+
+/// Whether every byte of the key has an odd number of set bits, which the
+/// least significant bit of each byte is meant to ensure
+pub fn has_odd_parity(key: &[u8; 8]) -> bool {
+    key.iter().all(|byte| byte.count_ones() % 2 == 1)
+}
+
+/// Sets the least significant bit of each byte so the byte has odd parity
+pub fn set_odd_parity(key: &mut [u8; 8]) {
+    for byte in key {
+        let high = *byte & 0xfe;
+        *byte = high | (high.count_ones() as u8 & 1 ^ 1);
+    }
+}
+
+/// Whether two DES keys agree once the parity bits are dropped
+fn same_des_key(a: &[u8], b: &[u8]) -> bool {
+    a.iter().zip(b).all(|(x, y)| (x ^ y) & 0xfe == 0)
+}
+
+/// Whether the key is one of [`WEAK_KEYS`], ignoring parity
+pub fn is_weak_key(key: &[u8; 8]) -> bool {
+    WEAK_KEYS.iter().any(|weak| same_des_key(weak, key))
+}
+
+/// Whether the key is in one of [`SEMI_WEAK_KEY_PAIRS`], ignoring parity
+pub fn is_semi_weak_key(key: &[u8; 8]) -> bool {
+    SEMI_WEAK_KEY_PAIRS
+        .iter()
+        .any(|(a, b)| same_des_key(a, key) || same_des_key(b, key))
+}
+
+/// Picks bit `table[i]` of the `width` bit `input` into bit `i` of the
+/// output, both counted from the most significant end
+fn permute(input: u64, width: u32, table: &[u8]) -> u64 {
+    table.iter().fold(0, |output, &position| {
+        (output << 1) | ((input >> (width - position as u32)) & 1)
+    })
+}
+
+/// The round function, `P(S(E(R) ^ K))`
+fn feistel(right: u32, subkey: u64) -> u32 {
+    let mixed = permute(right as u64, 32, &E) ^ subkey;
+    let substituted =
+        S_BOXES.iter().enumerate().fold(0, |output, (i, sbox)| {
+            let six = (mixed >> (42 - 6 * i)) & 0b111111;
+            let row = (six >> 4) & 0b10 | six & 1;
+            let column = (six >> 1) & 0b1111;
+            (output << 4) | sbox[(row * 16 + column) as usize] as u64
+        });
+    permute(substituted, 32, &P) as u32
+}
+
+/// DES as FIPS 46-3 specifies it, 64 bit blocks under a 56 bit key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Des {
+    pub(crate) subkeys: [u64; 16],
+}
+
+impl Des {
+    /// The key is eight bytes whose least significant bits are parity bits.
+    /// They are dropped by the key schedule and not checked, so keys that
+    /// differ only in parity give the same cipher
+    pub fn new(key: &[u8; 8]) -> Self {
+        let key = permute(u64::from_be_bytes(*key), 64, &PC1);
+        let mask = (1 << 28) - 1;
+        let (mut c, mut d) = (key >> 28, key & mask);
+        let mut subkeys = [0; 16];
+        for (subkey, rotation) in subkeys.iter_mut().zip(ROTATIONS) {
+            c = ((c << rotation) | (c >> (28 - rotation))) & mask;
+            d = ((d << rotation) | (d >> (28 - rotation))) & mask;
+            *subkey = permute((c << 28) | d, 56, &PC2);
+        }
+        Self { subkeys }
+    }
+
+    /// Like [`Des::new`] but refusing a key whose parity bits are wrong,
+    /// usually the sign of a corrupted or mistyped key, and with
+    /// [`WeakKeys::Reject`] the weak and semi-weak keys
+    pub fn new_checked(
+        key: &[u8; 8],
+        weak_keys: WeakKeys,
+    ) -> Result<Self, DesError> {
+        if !has_odd_parity(key) {
+            return Err(DesError::InvalidParity);
+        }
+        if weak_keys == WeakKeys::Reject
+            && (is_weak_key(key) || is_semi_weak_key(key))
+        {
+            return Err(DesError::WeakKey);
+        }
+        Ok(Self::new(key))
+    }
+
+    pub fn encrypt_block(&self, block: &[u8; 8]) -> [u8; 8] {
+        let (left, right) = initial_permutation(block);
+        final_permutation(self.encrypt_halves(left, right))
+    }
+
+    /// Encryption with the round keys in reverse order
+    pub fn decrypt_block(&self, block: &[u8; 8]) -> [u8; 8] {
+        let (left, right) = initial_permutation(block);
+        final_permutation(self.decrypt_halves(left, right))
+    }
+}
+
+impl Feistel for Des {
+    type Half = u32;
+    type RoundKey = u64;
+
+    fn rounds(&self) -> usize {
+        16
+    }
+
+    fn round_key(&self, round: usize) -> u64 {
+        self.subkeys[round]
+    }
+
+    fn round(&self, right: u32, subkey: &u64) -> u32 {
+        feistel(right, *subkey)
+    }
+}
+
+fn initial_permutation(block: &[u8; 8]) -> (u32, u32) {
+    let block = permute(u64::from_be_bytes(*block), 64, &IP);
+    ((block >> 32) as u32, block as u32)
+}
+
+fn final_permutation((left, right): (u32, u32)) -> [u8; 8] {
+    permute(((left as u64) << 32) | right as u64, 64, &FP).to_be_bytes()
+}
+
+/// Triple DES (TDEA) of NIST SP 800-67, `E_K3(D_K2(E_K1(block)))`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tdea {
+    k1: Des,
+    k2: Des,
+    k3: Des,
+}
+
+impl Tdea {
+    /// Keying option 1, three independent keys K1 || K2 || K3. Any two
+    /// being equal is refused
+    pub fn new(key: &[u8; 24]) -> Result<Self, DesError> {
+        let (k1, k2, k3) = (&key[..8], &key[8..16], &key[16..]);
+        if same_des_key(k1, k2) || same_des_key(k2, k3) || same_des_key(k1, k3)
+        {
+            return Err(DesError::DegenerateKey);
+        }
+        Ok(Self::from_keys(k1, k2, k3))
+    }
+
+    /// Keying option 2, K1 || K2 with K3 = K1. Refuses K1 = K2, which
+    /// would make it single DES
+    pub fn new_two_key(key: &[u8; 16]) -> Result<Self, DesError> {
+        let (k1, k2) = (&key[..8], &key[8..]);
+        if same_des_key(k1, k2) {
+            return Err(DesError::DegenerateKey);
+        }
+        Ok(Self::from_keys(k1, k2, k1))
+    }
+
+    fn from_keys(k1: &[u8], k2: &[u8], k3: &[u8]) -> Self {
+        let des = |key: &[u8]| Des::new(key.try_into().unwrap());
+        Self {
+            k1: des(k1),
+            k2: des(k2),
+            k3: des(k3),
+        }
+    }
+
+    pub fn encrypt_block(&self, block: &[u8; 8]) -> [u8; 8] {
+        let block = self.k1.encrypt_block(block);
+        let block = self.k2.decrypt_block(&block);
+        self.k3.encrypt_block(&block)
+    }
+
+    pub fn decrypt_block(&self, block: &[u8; 8]) -> [u8; 8] {
+        let block = self.k3.decrypt_block(block);
+        let block = self.k2.encrypt_block(&block);
+        self.k1.decrypt_block(&block)
+    }
+}
+
+/// DESX, `K2 ^ E_K(block ^ K1)`. The 64 bit whitening keys around DES
+/// make exhaustive search cost far more than 2^56 for one extra XOR per
+/// side, though it adds nothing against differential or linear attacks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Desx {
+    des: Des,
+    pre_whitening: u64,
+    post_whitening: u64,
+}
+
+impl Desx {
+    /// The key is K || K1 || K2, the DES key followed by the pre- and
+    /// post-whitening keys, 184 bits once the parity bits of K are dropped
+    pub fn new(key: &[u8; 24]) -> Self {
+        let word = |bytes: &[u8]| u64::from_be_bytes(bytes.try_into().unwrap());
+        Self {
+            des: Des::new(key[..8].try_into().unwrap()),
+            pre_whitening: word(&key[8..16]),
+            post_whitening: word(&key[16..]),
+        }
+    }
+
+    pub fn encrypt_block(&self, block: &[u8; 8]) -> [u8; 8] {
+        let block = u64::from_be_bytes(*block) ^ self.pre_whitening;
+        let block = self.des.encrypt_block(&block.to_be_bytes());
+        (u64::from_be_bytes(block) ^ self.post_whitening).to_be_bytes()
+    }
+
+    pub fn decrypt_block(&self, block: &[u8; 8]) -> [u8; 8] {
+        let block = u64::from_be_bytes(*block) ^ self.post_whitening;
+        let block = self.des.decrypt_block(&block.to_be_bytes());
+        (u64::from_be_bytes(block) ^ self.pre_whitening).to_be_bytes()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,7 +652,7 @@ mod tests {
     #[test]
     fn test_sdes_keygen() {
         let key = 0b1010000010;
-        let sdes = Sdes::new(key);
+        let sdes = Sdes::new(key).unwrap();
 
         assert_eq!(
             sdes,
@@ -179,7 +666,7 @@ mod tests {
     #[test]
     fn test_sdes_encryption() {
         let key = 0b1010000010;
-        let sdes = Sdes::new(key);
+        let sdes = Sdes::new(key).unwrap();
         let ciphertext = sdes.encrypt(0b10010111);
 
         assert_eq!(ciphertext, 0b00111000)
@@ -188,8 +675,8 @@ mod tests {
     #[test]
     fn test_sdes_decryption1() {
         let key = 0b1010000010;
-        let sdes = Sdes::new(key);
-        let ciphertext = sdes.decyrpt(0b00111000);
+        let sdes = Sdes::new(key).unwrap();
+        let ciphertext = sdes.decrypt(0b00111000);
 
         assert_eq!(ciphertext, 0b10010111)
     }
@@ -197,14 +684,345 @@ mod tests {
     #[test]
     fn test_sdes_decryption2() {
         let key = 0b1100101001;
-        let sdes = Sdes::new(key);
-        let data = sdes.decyrpt(0b00011001);
+        let sdes = Sdes::new(key).unwrap();
+        let data = sdes.decrypt(0b00011001);
 
         assert_eq!(data, 0b10100110)
     }
 
+    #[test]
+    fn test_sdes_key_range() {
+        assert!(Sdes::new(1023).is_ok());
+        assert_eq!(Sdes::new(1024), Err(SdesError::KeyOutOfRange));
+        assert_eq!(Sdes::new(u16::MAX), Err(SdesError::KeyOutOfRange));
+    }
+
+    #[test]
+    fn test_sdes_ecb() {
+        let sdes = Sdes::new(0b1010000010).unwrap();
+        let ciphertext = sdes.encrypt_bytes(&[0b10010111; 3], SdesMode::Ecb);
+        assert_eq!(ciphertext, [0b00111000; 3]);
+        assert_eq!(
+            sdes.decrypt_bytes(&ciphertext, SdesMode::Ecb),
+            [0b10010111; 3]
+        );
+    }
+
+    #[test]
+    fn test_sdes_cbc() {
+        let sdes = Sdes::new(0b1010000010).unwrap();
+        let mode = SdesMode::Cbc { iv: 0b11001100 };
+        let plaintext = b"attack at dawn, attack at dawn";
+        let ciphertext = sdes.encrypt_bytes(plaintext, mode);
+
+        let mut previous = 0b11001100;
+        for (&p, &c) in plaintext.iter().zip(&ciphertext) {
+            assert_eq!(c, sdes.encrypt(p ^ previous));
+            previous = c;
+        }
+        // the repeated halves no longer repeat
+        assert_ne!(ciphertext[..15], ciphertext[15..]);
+        assert_eq!(sdes.decrypt_bytes(&ciphertext, mode), plaintext);
+        assert!(sdes.encrypt_bytes(&[], mode).is_empty());
+    }
+
     #[test]
     fn test_permutation10() {
         assert_eq!(permutation_10(0b1010000010), 0b1000001100)
     }
+
+    fn des_encrypt(key: u64, plaintext: u64) -> u64 {
+        let des = Des::new(&key.to_be_bytes());
+        u64::from_be_bytes(des.encrypt_block(&plaintext.to_be_bytes()))
+    }
+
+    #[test]
+    fn test_des_textbook() {
+        let des = Des::new(&0x133457799BBCDFF1u64.to_be_bytes());
+        let plaintext = 0x0123456789ABCDEFu64.to_be_bytes();
+        let ciphertext = des.encrypt_block(&plaintext);
+        assert_eq!(ciphertext, 0x85E813540F0AB405u64.to_be_bytes());
+        assert_eq!(des.decrypt_block(&ciphertext), plaintext);
+    }
+
+    /// NBS special publication 500-20 as repeated in NIST SP 800-17: the
+    /// first entries of the variable plaintext and variable key tests and
+    /// the whole substitution table test
+    #[test]
+    fn test_des_validation_vectors() {
+        let variable_plaintext = [
+            (0x8000000000000000, 0x95F8A5E5DD31D900),
+            (0x4000000000000000, 0xDD7F121CA5015619),
+            (0x2000000000000000, 0x2E8653104F3834EA),
+            (0x0000000100000000, 0xAEB5F5EDE22D1A36),
+            (0x0000000000000001, 0x166B40B44ABA4BD6),
+        ];
+        for (plaintext, ciphertext) in variable_plaintext {
+            assert_eq!(des_encrypt(0x0101010101010101, plaintext), ciphertext);
+        }
+
+        let variable_key = [
+            (0x8001010101010101, 0x95A8D72813DAA94D),
+            (0x4001010101010101, 0x0EEC1487DD8C26D5),
+            (0x0201010101010101, 0x4615AA1D33E72F10),
+            (0x0180010101010101, 0x2055123350C00858),
+            (0x0101010101010102, 0x869EFD7F9F265A09),
+        ];
+        for (key, ciphertext) in variable_key {
+            assert_eq!(des_encrypt(key, 0), ciphertext);
+        }
+
+        let substitution = [
+            (0x7CA110454A1A6E57, 0x01A1D6D039776742, 0x690F5B0D9A26939B),
+            (0x0131D9619DC1376E, 0x5CD54CA83DEF57DA, 0x7A389D10354BD271),
+            (0x07A1133E4A0B2686, 0x0248D43806F67172, 0x868EBB51CAB4599A),
+            (0x3849674C2602319E, 0x51454B582DDF440A, 0x7178876E01F19B2A),
+            (0x04B915BA43FEB5B6, 0x42FD443059577FA2, 0xAF37FB421F8C4095),
+            (0x0113B970FD34F2CE, 0x059B5E0851CF143A, 0x86A560F10EC6D85B),
+            (0x0170F175468FB5E6, 0x0756D8E0774761D2, 0x0CD3DA020021DC09),
+            (0x43297FAD38E373FE, 0x762514B829BF486A, 0xEA676B2CB7DB2B7A),
+            (0x07A7137045DA2A16, 0x3BDD119049372802, 0xDFD64A815CAF1A0F),
+            (0x04689104C2FD3B2F, 0x26955F6835AF609A, 0x5C513C9C4886C088),
+            (0x37D06BB516CB7546, 0x164D5E404F275232, 0x0A2AEEAE3FF4AB77),
+            (0x1F08260D1AC2465E, 0x6B056E18759F5CCA, 0xEF1BF03E5DFA575A),
+            (0x584023641ABA6176, 0x004BD6EF09176062, 0x88BF0DB6D70DEE56),
+            (0x025816164629B007, 0x480D39006EE762F2, 0xA1F9915541020B56),
+            (0x49793EBC79B3258F, 0x437540C8698F3CFA, 0x6FBF1CAFCFFD0556),
+            (0x4FB05E1515AB73A7, 0x072D43A077075292, 0x2F22E49BAB7CA1AC),
+            (0x49E95D6D4CA229BF, 0x02FE55778117F12A, 0x5A6B612CC26CCE4A),
+            (0x018310DC409B26D6, 0x1D9D5C5018F728C2, 0x5F4C038ED12B2E41),
+            (0x1C587F1C13924FEF, 0x305532286D6F295A, 0x63FAC0D034D9F793),
+        ];
+        for (key, plaintext, ciphertext) in substitution {
+            assert_eq!(des_encrypt(key, plaintext), ciphertext, "{key:016X}");
+            let des = Des::new(&key.to_be_bytes());
+            let decrypted = des.decrypt_block(&ciphertext.to_be_bytes());
+            assert_eq!(u64::from_be_bytes(decrypted), plaintext);
+        }
+    }
+
+    /// Rivest's test: alternately encrypt and decrypt a block under itself
+    /// sixteen times, which exercises the S-boxes thoroughly
+    #[test]
+    fn test_des_iterated() {
+        let mut x = 0x9474B8E8C73BCA7Du64.to_be_bytes();
+        for i in 0..16 {
+            let des = Des::new(&x);
+            x = if i % 2 == 0 {
+                des.encrypt_block(&x)
+            } else {
+                des.decrypt_block(&x)
+            };
+        }
+        assert_eq!(x, 0x1B1A2DDB4C642438u64.to_be_bytes());
+    }
+
+    #[test]
+    fn test_des_parity() {
+        let key = 0x133457799BBCDFF1u64.to_be_bytes();
+        assert!(has_odd_parity(&key));
+        assert_eq!(
+            Des::new_checked(&key, WeakKeys::Reject),
+            Ok(Des::new(&key))
+        );
+
+        // flipping parity bits leaves the cipher alone but fails the check
+        let flipped =
+            (0x133457799BBCDFF1u64 ^ 0x0100000000000001).to_be_bytes();
+        assert!(!has_odd_parity(&flipped));
+        assert_eq!(Des::new(&flipped), Des::new(&key));
+        assert_eq!(
+            Des::new_checked(&flipped, WeakKeys::Allow),
+            Err(DesError::InvalidParity)
+        );
+    }
+
+    fn tdea_blocks(
+        encrypt: impl Fn(&[u8; 8]) -> [u8; 8],
+        data: &[u8],
+    ) -> Vec<u8> {
+        data.chunks_exact(8)
+            .flat_map(|block| encrypt(block.try_into().unwrap()))
+            .collect()
+    }
+
+    /// The example of SP 800-67 appendix B, three blocks in ECB mode, and
+    /// the same plaintext under the first two keys alone
+    #[test]
+    fn test_tdea_vectors() {
+        let plaintext = b"The qufck brown fox jump";
+        let key = [
+            0x01, 0x23, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF, 0x23, 0x45, 0x67,
+            0x89, 0xAB, 0xCD, 0xEF, 0x01, 0x45, 0x67, 0x89, 0xAB, 0xCD, 0xEF,
+            0x01, 0x23,
+        ];
+
+        let tdea = Tdea::new(&key).unwrap();
+        let ciphertext = tdea_blocks(|b| tdea.encrypt_block(b), plaintext);
+        assert_eq!(
+            ciphertext,
+            [
+                0xA8, 0x26, 0xFD, 0x8C, 0xE5, 0x3B, 0x85, 0x5F, 0xCC, 0xE2,
+                0x1C, 0x81, 0x12, 0x25, 0x6F, 0xE6, 0x68, 0xD5, 0xC0, 0x5D,
+                0xD9, 0xB6, 0xB9, 0x00
+            ]
+        );
+        assert_eq!(
+            tdea_blocks(|b| tdea.decrypt_block(b), &ciphertext),
+            plaintext
+        );
+
+        let tdea = Tdea::new_two_key(&key[..16].try_into().unwrap()).unwrap();
+        let ciphertext = tdea_blocks(|b| tdea.encrypt_block(b), plaintext);
+        assert_eq!(
+            ciphertext,
+            [
+                0xC4, 0x48, 0x62, 0xF7, 0x0C, 0xF2, 0xFB, 0xDC, 0x90, 0x77,
+                0xD0, 0x90, 0x9F, 0xA9, 0x1B, 0x88, 0x4C, 0xAB, 0xD6, 0x1F,
+                0xC5, 0x8E, 0x0C, 0xBB
+            ]
+        );
+        assert_eq!(
+            tdea_blocks(|b| tdea.decrypt_block(b), &ciphertext),
+            plaintext
+        );
+    }
+
+    #[test]
+    fn test_tdea_degenerate_keys() {
+        let k1 = 0x0123456789ABCDEFu64.to_be_bytes();
+        let k2 = 0x23456789ABCDEF01u64.to_be_bytes();
+        // K1 with its parity bits flipped is still K1
+        let k1_parity = 0x0022446688AACCEEu64.to_be_bytes();
+
+        let two_key = |a: &[u8; 8], b: &[u8; 8]| {
+            Tdea::new_two_key(&[&a[..], b].concat().try_into().unwrap())
+        };
+        assert_eq!(two_key(&k1, &k1), Err(DesError::DegenerateKey));
+        assert_eq!(two_key(&k1, &k1_parity), Err(DesError::DegenerateKey));
+        assert!(two_key(&k1, &k2).is_ok());
+
+        let three_key = |a: &[u8; 8], b: &[u8; 8], c: &[u8; 8]| {
+            Tdea::new(&[&a[..], b, c].concat().try_into().unwrap())
+        };
+        let k3 = 0x456789ABCDEF0123u64.to_be_bytes();
+        for keys in [(k1, k1, k3), (k1, k2, k2), (k1, k2, k1_parity)] {
+            assert_eq!(
+                three_key(&keys.0, &keys.1, &keys.2),
+                Err(DesError::DegenerateKey)
+            );
+        }
+
+        // with K1 = K2 the first two stages cancel, leaving DES under K3,
+        // which is why the key is refused
+        let des = Des::new(&k3);
+        let collapsed = Tdea::from_keys(&k1, &k1, &k3);
+        let block = *b"8 bytes!";
+        assert_eq!(collapsed.encrypt_block(&block), des.encrypt_block(&block));
+    }
+
+    /// DESX has no published test vectors. The ciphertext is a regression
+    /// value, which agrees with one block of `openssl enc -desx-cbc` with
+    /// the same key and a zero IV
+    #[test]
+    fn test_desx() {
+        let key: [u8; 24] = [
+            0x0123456789ABCDEFu64.to_be_bytes(),
+            0xF1E0D3C2B5A49786u64.to_be_bytes(),
+            0xFEDCBA9876543210u64.to_be_bytes(),
+        ]
+        .concat()
+        .try_into()
+        .unwrap();
+        let desx = Desx::new(&key);
+        let ciphertext = desx.encrypt_block(b"Now is t");
+        assert_eq!(ciphertext, 0xC327092461E40219u64.to_be_bytes());
+        assert_eq!(desx.decrypt_block(&ciphertext), *b"Now is t");
+
+        for i in 0..64u64 {
+            let block = i.wrapping_mul(0x9E3779B97F4A7C15).to_be_bytes();
+            assert_eq!(desx.decrypt_block(&desx.encrypt_block(&block)), block);
+        }
+    }
+
+    #[test]
+    fn test_desx_without_whitening() {
+        let key = [0x13, 0x34, 0x57, 0x79, 0x9B, 0xBC, 0xDF, 0xF1];
+        let mut desx_key = [0; 24];
+        desx_key[..8].copy_from_slice(&key);
+        let block = 0x0123456789ABCDEFu64.to_be_bytes();
+        let ciphertext = Desx::new(&desx_key).encrypt_block(&block);
+        assert_eq!(ciphertext, 0x85E813540F0AB405u64.to_be_bytes());
+        assert_eq!(ciphertext, Des::new(&key).encrypt_block(&block));
+    }
+
+    #[test]
+    fn test_des_weak_keys() {
+        let block = 0x0123456789ABCDEFu64.to_be_bytes();
+        for key in WEAK_KEYS {
+            assert!(is_weak_key(&key));
+            let des = Des::new(&key);
+            assert_eq!(des.encrypt_block(&des.encrypt_block(&block)), block);
+            assert_eq!(
+                Des::new_checked(&key, WeakKeys::Reject),
+                Err(DesError::WeakKey)
+            );
+            assert!(Des::new_checked(&key, WeakKeys::Allow).is_ok());
+        }
+        for (first, second) in SEMI_WEAK_KEY_PAIRS {
+            assert!(is_semi_weak_key(&first) && is_semi_weak_key(&second));
+            assert!(!is_weak_key(&first));
+            let ciphertext = Des::new(&first).encrypt_block(&block);
+            assert_eq!(Des::new(&second).encrypt_block(&ciphertext), block);
+            assert_eq!(
+                Des::new_checked(&second, WeakKeys::Reject),
+                Err(DesError::WeakKey)
+            );
+        }
+
+        // parity bits do not hide a weak key
+        assert!(is_weak_key(&[0; 8]));
+        let key = 0x133457799BBCDFF1u64.to_be_bytes();
+        assert!(!is_weak_key(&key) && !is_semi_weak_key(&key));
+    }
+
+    #[test]
+    fn test_set_odd_parity() {
+        let mut key = 0x0022446688AACCEEu64.to_be_bytes();
+        set_odd_parity(&mut key);
+        assert_eq!(key, 0x0123456789ABCDEFu64.to_be_bytes());
+        assert!(has_odd_parity(&key));
+        set_odd_parity(&mut key);
+        assert_eq!(key, 0x0123456789ABCDEFu64.to_be_bytes());
+
+        for byte in 0..=255u8 {
+            let mut key = [byte; 8];
+            set_odd_parity(&mut key);
+            assert!(has_odd_parity(&key));
+            assert_eq!(key[0] & 0xfe, byte & 0xfe);
+        }
+    }
+
+    #[test]
+    fn test_sdes_weak_keys() {
+        let weak = Sdes::weak_keys();
+        assert_eq!(weak, [0, 96, 392, 488, 535, 631, 927, 1023]);
+        for key in weak {
+            let sdes = Sdes::new(key).unwrap();
+            for bits in 0..=255 {
+                assert_eq!(sdes.encrypt(sdes.encrypt(bits)), bits);
+            }
+        }
+
+        let pairs = Sdes::semi_weak_key_pairs();
+        assert_eq!(pairs.len(), 12);
+        assert_eq!(pairs[0], (0b0000100000, 0b0001000000));
+        for (first, second) in pairs {
+            let (first, second) =
+                (Sdes::new(first).unwrap(), Sdes::new(second).unwrap());
+            for bits in 0..=255 {
+                assert_eq!(second.encrypt(first.encrypt(bits)), bits);
+            }
+        }
+    }
 }
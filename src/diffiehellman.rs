@@ -1,3 +1,4 @@
+use crate::bigint::BigUint;
 use crate::math::*;
 
 pub struct DiffieHellman {
@@ -19,6 +20,42 @@ impl DiffieHellman {
     }
 }
 
+/// The subgroup of prime order `q` of the integers mod the prime `p`,
+/// generated by `g`. Unlike [`DiffieHellman`] the generator need not be a
+/// primitive root, which is what DSA and friends build on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DhGroup {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+impl DhGroup {
+    pub fn new(p: BigUint, q: BigUint, g: BigUint) -> Self {
+        Self { p, q, g }
+    }
+
+    pub fn generate_exchange_key(&self, key: &BigUint) -> BigUint {
+        self.g.pow_mod(key, &self.p)
+    }
+
+    pub fn shared_secret(
+        &self,
+        key: &BigUint,
+        exchange_key: &BigUint,
+    ) -> BigUint {
+        exchange_key.pow_mod(key, &self.p)
+    }
+
+    /// Whether `element` is a member of the order `q` subgroup other than
+    /// the identity, i.e. `1 < element < p` and `element^q = 1 (mod p)`
+    pub fn contains(&self, element: &BigUint) -> bool {
+        element > &BigUint::one()
+            && element < &self.p
+            && element.pow_mod(&self.q, &self.p) == BigUint::one()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,4 +103,27 @@ mod tests {
         let bob_shared_secret = dh.shared_secret(bob_key, a);
         assert!(alice_shared_secret == bob_shared_secret)
     }
+
+    #[test]
+    pub fn dh_group_exchange() {
+        // p = 2q + 1 and 4 = 2^2 is a quadratic residue, so it has order q
+        let group = DhGroup::new(
+            BigUint::from(2039u64),
+            BigUint::from(1019u64),
+            BigUint::from(4u64),
+        );
+
+        let alice_key = BigUint::from(123u64);
+        let bob_key = BigUint::from(456u64);
+
+        let a = group.generate_exchange_key(&alice_key);
+        let b = group.generate_exchange_key(&bob_key);
+        assert!(group.contains(&a) && group.contains(&b));
+        // 7 is a non residue mod 2039, outside the subgroup
+        assert!(!group.contains(&BigUint::from(7u64)));
+
+        let alice_shared_secret = group.shared_secret(&alice_key, &b);
+        let bob_shared_secret = group.shared_secret(&bob_key, &a);
+        assert_eq!(alice_shared_secret, bob_shared_secret)
+    }
 }
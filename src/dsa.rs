@@ -0,0 +1,694 @@
+//! The Digital Signature Algorithm (FIPS 186-4) over the prime order
+//! subgroups of [`DhGroup`], with RFC 6979 deterministic nonces
+
+use crate::bigint::BigUint;
+use crate::diffiehellman::DhGroup;
+use crate::hash::Digest;
+use crate::rfc6979::{bits2int, NonceGenerator};
+
+/// The (L, N) bit lengths of p and q approved by FIPS 186-4 section 4.2
+pub const APPROVED_SIZES: [(usize, usize); 4] =
+    [(1024, 160), (2048, 224), (2048, 256), (3072, 256)];
+
+/// Miller-Rabin rounds, enough for every size in FIPS 186-4 table C.1
+const PRIMALITY_ROUNDS: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DsaError {
+    /// (L, N) is not one of [`APPROVED_SIZES`] or the hash or seed is too short
+    UnsupportedSize,
+    /// p, q or g fail the FIPS 186-4 appendix A checks
+    InvalidParameters,
+    /// The private key is not in `[1, q - 1]`
+    InvalidPrivateKey,
+    /// The public key is not a member of the subgroup
+    InvalidPublicKey,
+}
+
+impl std::fmt::Display for DsaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::UnsupportedSize => "unsupported domain parameter size",
+            Self::InvalidParameters => "invalid domain parameters",
+            Self::InvalidPrivateKey => "invalid private key",
+            Self::InvalidPublicKey => "invalid public key",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for DsaError {}
+
+/// Everything needed to rederive a set of domain parameters, published
+/// alongside them so anyone can check they were generated honestly
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DomainParameterSeed {
+    pub seed: Vec<u8>,
+    /// Which of the candidates for `p` derived from the seed was prime
+    pub counter: usize,
+    /// Distinguishes generators derived from the same p and q
+    pub index: u8,
+}
+
+/// Generates p and q from `seed` (A.1.1.2) and then a verifiable generator
+/// (A.2.3), hashing with `D`. A seed whose q is not prime is incremented and
+/// retried so the returned [`DomainParameterSeed`] may hold a different seed
+pub fn generate_parameters<D: Digest>(
+    l: usize,
+    n: usize,
+    seed: &[u8],
+    index: u8,
+) -> Result<(DhGroup, DomainParameterSeed), DsaError> {
+    check_sizes::<D>(l, n, seed)?;
+
+    let mut seed = seed.to_vec();
+    loop {
+        let q = derive_q::<D>(n, &seed);
+        if q.is_probable_prime(PRIMALITY_ROUNDS) {
+            let mut candidates = PrimeCandidates::<D>::new(l, &q, &seed);
+            if let Some((counter, p)) = (0..4 * l)
+                .map(|counter| (counter, candidates.next_p()))
+                .find(|(_, p)| {
+                    p.bits() == l && p.is_probable_prime(PRIMALITY_ROUNDS)
+                })
+            {
+                let g = derive_generator::<D>(&p, &q, &seed, index)
+                    .ok_or(DsaError::InvalidParameters)?;
+                let parameter_seed = DomainParameterSeed {
+                    seed,
+                    counter,
+                    index,
+                };
+                return Ok((DhGroup::new(p, q, g), parameter_seed));
+            }
+        }
+        increment(&mut seed);
+    }
+}
+
+/// Checks p and q are prime, q divides p - 1 and g generates the order q
+/// subgroup, without knowing how they were generated (A.1.1.1 and A.2.2)
+pub fn validate_parameters(group: &DhGroup) -> Result<(), DsaError> {
+    let (l, n) = (group.p.bits(), group.q.bits());
+    if !APPROVED_SIZES.contains(&(l, n)) {
+        return Err(DsaError::UnsupportedSize);
+    }
+
+    let p_minus_one = &group.p - &BigUint::one();
+    let valid = group.q.is_probable_prime(PRIMALITY_ROUNDS)
+        && group.p.is_probable_prime(PRIMALITY_ROUNDS)
+        && (&p_minus_one % &group.q).is_zero()
+        && group.contains(&group.g);
+    valid.then_some(()).ok_or(DsaError::InvalidParameters)
+}
+
+/// Rederives p, q and g from their seed and checks they match (A.1.1.3 and
+/// A.2.4), hashing with the same `D` they were generated with
+pub fn verify_parameter_seed<D: Digest>(
+    group: &DhGroup,
+    parameter_seed: &DomainParameterSeed,
+) -> Result<(), DsaError> {
+    verify_primes_seed::<D>(
+        &group.p,
+        &group.q,
+        &parameter_seed.seed,
+        parameter_seed.counter,
+    )?;
+    let g = derive_generator::<D>(
+        &group.p,
+        &group.q,
+        &parameter_seed.seed,
+        parameter_seed.index,
+    );
+    match g {
+        Some(g) if g == group.g && group.contains(&g) => Ok(()),
+        _ => Err(DsaError::InvalidParameters),
+    }
+}
+
+/// Rederives just p and q from their seed and counter and checks they
+/// match (A.1.1.3), for parameters whose generator was not derived from
+/// the seed
+pub fn verify_primes_seed<D: Digest>(
+    p: &BigUint,
+    q: &BigUint,
+    seed: &[u8],
+    counter: usize,
+) -> Result<(), DsaError> {
+    let (l, n) = (p.bits(), q.bits());
+    check_sizes::<D>(l, n, seed)?;
+    if counter >= 4 * l {
+        return Err(DsaError::InvalidParameters);
+    }
+
+    let derived_q = derive_q::<D>(n, seed);
+    if &derived_q != q || !derived_q.is_probable_prime(PRIMALITY_ROUNDS) {
+        return Err(DsaError::InvalidParameters);
+    }
+
+    // The first prime candidate must be the one at `counter`, otherwise the
+    // generator skipped a valid p which is a sign it was chosen
+    let mut candidates = PrimeCandidates::<D>::new(l, q, seed);
+    for i in 0..=counter {
+        let candidate = candidates.next_p();
+        if candidate.bits() == l
+            && candidate.is_probable_prime(PRIMALITY_ROUNDS)
+        {
+            return (i == counter && &candidate == p)
+                .then_some(())
+                .ok_or(DsaError::InvalidParameters);
+        }
+    }
+    Err(DsaError::InvalidParameters)
+}
+
+fn check_sizes<D: Digest>(
+    l: usize,
+    n: usize,
+    seed: &[u8],
+) -> Result<(), DsaError> {
+    let supported = APPROVED_SIZES.contains(&(l, n))
+        && D::OUTPUT_SIZE * 8 >= n
+        && seed.len() * 8 >= n;
+    supported.then_some(()).ok_or(DsaError::UnsupportedSize)
+}
+
+/// q = 2^(N-1) + U + 1 - (U mod 2) where U = Hash(seed) mod 2^(N-1)
+fn derive_q<D: Digest>(n: usize, seed: &[u8]) -> BigUint {
+    let u = BigUint::from_be_bytes(&D::digest(seed)).truncate(n - 1);
+    let q = &(&BigUint::one() << (n - 1)) + &u;
+    if u.is_odd() {
+        q
+    } else {
+        &q + &BigUint::one()
+    }
+}
+
+/// The candidates for p in the order A.1.1.2 tries them: L bit numbers
+/// congruent to 1 mod 2q made from hashes of seed + offset
+struct PrimeCandidates<'a, D: Digest> {
+    l: usize,
+    q: &'a BigUint,
+    seed: BigUint,
+    seed_bits: usize,
+    offset: usize,
+    _digest: std::marker::PhantomData<D>,
+}
+
+impl<'a, D: Digest> PrimeCandidates<'a, D> {
+    fn new(l: usize, q: &'a BigUint, seed: &[u8]) -> Self {
+        Self {
+            l,
+            q,
+            seed: BigUint::from_be_bytes(seed),
+            seed_bits: seed.len() * 8,
+            offset: 1,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    fn next_p(&mut self) -> BigUint {
+        let outlen = D::OUTPUT_SIZE * 8;
+        let n = self.l.div_ceil(outlen) - 1;
+        let b = self.l - 1 - n * outlen;
+
+        let mut w = BigUint::zero();
+        for j in 0..=n {
+            let input = (&self.seed + &BigUint::from((self.offset + j) as u64))
+                .truncate(self.seed_bits);
+            let mut v = BigUint::from_be_bytes(&D::digest(
+                &input.to_be_bytes_padded(self.seed_bits / 8),
+            ));
+            if j == n {
+                v = v.truncate(b);
+            }
+            w = &w + &(&v << (j * outlen));
+        }
+        self.offset += n + 1;
+
+        let x = &w + &(&BigUint::one() << (self.l - 1));
+        let c = &x % &(self.q << 1);
+        &(&x + &BigUint::one()) - &c
+    }
+}
+
+/// Verifiable canonical generation of g (A.2.3)
+fn derive_generator<D: Digest>(
+    p: &BigUint,
+    q: &BigUint,
+    seed: &[u8],
+    index: u8,
+) -> Option<BigUint> {
+    let e = &(p - &BigUint::one()) / q;
+    (1..=u16::MAX).find_map(|count| {
+        let mut u = seed.to_vec();
+        u.extend_from_slice(b"ggen");
+        u.push(index);
+        u.extend_from_slice(&count.to_be_bytes());
+        let w = BigUint::from_be_bytes(&D::digest(&u));
+        let g = w.pow_mod(&e, p);
+        (g >= BigUint::from(2u64)).then_some(g)
+    })
+}
+
+/// Adds one to a big endian byte string, wrapping at its length
+fn increment(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut().rev() {
+        let (sum, overflow) = byte.overflowing_add(1);
+        *byte = sum;
+        if !overflow {
+            break;
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningKey {
+    group: DhGroup,
+    x: BigUint,
+    verifying_key: VerifyingKey,
+}
+
+impl SigningKey {
+    pub fn new(group: DhGroup, x: BigUint) -> Result<Self, DsaError> {
+        if x.is_zero() || x >= group.q {
+            return Err(DsaError::InvalidPrivateKey);
+        }
+        let y = group.generate_exchange_key(&x);
+        let verifying_key = VerifyingKey::new(group.clone(), y)?;
+        Ok(Self {
+            group,
+            x,
+            verifying_key,
+        })
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.verifying_key
+    }
+
+    /// Signs `message` hashed with `D`, which also drives the RFC 6979 nonce
+    pub fn sign<D: Digest>(
+        &self,
+        message: &[u8],
+    ) -> Result<Signature, DsaError> {
+        self.sign_prehashed::<D>(&D::digest(message))
+    }
+
+    /// Signs an already computed `D` hash of the message. Fails with
+    /// [`DsaError::InvalidParameters`] if the nonce has no inverse mod q,
+    /// which only happens when q is not prime
+    pub fn sign_prehashed<D: Digest>(
+        &self,
+        hash: &[u8],
+    ) -> Result<Signature, DsaError> {
+        let DhGroup { p, q, g } = &self.group;
+        let z = bits2int(hash, q.bits());
+
+        let mut nonces = NonceGenerator::<D>::new(q, &self.x, hash);
+        loop {
+            let k = nonces.next_k();
+            let r = &g.pow_mod(&k, p) % q;
+            if r.is_zero() {
+                continue;
+            }
+            let k_inverse =
+                k.mod_inverse(q).ok_or(DsaError::InvalidParameters)?;
+            let s = k_inverse.mul_mod(&(&z + &self.x.mul_mod(&r, q)), q);
+            if !s.is_zero() {
+                return Ok(Signature { r, s });
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    group: DhGroup,
+    y: BigUint,
+}
+
+impl VerifyingKey {
+    pub fn new(group: DhGroup, y: BigUint) -> Result<Self, DsaError> {
+        if !group.contains(&y) {
+            return Err(DsaError::InvalidPublicKey);
+        }
+        Ok(Self { group, y })
+    }
+
+    pub fn y(&self) -> &BigUint {
+        &self.y
+    }
+
+    pub fn verify<D: Digest>(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        self.verify_prehashed(&D::digest(message), signature)
+    }
+
+    /// Nothing here checks the group, so a q that is not prime, where s may
+    /// have no inverse, gives `false` rather than a panic
+    pub fn verify_prehashed(&self, hash: &[u8], signature: &Signature) -> bool {
+        let DhGroup { p, q, g } = &self.group;
+        let Signature { r, s } = signature;
+        if r.is_zero() || r >= q || s.is_zero() || s >= q {
+            return false;
+        }
+
+        let z = bits2int(hash, q.bits());
+        let Some(w) = s.mod_inverse(q) else {
+            return false;
+        };
+        let u1 = z.mul_mod(&w, q);
+        let u2 = r.mul_mod(&w, q);
+        let v = &g.pow_mod(&u1, p).mul_mod(&self.y.pow_mod(&u2, p), p) % q;
+        &v == r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha1::Sha1;
+    use crate::sha2::{Sha224, Sha256, Sha384, Sha512};
+
+    /// RFC 6979 appendix A.2 keys and signatures in a CAVP like layout
+    const RFC6979_VECTORS: &str =
+        include_str!("../test_vectors/dsa_rfc6979.txt");
+    /// Signature verification and p, q validation cases, from NIST ACVP
+    /// for L = 3072 and generated with OpenSSL for every supported size
+    const SIGVER_VECTORS: [&str; 2] = [
+        include_str!("../test_vectors/dsa_acvp_sigver.txt"),
+        include_str!("../test_vectors/dsa_openssl_sigver.txt"),
+    ];
+    const PQGVER_VECTORS: [&str; 2] = [
+        include_str!("../test_vectors/dsa_acvp_pqgver.txt"),
+        include_str!("../test_vectors/dsa_openssl_pqgver.txt"),
+    ];
+
+    struct Rfc6979Case {
+        hash: String,
+        group: DhGroup,
+        message: Vec<u8>,
+        x: BigUint,
+        y: BigUint,
+        signature: Signature,
+    }
+
+    fn rfc6979_cases() -> Vec<Rfc6979Case> {
+        let mut cases = Vec::new();
+        let mut hash = String::new();
+        let (mut p, mut q, mut g) =
+            (BigUint::zero(), BigUint::zero(), BigUint::zero());
+        let (mut message, mut x, mut y, mut r) = (
+            Vec::new(),
+            BigUint::zero(),
+            BigUint::zero(),
+            BigUint::zero(),
+        );
+
+        for line in RFC6979_VECTORS.lines().map(str::trim) {
+            if let Some(mode) = line.strip_prefix("[mod = ") {
+                hash = mode
+                    .trim_end_matches(']')
+                    .rsplit(", ")
+                    .next()
+                    .unwrap()
+                    .to_string();
+                continue;
+            }
+            let Some((name, value)) = line.split_once(" = ") else {
+                continue;
+            };
+            let number = || BigUint::from_hex(value).unwrap();
+            match name {
+                "P" => p = number(),
+                "Q" => q = number(),
+                "G" => g = number(),
+                "Msg" => message = crate::hash::hex(value),
+                "X" => x = number(),
+                "Y" => y = number(),
+                "R" => r = number(),
+                "S" => cases.push(Rfc6979Case {
+                    hash: hash.clone(),
+                    group: DhGroup::new(p.clone(), q.clone(), g.clone()),
+                    message: message.clone(),
+                    x: x.clone(),
+                    y: y.clone(),
+                    signature: Signature {
+                        r: r.clone(),
+                        s: number(),
+                    },
+                }),
+                _ => {}
+            }
+        }
+        cases
+    }
+
+    fn sign_and_verify<D: Digest>(case: &Rfc6979Case) {
+        let key = SigningKey::new(case.group.clone(), case.x.clone()).unwrap();
+        assert_eq!(key.verifying_key().y(), &case.y);
+
+        let signature = key.sign::<D>(&case.message).unwrap();
+        assert_eq!(
+            signature, case.signature,
+            "{} {:?}",
+            case.hash, case.message
+        );
+        assert!(key.verifying_key().verify::<D>(&case.message, &signature));
+
+        let mut tampered = case.message.clone();
+        tampered[0] ^= 1;
+        assert!(!key.verifying_key().verify::<D>(&tampered, &signature));
+    }
+
+    #[test]
+    fn test_rfc6979_vectors() {
+        let cases = rfc6979_cases();
+        assert_eq!(cases.len(), 20);
+        for (i, case) in cases.iter().enumerate() {
+            if i == 0 || case.group != cases[i - 1].group {
+                validate_parameters(&case.group).unwrap();
+            }
+            match case.hash.as_str() {
+                "SHA-1" => sign_and_verify::<Sha1>(case),
+                "SHA-224" => sign_and_verify::<Sha224>(case),
+                "SHA-256" => sign_and_verify::<Sha256>(case),
+                "SHA-384" => sign_and_verify::<Sha384>(case),
+                "SHA-512" => sign_and_verify::<Sha512>(case),
+                other => panic!("unknown hash {other}"),
+            }
+        }
+    }
+
+    /// The `key = value` pairs of a record
+    type Record<'a> = Vec<(&'a str, &'a str)>;
+
+    /// Each blank line separated record, leaving out comments and
+    /// `[mod = ...]` headers
+    fn records(vectors: &str) -> Vec<Record<'_>> {
+        vectors
+            .split("\n\n")
+            .map(|record| {
+                record
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.starts_with(['#', '[']))
+                    .filter_map(|line| line.split_once(" = "))
+                    .collect::<Vec<_>>()
+            })
+            .filter(|record| !record.is_empty())
+            .collect()
+    }
+
+    fn field<'a>(record: &[(&str, &'a str)], name: &str) -> &'a str {
+        record.iter().find(|(key, _)| *key == name).unwrap().1
+    }
+
+    /// The digest named by each `[mod = L=.., N=.., digest]` header with
+    /// the records of its section
+    fn sections(vectors: &str) -> Vec<(&str, Vec<Record<'_>>)> {
+        vectors
+            .split("[mod = ")
+            .skip(1)
+            .map(|section| {
+                let (header, body) = section.split_once(']').unwrap();
+                (header.rsplit(", ").next().unwrap(), records(body))
+            })
+            .collect()
+    }
+
+    fn passes(record: &[(&str, &str)]) -> bool {
+        field(record, "Result").starts_with('P')
+    }
+
+    fn sigver<D: Digest>(group: &DhGroup, case: &[(&str, &str)]) -> bool {
+        let number = |name| BigUint::from_hex(field(case, name)).unwrap();
+        let signature = Signature {
+            r: number("R"),
+            s: number("S"),
+        };
+        let message = crate::hash::hex(field(case, "Msg"));
+        VerifyingKey::new(group.clone(), number("Y"))
+            .is_ok_and(|key| key.verify::<D>(&message, &signature))
+    }
+
+    fn pqgver<D: Digest>(case: &[(&str, &str)]) -> bool {
+        let number = |name| BigUint::from_hex(field(case, name)).unwrap();
+        verify_primes_seed::<D>(
+            &number("P"),
+            &number("Q"),
+            &crate::hash::hex(field(case, "domain_parameter_seed")),
+            field(case, "counter").parse().unwrap(),
+        )
+        .is_ok()
+    }
+
+    #[test]
+    fn test_sigver_vectors() {
+        for (hash, records) in SIGVER_VECTORS.iter().flat_map(|v| sections(v)) {
+            let number =
+                |name| BigUint::from_hex(field(&records[0], name)).unwrap();
+            let group = DhGroup::new(number("P"), number("Q"), number("G"));
+            for case in &records[1..] {
+                let valid = match hash {
+                    "SHA-1" => sigver::<Sha1>(&group, case),
+                    "SHA-224" => sigver::<Sha224>(&group, case),
+                    "SHA-256" => sigver::<Sha256>(&group, case),
+                    "SHA-384" => sigver::<Sha384>(&group, case),
+                    "SHA-512" => sigver::<Sha512>(&group, case),
+                    other => panic!("unknown hash {other}"),
+                };
+                assert_eq!(valid, passes(case), "{hash} {case:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_pqgver_vectors() {
+        for (hash, records) in PQGVER_VECTORS.iter().flat_map(|v| sections(v)) {
+            for case in &records {
+                let valid = match hash {
+                    "SHA-1" => pqgver::<Sha1>(case),
+                    "SHA-224" => pqgver::<Sha224>(case),
+                    "SHA-256" => pqgver::<Sha256>(case),
+                    "SHA-384" => pqgver::<Sha384>(case),
+                    "SHA-512" => pqgver::<Sha512>(case),
+                    other => panic!("unknown hash {other}"),
+                };
+                assert_eq!(valid, passes(case), "{hash} {case:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_rejects_out_of_range() {
+        let case = &rfc6979_cases()[0];
+        let key =
+            VerifyingKey::new(case.group.clone(), case.y.clone()).unwrap();
+        let Signature { r, s } = case.signature.clone();
+
+        let shifted = Signature {
+            r: &r + &case.group.q,
+            s: s.clone(),
+        };
+        assert!(!key.verify::<Sha1>(&case.message, &shifted));
+        let zero = Signature {
+            r,
+            s: BigUint::zero(),
+        };
+        assert!(!key.verify::<Sha1>(&case.message, &zero));
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        let case = &rfc6979_cases()[0];
+        assert_eq!(
+            SigningKey::new(case.group.clone(), case.group.q.clone()),
+            Err(DsaError::InvalidPrivateKey)
+        );
+        assert_eq!(
+            VerifyingKey::new(
+                case.group.clone(),
+                &case.group.p - &BigUint::one()
+            ),
+            Err(DsaError::InvalidPublicKey)
+        );
+    }
+
+    #[test]
+    fn test_composite_q() {
+        // q = 16 divides p - 1 = 16, so every element passes the subgroup
+        // check but only odd values have an inverse mod q
+        let group = DhGroup::new(
+            BigUint::from(17u64),
+            BigUint::from(16u64),
+            BigUint::from(3u64),
+        );
+        let key = SigningKey::new(group.clone(), BigUint::from(5u64)).unwrap();
+        let verifying_key = key.verifying_key();
+        let even_s = Signature {
+            r: BigUint::from(3u64),
+            s: BigUint::from(4u64),
+        };
+        assert!(!verifying_key.verify::<Sha256>(b"composite", &even_s));
+
+        let results: Vec<_> =
+            (0..32u8).map(|i| key.sign::<Sha256>(&[i])).collect();
+        assert!(results.contains(&Err(DsaError::InvalidParameters)));
+    }
+
+    #[test]
+    fn test_generate_and_verify_parameters() {
+        let seed = Sha256::digest(b"krypto dsa parameters")[..20].to_vec();
+        let (group, parameter_seed) =
+            generate_parameters::<Sha256>(1024, 160, &seed, 1).unwrap();
+        assert_eq!((group.p.bits(), group.q.bits()), (1024, 160));
+        validate_parameters(&group).unwrap();
+        verify_parameter_seed::<Sha256>(&group, &parameter_seed).unwrap();
+
+        let mut wrong_counter = parameter_seed.clone();
+        wrong_counter.counter += 1;
+        assert_eq!(
+            verify_parameter_seed::<Sha256>(&group, &wrong_counter),
+            Err(DsaError::InvalidParameters)
+        );
+
+        let mut wrong_index = parameter_seed.clone();
+        wrong_index.index = 2;
+        assert_eq!(
+            verify_parameter_seed::<Sha256>(&group, &wrong_index),
+            Err(DsaError::InvalidParameters)
+        );
+
+        let key =
+            SigningKey::new(group, BigUint::from(0x1234_5678u64)).unwrap();
+        let signature = key.sign::<Sha256>(b"parameters").unwrap();
+        assert!(key
+            .verifying_key()
+            .verify::<Sha256>(b"parameters", &signature));
+    }
+
+    #[test]
+    fn test_unsupported_sizes() {
+        let seed = [0u8; 20];
+        assert_eq!(
+            generate_parameters::<Sha256>(512, 160, &seed, 1).map(|_| ()),
+            Err(DsaError::UnsupportedSize)
+        );
+        // The hash must be at least N bits long
+        assert_eq!(
+            generate_parameters::<Sha1>(2048, 256, &[0; 32], 1).map(|_| ()),
+            Err(DsaError::UnsupportedSize)
+        );
+    }
+}
@@ -0,0 +1,180 @@
+//! Elliptic curve Diffie-Hellman (SEC1 section 3.3.1) on the curves of
+//! [`crate::nist`]
+//!
+//! The shared secret is the x-coordinate of the private key times the
+//! peer's public point. Peer points are checked against the curve first so
+//! an invalid curve attack cannot leak the key
+
+use crate::bigint::BigUint;
+use crate::primefield::{FieldElement, Modulus};
+use crate::weierstrass::{AffinePoint, JacobianPoint, WeierstrassCurve};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcdhError {
+    /// The private key is not in `[1, n - 1]`, or `[key]G` is the identity
+    /// because n is not the base point's order
+    InvalidPrivateKey,
+    /// The peer's point is off the curve or outside the base point's
+    /// subgroup
+    InvalidPublicKey,
+}
+
+impl std::fmt::Display for EcdhError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::InvalidPrivateKey => "invalid private key",
+            Self::InvalidPublicKey => "invalid public key",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for EcdhError {}
+
+pub struct Ecdh<M: Modulus> {
+    curve: WeierstrassCurve<FieldElement<M>>,
+}
+
+impl<M: Modulus> Ecdh<M> {
+    pub fn new(curve: WeierstrassCurve<FieldElement<M>>) -> Self {
+        Self { curve }
+    }
+
+    fn scalar(&self, key: &BigUint) -> Result<Vec<u8>, EcdhError> {
+        let order = &self.curve.order;
+        if key.is_zero() || key >= order {
+            return Err(EcdhError::InvalidPrivateKey);
+        }
+        Ok(key.to_le_bytes_padded(order.bits().div_ceil(8)))
+    }
+
+    /// `[key]G`, the public key sent to the peer
+    pub fn generate_exchange_key(
+        &self,
+        key: &BigUint,
+    ) -> Result<AffinePoint<FieldElement<M>>, EcdhError> {
+        let point = self.curve.mul_base(&self.scalar(key)?);
+        point.to_affine().ok_or(EcdhError::InvalidPrivateKey)
+    }
+
+    /// The big endian x-coordinate of `[key]Q` for the peer's point Q
+    pub fn shared_secret(
+        &self,
+        key: &BigUint,
+        exchange_key: &AffinePoint<FieldElement<M>>,
+    ) -> Result<Vec<u8>, EcdhError> {
+        let scalar = self.scalar(key)?;
+        let curve = &self.curve;
+        if !curve.is_on_curve(exchange_key) {
+            return Err(EcdhError::InvalidPublicKey);
+        }
+        let peer = JacobianPoint::from_affine(exchange_key);
+        let order = curve.order.to_le_bytes_padded(scalar.len());
+        if curve.cofactor != 1
+            && !curve.scalar_mult(&order, &peer).is_identity()
+        {
+            return Err(EcdhError::InvalidPublicKey);
+        }
+        let shared = curve.scalar_mult(&scalar, &peer).to_affine();
+        Ok(shared.ok_or(EcdhError::InvalidPublicKey)?.x.to_be_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hex;
+    use crate::nist::{p256, p384};
+
+    fn number(hex: &str) -> BigUint {
+        BigUint::from_hex(hex).unwrap()
+    }
+
+    /// The first P-256 and P-384 cases of the NIST CAVS 14.1 ECC CDH
+    /// primitive vectors
+    #[test]
+    fn test_cavs_vectors() {
+        let ecdh = Ecdh::new(p256());
+        let curve = p256();
+        let peer = curve
+            .decode_point(&hex(
+                "04700c48f77f56584c5cc632ca65640db91b6bacce3a4df6b42ce7cc838833d287
+                   db71e509e3fd9b060ddb20ba5c51dcc5948d46fbf640dfe0441782cab85fa4ac",
+            ))
+            .unwrap();
+        let key = number(
+            "7d7dc5f71eb29ddaf80d6214632eeae03d9058af1fb6d22ed80badb62bc1a534",
+        );
+        let public = ecdh.generate_exchange_key(&key).unwrap();
+        assert_eq!(
+            curve.encode_point(&public, false),
+            hex("04ead218590119e8876b29146ff89ca61770c4edbbf97d38ce385ed281d8a6b230
+                   28af61281fd35e2fa7002523acc85a429cb06ee6648325389f59edfce1405141")
+        );
+        assert_eq!(
+            ecdh.shared_secret(&key, &peer).unwrap(),
+            hex("46fc62106420ff012e54a434fbdd2d25ccc5852060561e68040dd7778997bd7b")
+        );
+
+        let ecdh = Ecdh::new(p384());
+        let curve = p384();
+        let peer = curve
+            .decode_point(&hex(
+                "04a7c76b970c3b5fe8b05d2838ae04ab47697b9eaf52e764592efda27fe7513272
+                   734466b400091adbf2d68c58e0c50066ac68f19f2e1cb879aed43a9969b91a08
+                   39c4c38a49749b661efedf243451915ed0905a32b060992b468c64766fc8437a",
+            ))
+            .unwrap();
+        let key = number(
+            "3cc3122a68f0d95027ad38c067916ba0eb8c38894d22e1b15618b6818a661774
+             ad463b205da88cf699ab4d43c9cf98a1",
+        );
+        let public = ecdh.generate_exchange_key(&key).unwrap();
+        assert_eq!(
+            curve.encode_point(&public, false),
+            hex("049803807f2f6d2fd966cdd0290bd410c0190352fbec7ff6247de1302df86f25d3
+                   4fe4a97bef60cff548355c015dbb3e5fba26ca69ec2f5b5d9dad20cc9da71138
+                   3a9dbe34ea3fa5a2af75b46502629ad54dd8b7d73a8abb06a3a3be47d650cc99")
+        );
+        assert_eq!(
+            ecdh.shared_secret(&key, &peer).unwrap(),
+            hex("5f9d29dc5e31a163060356213669c8ce132e22f57c9a04f40ba7fcead493b457
+                 e5621e766c40a2e3d4d6a04b25e533f1")
+        );
+    }
+
+    #[test]
+    fn test_exchange() {
+        let ecdh = Ecdh::new(p256());
+        let (alice, bob) = (number("1234567890"), number("abcdef0123456789"));
+        let alice_public = ecdh.generate_exchange_key(&alice).unwrap();
+        let bob_public = ecdh.generate_exchange_key(&bob).unwrap();
+        assert_eq!(
+            ecdh.shared_secret(&alice, &bob_public),
+            ecdh.shared_secret(&bob, &alice_public)
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_keys() {
+        let ecdh = Ecdh::new(p256());
+        let curve = p256();
+        assert_eq!(
+            ecdh.generate_exchange_key(&curve.order),
+            Err(EcdhError::InvalidPrivateKey)
+        );
+        let mut doubled = p256();
+        doubled.order = &curve.order + &curve.order;
+        assert_eq!(
+            Ecdh::new(doubled).generate_exchange_key(&curve.order),
+            Err(EcdhError::InvalidPrivateKey)
+        );
+
+        let mut off_curve = curve.base;
+        off_curve.x = off_curve.x + off_curve.y;
+        assert_eq!(
+            ecdh.shared_secret(&BigUint::one(), &off_curve),
+            Err(EcdhError::InvalidPublicKey)
+        );
+    }
+}
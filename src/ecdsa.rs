@@ -0,0 +1,638 @@
+//! ECDSA (FIPS 186-4 section 6) on the curves of [`crate::nist`] and
+//! [`crate::secp256k1`], with RFC 6979 deterministic nonces
+//!
+//! Mirrors [`crate::dsa`], with the subgroup of a [`WeierstrassCurve`]
+//! taking the place of the subgroup of the integers mod p. For Bitcoin
+//! there are also low s signatures and public key recovery (SEC1 section
+//! 4.1.6)
+
+use crate::bigint::BigUint;
+use crate::field::Field;
+use crate::hash::Digest;
+use crate::primefield::{FieldElement, Modulus};
+use crate::rfc6979::{bits2int, NonceGenerator};
+use crate::weierstrass::{AffinePoint, JacobianPoint, WeierstrassCurve};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EcdsaError {
+    /// The private key is not in `[1, n - 1]`, or `[d]G` is the identity
+    /// because n is not the base point's order
+    InvalidPrivateKey,
+    /// The public key is not a point of the base point's subgroup
+    InvalidPublicKey,
+    /// No public key can be recovered from the signature and recovery id
+    InvalidSignature,
+    /// The curve's order is not prime, so a nonce had no inverse mod n
+    InvalidCurve,
+}
+
+impl std::fmt::Display for EcdsaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::InvalidPrivateKey => "invalid private key",
+            Self::InvalidPublicKey => "invalid public key",
+            Self::InvalidSignature => "invalid signature",
+            Self::InvalidCurve => "invalid curve",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for EcdsaError {}
+
+/// A little endian scalar long enough for every value below the order
+fn scalar_bytes(scalar: &BigUint, order: &BigUint) -> Vec<u8> {
+    scalar.to_le_bytes_padded(order.bits().div_ceil(8))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+impl Signature {
+    /// Whether s is at most n / 2. Both (r, s) and (r, n - s) verify, so
+    /// Bitcoin only accepts the low one to keep signatures non-malleable
+    pub fn is_low_s(&self, order: &BigUint) -> bool {
+        self.s <= order >> 1
+    }
+
+    /// The low s one of the two signatures that verify
+    pub fn normalize_s(&self, order: &BigUint) -> Self {
+        if self.is_low_s(order) {
+            return self.clone();
+        }
+        Self {
+            r: self.r.clone(),
+            s: order - &self.s,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningKey<M: Modulus> {
+    d: BigUint,
+    verifying_key: VerifyingKey<M>,
+}
+
+impl<M: Modulus> SigningKey<M> {
+    pub fn new(
+        curve: WeierstrassCurve<FieldElement<M>>,
+        d: BigUint,
+    ) -> Result<Self, EcdsaError> {
+        if d.is_zero() || d >= curve.order {
+            return Err(EcdsaError::InvalidPrivateKey);
+        }
+        let point = curve
+            .mul_base(&scalar_bytes(&d, &curve.order))
+            .to_affine()
+            .ok_or(EcdsaError::InvalidPrivateKey)?;
+        let verifying_key = VerifyingKey::new(curve, point)?;
+        Ok(Self { d, verifying_key })
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey<M> {
+        &self.verifying_key
+    }
+
+    /// Signs `message` hashed with `D`, which also drives the RFC 6979 nonce.
+    /// s is left as computed, [`Signature::normalize_s`] or
+    /// [`SigningKey::sign_recoverable`] give the low s one
+    pub fn sign<D: Digest>(
+        &self,
+        message: &[u8],
+    ) -> Result<Signature, EcdsaError> {
+        self.sign_prehashed::<D>(&D::digest(message))
+    }
+
+    /// Signs an already computed `D` hash of the message
+    pub fn sign_prehashed<D: Digest>(
+        &self,
+        hash: &[u8],
+    ) -> Result<Signature, EcdsaError> {
+        Ok(self.sign_deterministic::<D>(hash)?.0)
+    }
+
+    /// Signs `message` with a low s and returns the recovery id
+    /// [`VerifyingKey::recover`] needs to find the public key again
+    pub fn sign_recoverable<D: Digest>(
+        &self,
+        message: &[u8],
+    ) -> Result<(Signature, u8), EcdsaError> {
+        self.sign_recoverable_prehashed::<D>(&D::digest(message))
+    }
+
+    /// [`SigningKey::sign_recoverable`] of an already computed hash
+    pub fn sign_recoverable_prehashed<D: Digest>(
+        &self,
+        hash: &[u8],
+    ) -> Result<(Signature, u8), EcdsaError> {
+        let order = &self.verifying_key.curve.order;
+        let (signature, recovery_id) = self.sign_deterministic::<D>(hash)?;
+        if signature.is_low_s(order) {
+            return Ok((signature, recovery_id));
+        }
+        // n - s is the signature for -k, whose point has the other y
+        Ok((signature.normalize_s(order), recovery_id ^ 1))
+    }
+
+    fn sign_deterministic<D: Digest>(
+        &self,
+        hash: &[u8],
+    ) -> Result<(Signature, u8), EcdsaError> {
+        let order = &self.verifying_key.curve.order;
+        let mut nonces = NonceGenerator::<D>::new(order, &self.d, hash);
+        loop {
+            if let Some(signed) =
+                self.sign_with_nonce(hash, &nonces.next_k())?
+            {
+                return Ok(signed);
+            }
+        }
+    }
+
+    /// r is the x-coordinate of `[k]G` mod n and `s = (z + rd) / k`, `None`
+    /// when either comes out zero and another k is needed. The recovery id
+    /// has the parity of `[k]G`'s y in bit 0 and whether its x is at least
+    /// n in bit 1
+    fn sign_with_nonce(
+        &self,
+        hash: &[u8],
+        k: &BigUint,
+    ) -> Result<Option<(Signature, u8)>, EcdsaError> {
+        let curve = &self.verifying_key.curve;
+        let n = &curve.order;
+        let z = bits2int(hash, n.bits());
+
+        let Some(point) = curve.mul_base(&scalar_bytes(k, n)).to_affine()
+        else {
+            return Ok(None);
+        };
+        let x = point.x.to_biguint();
+        let r = &x % n;
+        if r.is_zero() {
+            return Ok(None);
+        }
+        let k_inverse = k.mod_inverse(n).ok_or(EcdsaError::InvalidCurve)?;
+        let s = k_inverse.mul_mod(&(&z + &self.d.mul_mod(&r, n)), n);
+        if s.is_zero() {
+            return Ok(None);
+        }
+        let recovery_id = point.y.is_odd() as u8 | ((&x >= n) as u8) << 1;
+        Ok(Some((Signature { r, s }, recovery_id)))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<M: Modulus> {
+    curve: WeierstrassCurve<FieldElement<M>>,
+    point: AffinePoint<FieldElement<M>>,
+}
+
+impl<M: Modulus> VerifyingKey<M> {
+    /// Checks the point is on the curve and, for curves with a cofactor,
+    /// in the base point's subgroup
+    pub fn new(
+        curve: WeierstrassCurve<FieldElement<M>>,
+        point: AffinePoint<FieldElement<M>>,
+    ) -> Result<Self, EcdsaError> {
+        if !curve.is_on_curve(&point) {
+            return Err(EcdsaError::InvalidPublicKey);
+        }
+        if curve.cofactor != 1 {
+            let order = scalar_bytes(&curve.order, &curve.order);
+            let jacobian = JacobianPoint::from_affine(&point);
+            if !curve.scalar_mult(&order, &jacobian).is_identity() {
+                return Err(EcdsaError::InvalidPublicKey);
+            }
+        }
+        Ok(Self { curve, point })
+    }
+
+    /// Decodes a SEC1 compressed or uncompressed public key
+    pub fn from_sec1(
+        curve: WeierstrassCurve<FieldElement<M>>,
+        bytes: &[u8],
+    ) -> Result<Self, EcdsaError> {
+        let point = curve
+            .decode_point(bytes)
+            .map_err(|_| EcdsaError::InvalidPublicKey)?;
+        Self::new(curve, point)
+    }
+
+    /// The public key `signature` of `message` hashed with `D` verifies
+    /// under, picked by `recovery_id` among up to four candidates
+    pub fn recover<D: Digest>(
+        curve: WeierstrassCurve<FieldElement<M>>,
+        message: &[u8],
+        signature: &Signature,
+        recovery_id: u8,
+    ) -> Result<Self, EcdsaError> {
+        Self::recover_prehashed(
+            curve,
+            &D::digest(message),
+            signature,
+            recovery_id,
+        )
+    }
+
+    /// Rebuilds R from r and the recovery id, then `Q = [1/r]([s]R - [z]G)`
+    pub fn recover_prehashed(
+        curve: WeierstrassCurve<FieldElement<M>>,
+        hash: &[u8],
+        signature: &Signature,
+        recovery_id: u8,
+    ) -> Result<Self, EcdsaError> {
+        let n = &curve.order;
+        let Signature { r, s } = signature;
+        if recovery_id > 3 || r.is_zero() || r >= n || s.is_zero() || s >= n {
+            return Err(EcdsaError::InvalidSignature);
+        }
+        let x = if recovery_id & 2 == 0 {
+            r.clone()
+        } else {
+            r + n
+        };
+        if x >= FieldElement::<M>::modulus() {
+            return Err(EcdsaError::InvalidSignature);
+        }
+        let x = FieldElement::from_be_bytes(
+            &x.to_be_bytes_padded(FieldElement::<M>::BYTES),
+        )
+        .expect("x is below p");
+        let mut big_r = curve.lift_x(x).ok_or(EcdsaError::InvalidSignature)?;
+        if big_r.y.is_odd() != (recovery_id & 1 == 1) {
+            big_r.y = -big_r.y;
+        }
+
+        let z = &bits2int(hash, n.bits()) % n;
+        let r_inverse = r.mod_inverse(n).ok_or(EcdsaError::InvalidSignature)?;
+        let u1 = BigUint::zero().sub_mod(&z, n).mul_mod(&r_inverse, n);
+        let u2 = s.mul_mod(&r_inverse, n);
+        let sum = curve.add(
+            &curve.mul_base(&scalar_bytes(&u1, n)),
+            &curve.scalar_mult(
+                &scalar_bytes(&u2, n),
+                &JacobianPoint::from_affine(&big_r),
+            ),
+        );
+        let point = sum.to_affine().ok_or(EcdsaError::InvalidSignature)?;
+        Self::new(curve, point)
+    }
+
+    pub fn to_sec1(&self, compressed: bool) -> Vec<u8> {
+        self.curve.encode_point(&self.point, compressed)
+    }
+
+    pub fn point(&self) -> &AffinePoint<FieldElement<M>> {
+        &self.point
+    }
+
+    pub fn verify<D: Digest>(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        self.verify_prehashed(&D::digest(message), signature)
+    }
+
+    /// [`VerifyingKey::verify`] that also rejects a high s, as Bitcoin
+    /// does, so that each message and key have a single valid signature
+    /// per nonce
+    pub fn verify_low_s<D: Digest>(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        self.verify_low_s_prehashed(&D::digest(message), signature)
+    }
+
+    pub fn verify_low_s_prehashed(
+        &self,
+        hash: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        signature.is_low_s(&self.curve.order)
+            && self.verify_prehashed(hash, signature)
+    }
+
+    /// Accepts when the x-coordinate of `[z/s]G + [r/s]Q` is r mod n
+    pub fn verify_prehashed(&self, hash: &[u8], signature: &Signature) -> bool {
+        let curve = &self.curve;
+        let n = &curve.order;
+        let Signature { r, s } = signature;
+        if r.is_zero() || r >= n || s.is_zero() || s >= n {
+            return false;
+        }
+
+        let z = bits2int(hash, n.bits());
+        let Some(w) = s.mod_inverse(n) else {
+            return false;
+        };
+        let u1 = z.mul_mod(&w, n);
+        let u2 = r.mul_mod(&w, n);
+        let sum = curve.add(
+            &curve.mul_base(&scalar_bytes(&u1, n)),
+            &curve.scalar_mult(
+                &scalar_bytes(&u2, n),
+                &JacobianPoint::from_affine(&self.point),
+            ),
+        );
+        match sum.to_affine() {
+            Some(point) => &(&point.x.to_biguint() % n) == r,
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hex;
+    use crate::nist::{p256, p384, P256, P384};
+    use crate::sha2::{Sha256, Sha384};
+
+    const SIGGEN_VECTORS: &str =
+        include_str!("../test_vectors/ecdsa_siggen.rsp");
+    const P256_WYCHEPROOF: &str =
+        include_str!("../test_vectors/ecdsa_p256_wycheproof.rsp");
+    const P384_WYCHEPROOF: &str =
+        include_str!("../test_vectors/ecdsa_p384_wycheproof.rsp");
+
+    /// The `name = value` lines of each blank line separated case under
+    /// the section header `[section]`
+    fn cases<'a>(
+        vectors: &'a str,
+        section: &str,
+    ) -> Vec<Vec<(&'a str, &'a str)>> {
+        let mut cases = Vec::new();
+        let mut current = Vec::new();
+        let mut in_section = false;
+        for line in vectors.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_section = line == format!("[{section}]");
+            } else if let Some((name, value)) = line.split_once('=') {
+                if in_section {
+                    current.push((name.trim(), value.trim()));
+                }
+            } else if !current.is_empty() {
+                cases.push(std::mem::take(&mut current));
+            }
+        }
+        if !current.is_empty() {
+            cases.push(current);
+        }
+        cases
+    }
+
+    fn field<'a>(case: &[(&str, &'a str)], name: &str) -> &'a str {
+        case.iter().find(|(n, _)| *n == name).unwrap().1
+    }
+
+    fn number(case: &[(&str, &str)], name: &str) -> BigUint {
+        BigUint::from_hex(field(case, name)).unwrap()
+    }
+
+    fn uncompressed(case: &[(&str, &str)], len: usize) -> Vec<u8> {
+        let mut bytes = vec![0x04];
+        bytes.extend(number(case, "Qx").to_be_bytes_padded(len));
+        bytes.extend(number(case, "Qy").to_be_bytes_padded(len));
+        bytes
+    }
+
+    /// NIST SigGen cases, signing with the random k the vectors record
+    fn check_siggen<M: Modulus>(
+        curve: WeierstrassCurve<FieldElement<M>>,
+        section: &str,
+    ) {
+        let cases = cases(SIGGEN_VECTORS, section);
+        assert_eq!(cases.len(), 15);
+        for case in cases {
+            let key =
+                SigningKey::new(curve.clone(), number(&case, "d")).unwrap();
+            let public = key.verifying_key();
+            assert_eq!(
+                public.to_sec1(false),
+                uncompressed(&case, FieldElement::<M>::BYTES)
+            );
+
+            let hash = hex(field(&case, "H"));
+            let expected = Signature {
+                r: number(&case, "R"),
+                s: number(&case, "S"),
+            };
+            let signature = key
+                .sign_with_nonce(&hash, &number(&case, "k"))
+                .unwrap()
+                .map(|(signature, _)| signature);
+            assert_eq!(signature.as_ref(), Some(&expected));
+            assert!(public.verify_prehashed(&hash, &expected));
+
+            let mut tampered = hash.clone();
+            tampered[0] ^= 1;
+            assert!(!public.verify_prehashed(&tampered, &expected));
+        }
+    }
+
+    #[test]
+    fn test_siggen_vectors() {
+        check_siggen(p256(), "P-256,SHA-256");
+        check_siggen(p384(), "P-384,SHA-384");
+    }
+
+    /// Wycheproof cases including edge case keys, r and s near n, and
+    /// sums that hit the identity or need a doubling
+    fn check_wycheproof<M: Modulus, D: Digest>(
+        curve: WeierstrassCurve<FieldElement<M>>,
+        vectors: &str,
+        section: &str,
+        count: usize,
+    ) {
+        let cases = cases(vectors, section);
+        assert_eq!(cases.len(), count);
+        for case in cases {
+            let key = VerifyingKey::from_sec1(
+                curve.clone(),
+                &uncompressed(&case, FieldElement::<M>::BYTES),
+            )
+            .unwrap();
+            let signature = Signature {
+                r: number(&case, "R"),
+                s: number(&case, "S"),
+            };
+            let message = hex(field(&case, "Msg"));
+            assert_eq!(
+                key.verify::<D>(&message, &signature),
+                field(&case, "Result") == "P",
+                "{case:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_wycheproof_p256() {
+        check_wycheproof::<P256, Sha256>(
+            p256(),
+            P256_WYCHEPROOF,
+            "P-256,SHA-256",
+            228,
+        );
+    }
+
+    #[test]
+    fn test_wycheproof_p384() {
+        check_wycheproof::<P384, Sha384>(
+            p384(),
+            P384_WYCHEPROOF,
+            "P-384,SHA-384",
+            249,
+        );
+    }
+
+    /// RFC 6979 appendix A.2.5 and A.2.6, "sample" and "test"
+    #[test]
+    fn test_rfc6979_vectors() {
+        let key = SigningKey::new(
+            p256(),
+            BigUint::from_hex(
+                "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let signature = key.sign::<Sha256>(b"sample").unwrap();
+        assert_eq!(
+            signature,
+            Signature {
+                r: BigUint::from_hex(
+                    "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716"
+                )
+                .unwrap(),
+                s: BigUint::from_hex(
+                    "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"
+                )
+                .unwrap(),
+            }
+        );
+        assert!(key.verifying_key().verify::<Sha256>(b"sample", &signature));
+        assert!(!key.verifying_key().verify::<Sha256>(b"test", &signature));
+
+        let key = SigningKey::new(
+            p384(),
+            BigUint::from_hex(
+                "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba
+                 9aa47740787137d896d5724e4c70a825f872c9ea60d2edf5",
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        let signature = key.sign::<Sha384>(b"sample").unwrap();
+        assert_eq!(
+            signature,
+            Signature {
+                r: BigUint::from_hex(
+                    "94edbb92a5ecb8aad4736e56c691916b3f88140666ce9fa7
+                     3d64c4ea95ad133c81a648152e44acf96e36dd1e80fabe46"
+                )
+                .unwrap(),
+                s: BigUint::from_hex(
+                    "99ef4aeb15f178cea1fe40db2603138f130e740a19624526
+                     203b6351d0a3a94fa329c145786e679e7b82c71a38628ac8"
+                )
+                .unwrap(),
+            }
+        );
+        assert!(key.verifying_key().verify::<Sha384>(b"sample", &signature));
+    }
+
+    #[test]
+    fn test_invalid_keys() {
+        let curve = p256();
+        assert_eq!(
+            SigningKey::new(curve.clone(), BigUint::zero()),
+            Err(EcdsaError::InvalidPrivateKey)
+        );
+        assert_eq!(
+            SigningKey::new(curve.clone(), curve.order.clone()),
+            Err(EcdsaError::InvalidPrivateKey)
+        );
+
+        let mut off_curve = curve.base;
+        off_curve.y = -off_curve.y + off_curve.x;
+        assert_eq!(
+            VerifyingKey::new(curve.clone(), off_curve),
+            Err(EcdsaError::InvalidPublicKey)
+        );
+        assert_eq!(
+            VerifyingKey::from_sec1(curve, &[0]),
+            Err(EcdsaError::InvalidPublicKey)
+        );
+    }
+
+    #[test]
+    fn test_compressed_key_verifies() {
+        let key =
+            SigningKey::new(p256(), BigUint::from(0x1234_5678u64)).unwrap();
+        let signature = key.sign::<Sha256>(b"message").unwrap();
+        let compressed = key.verifying_key().to_sec1(true);
+        assert_eq!(compressed.len(), 33);
+        let public = VerifyingKey::from_sec1(p256(), &compressed).unwrap();
+        assert_eq!(&public, key.verifying_key());
+        assert!(public.verify::<Sha256>(b"message", &signature));
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct P1009;
+
+    impl Modulus for P1009 {
+        const LIMBS: usize = 1;
+        const P: [u64; 6] = [1009, 0, 0, 0, 0, 0];
+    }
+
+    #[test]
+    fn test_composite_order() {
+        // y^2 = x^3 + x + 1 over GF(1009), with a base point of order
+        // 1034 = 2 * 11 * 47, so only scalars prime to 1034 have inverses
+        let element = FieldElement::<P1009>::from_u64;
+        let curve = WeierstrassCurve::new(
+            element(1),
+            element(1),
+            AffinePoint {
+                x: element(52),
+                y: element(274),
+            },
+            BigUint::from(1034u64),
+            1,
+        );
+        let key = SigningKey::new(curve.clone(), BigUint::from(5u64)).unwrap();
+        let even_s = Signature {
+            r: BigUint::from(3u64),
+            s: BigUint::from(4u64),
+        };
+        assert!(!key.verifying_key().verify::<Sha256>(b"composite", &even_s));
+
+        // x = 10 is on the curve, so recovery gets as far as inverting r
+        let even_r = Signature {
+            r: BigUint::from(10u64),
+            s: BigUint::from(3u64),
+        };
+        assert_eq!(
+            VerifyingKey::recover::<Sha256>(curve, b"composite", &even_r, 0),
+            Err(EcdsaError::InvalidSignature)
+        );
+
+        let results: Vec<_> =
+            (0..32u8).map(|i| key.sign::<Sha256>(&[i])).collect();
+        assert!(results.contains(&Err(EcdsaError::InvalidCurve)));
+
+        // with twice the true order, d = n passes the range check
+        let mut doubled = p256();
+        let n = doubled.order.clone();
+        doubled.order = &n + &n;
+        assert_eq!(
+            SigningKey::new(doubled, n),
+            Err(EcdsaError::InvalidPrivateKey)
+        );
+    }
+}
@@ -0,0 +1,379 @@
+//! Ed25519 signatures (RFC 8032) on edwards25519,
+//! `-x^2 + y^2 = 1 + dx^2y^2` with `d = -121665/121666`, the twisted
+//! Edwards form of Curve25519
+//!
+//! The point arithmetic runs in constant time, the scalar arithmetic mod
+//! the group order uses [`BigUint`] and does not
+
+use crate::bigint::BigUint;
+use crate::edwards::{EdwardsCurve, EdwardsPoint};
+use crate::field25519::FieldElement;
+use crate::hash::Digest;
+use crate::sha2::Sha512;
+
+/// The compressed base point, y = 4/5 with x even
+const BASEPOINT: [u8; 32] = [
+    0x58, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+    0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ed25519Error {
+    /// The public key is not the encoding of a point on the curve
+    InvalidPublicKey,
+}
+
+impl std::fmt::Display for Ed25519Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::InvalidPublicKey => "invalid public key",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for Ed25519Error {}
+
+fn d() -> FieldElement {
+    -FieldElement::from_u64(121665) * FieldElement::from_u64(121666).invert()
+}
+
+/// The prime order of the base point,
+/// 2^252 + 27742317777372353535851937790883648493
+pub fn order() -> BigUint {
+    BigUint::from_hex(
+        "1000000000000000000000000000000014DEF9DEA2F79CD65812631A5CF5D3ED",
+    )
+    .unwrap()
+}
+
+pub fn edwards25519() -> EdwardsCurve<FieldElement> {
+    EdwardsCurve::new(
+        -FieldElement::ONE,
+        d(),
+        decompress(&BASEPOINT).unwrap(),
+        order(),
+        8,
+    )
+}
+
+/// The 32 byte encoding of RFC 8032 section 5.1.2, y with the sign of x in
+/// the top bit
+pub fn compress(point: &EdwardsPoint<FieldElement>) -> [u8; 32] {
+    let (x, y) = point.to_affine();
+    let mut bytes = y.to_bytes();
+    bytes[31] |= (x.is_negative() as u8) << 7;
+    bytes
+}
+
+/// Decoding as in RFC 8032 section 5.1.3, rejecting a y that is not fully
+/// reduced and the encoding of x = 0 with the sign bit set
+pub fn decompress(bytes: &[u8; 32]) -> Option<EdwardsPoint<FieldElement>> {
+    let sign = bytes[31] >> 7 == 1;
+    let y = FieldElement::from_bytes(bytes);
+    let mut canonical = *bytes;
+    canonical[31] &= 0x7f;
+    if y.to_bytes() != canonical {
+        return None;
+    }
+
+    let y2 = y.square();
+    let u = y2 - FieldElement::ONE;
+    let v = d() * y2 + FieldElement::ONE;
+    let mut x = FieldElement::sqrt_ratio(&u, &v)?;
+    if x.is_zero() && sign {
+        return None;
+    }
+    if x.is_negative() != sign {
+        x = -x;
+    }
+    Some(EdwardsPoint::from_affine(x, y))
+}
+
+/// SHA-512 of the concatenated `parts` as a little endian integer mod the
+/// group order
+fn hash_to_scalar(parts: &[&[u8]]) -> BigUint {
+    let mut hasher = Sha512::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    BigUint::from_le_bytes(&hasher.finalize()) % order()
+}
+
+fn scalar_bytes(scalar: &BigUint) -> [u8; 32] {
+    scalar.to_le_bytes_padded(32).try_into().unwrap()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature {
+    /// The encoded commitment point R
+    pub r: [u8; 32],
+    /// The scalar S, little endian
+    pub s: [u8; 32],
+}
+
+impl Signature {
+    pub fn from_bytes(bytes: &[u8; 64]) -> Self {
+        Self {
+            r: bytes[..32].try_into().unwrap(),
+            s: bytes[32..].try_into().unwrap(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 64] {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&self.r);
+        bytes[32..].copy_from_slice(&self.s);
+        bytes
+    }
+}
+
+pub struct SigningKey {
+    secret: [u8; 32],
+    /// The clamped lower half of the hashed secret
+    scalar: [u8; 32],
+    /// The upper half of the hashed secret, keys the nonce derivation
+    prefix: [u8; 32],
+    verifying_key: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Expands a 32 byte secret key as in RFC 8032 section 5.1.5
+    pub fn from_bytes(secret: &[u8; 32]) -> Self {
+        let hash = Sha512::digest(secret);
+        let mut scalar: [u8; 32] = hash[..32].try_into().unwrap();
+        scalar[0] &= 248;
+        scalar[31] &= 127;
+        scalar[31] |= 64;
+
+        let curve = edwards25519();
+        let point = curve.scalar_mult(&scalar, &curve.base);
+        Self {
+            secret: *secret,
+            scalar,
+            prefix: hash[32..].try_into().unwrap(),
+            verifying_key: VerifyingKey {
+                bytes: compress(&point),
+                point,
+            },
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.secret
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.verifying_key
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        let r = hash_to_scalar(&[&self.prefix, message]);
+        self.sign_with_nonce(&r, message)
+    }
+
+    /// Signs with the nonce `r`, R = [r]B
+    fn sign_with_nonce(&self, r: &BigUint, message: &[u8]) -> Signature {
+        let curve = edwards25519();
+        let big_r = compress(&curve.scalar_mult(&scalar_bytes(r), &curve.base));
+        self.finish_signature(r, big_r, message)
+    }
+
+    /// S = r + k * s where k is the hash of R, A and the message
+    fn finish_signature(
+        &self,
+        r: &BigUint,
+        big_r: [u8; 32],
+        message: &[u8],
+    ) -> Signature {
+        let k = hash_to_scalar(&[&big_r, &self.verifying_key.bytes, message]);
+        let s = BigUint::from_le_bytes(&self.scalar);
+        let big_s = (r + &(&k * &s)) % order();
+        Signature {
+            r: big_r,
+            s: scalar_bytes(&big_s),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    bytes: [u8; 32],
+    point: EdwardsPoint<FieldElement>,
+}
+
+impl VerifyingKey {
+    pub fn from_bytes(bytes: &[u8; 32]) -> Result<Self, Ed25519Error> {
+        let point = decompress(bytes).ok_or(Ed25519Error::InvalidPublicKey)?;
+        Ok(Self {
+            bytes: *bytes,
+            point,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.bytes
+    }
+
+    /// Checks `[8][S]B = [8]R + [8][k]A`, the cofactored equation RFC 8032
+    /// specifies. Honest signatures pass both this and
+    /// [`VerifyingKey::verify_cofactorless`], the two only disagree on
+    /// signatures crafted with small order components
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> bool {
+        self.check(message, signature, true)
+    }
+
+    /// Checks `[S]B = R + [k]A` without clearing the cofactor, as many
+    /// other implementations do
+    pub fn verify_cofactorless(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        self.check(message, signature, false)
+    }
+
+    fn check(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+        cofactored: bool,
+    ) -> bool {
+        let curve = edwards25519();
+        let s = BigUint::from_le_bytes(&signature.s);
+        if s >= curve.order {
+            return false;
+        }
+        let Some(r) = decompress(&signature.r) else {
+            return false;
+        };
+        let k = hash_to_scalar(&[&signature.r, &self.bytes, message]);
+
+        let lhs = curve.scalar_mult(&signature.s, &curve.base);
+        let ka = curve.scalar_mult(&scalar_bytes(&k), &self.point);
+        let rhs = curve.add(&r, &ka);
+        if cofactored {
+            curve.mul_by_cofactor(&lhs) == curve.mul_by_cofactor(&rhs)
+        } else {
+            lhs == rhs
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hex;
+
+    /// RFC 8032 section 7.1 tests 1, 2, 3 and SHA(abc)
+    const VECTORS: [(&str, &str, &str, &str); 4] = [
+        (
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555
+             fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+        ),
+        (
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da
+             085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+        ),
+        (
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac
+             18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+        ),
+        (
+            "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42",
+            "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf",
+            "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a
+             2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+            "dc2a4459e7369633a52b1bf277839a00201009a3efbf3ecb69bea2186c26b589
+             09351fc9ac90b3ecfdfbc7c66431e0303dca179c138ac17ad9bef1177331a704",
+        ),
+    ];
+
+    #[test]
+    fn test_rfc8032_vectors() {
+        for (secret, public, message, signature) in VECTORS {
+            let key = SigningKey::from_bytes(&hex(secret).try_into().unwrap());
+            assert_eq!(key.verifying_key().to_bytes().to_vec(), hex(public));
+
+            let message = hex(message);
+            let signature = hex(signature);
+            let signed = key.sign(&message);
+            assert_eq!(signed.to_bytes().to_vec(), signature);
+
+            let public =
+                VerifyingKey::from_bytes(&hex(public).try_into().unwrap())
+                    .unwrap();
+            assert!(public.verify(&message, &signed));
+            assert!(public.verify_cofactorless(&message, &signed));
+            assert!(!public.verify(b"something else", &signed));
+        }
+    }
+
+    #[test]
+    fn test_curve_parameters() {
+        assert_eq!(edwards25519().validate(), Ok(()));
+        assert_eq!(compress(&edwards25519().base), BASEPOINT);
+    }
+
+    #[test]
+    fn test_rejects_malleated_s() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut signature = key.sign(b"message");
+        let s = BigUint::from_le_bytes(&signature.s) + order();
+        signature.s = scalar_bytes(&s);
+        assert!(!key.verifying_key().verify(b"message", &signature));
+    }
+
+    #[test]
+    fn test_rejects_bad_encodings() {
+        // y = p is not reduced, y = 2 gives a non-square x^2
+        let mut p = [0xff; 32];
+        p[0] = 0xed;
+        p[31] = 0x7f;
+        assert_eq!(
+            VerifyingKey::from_bytes(&p),
+            Err(Ed25519Error::InvalidPublicKey)
+        );
+        let mut two = [0; 32];
+        two[0] = 2;
+        assert!(decompress(&two).is_none());
+        // y = 1 is the identity, x = 0 may not carry the sign bit
+        let mut identity = [0; 32];
+        identity[0] = 1;
+        assert!(decompress(&identity).unwrap().is_identity());
+        identity[31] |= 0x80;
+        assert!(decompress(&identity).is_none());
+    }
+
+    /// A commitment with a component of order 2 added passes the
+    /// cofactored check but not the cofactorless one
+    #[test]
+    fn test_cofactored_verification() {
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let curve = edwards25519();
+        let r = BigUint::from(12345u64);
+        let torsion =
+            EdwardsPoint::from_affine(FieldElement::ZERO, -FieldElement::ONE);
+        let commitment = curve
+            .add(&curve.scalar_mult(&scalar_bytes(&r), &curve.base), &torsion);
+        let signature =
+            key.finish_signature(&r, compress(&commitment), b"message");
+
+        let public = key.verifying_key();
+        assert!(public.verify(b"message", &signature));
+        assert!(!public.verify_cofactorless(b"message", &signature));
+
+        let honest = key.sign_with_nonce(&r, b"message");
+        assert!(public.verify_cofactorless(b"message", &honest));
+    }
+}
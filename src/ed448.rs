@@ -0,0 +1,477 @@
+//! Ed448 and Ed448ph signatures (RFC 8032) on edwards448,
+//! `x^2 + y^2 = 1 - 39081x^2y^2`, the Edwards curve 4-isogenous to
+//! Curve448
+//!
+//! Both variants hash with SHAKE256 and bind an optional context string of
+//! up to 255 bytes into every signature. Ed448ph signs SHAKE256(M, 64)
+//! rather than M itself. As in [`crate::ed25519`] the point arithmetic runs
+//! in constant time and the scalar arithmetic does not
+
+use crate::bigint::BigUint;
+use crate::edwards::{EdwardsCurve, EdwardsPoint};
+use crate::field448::FieldElement;
+use crate::sha3::Shake256;
+
+/// The compressed base point, x is even
+const BASEPOINT: [u8; 57] = [
+    0x14, 0xfa, 0x30, 0xf2, 0x5b, 0x79, 0x08, 0x98, 0xad, 0xc8, 0xd7, 0x4e,
+    0x2c, 0x13, 0xbd, 0xfd, 0xc4, 0x39, 0x7c, 0xe6, 0x1c, 0xff, 0xd3, 0x3a,
+    0xd7, 0xc2, 0xa0, 0x05, 0x1e, 0x9c, 0x78, 0x87, 0x40, 0x98, 0xa3, 0x6c,
+    0x73, 0x73, 0xea, 0x4b, 0x62, 0xc7, 0xc9, 0x56, 0x37, 0x20, 0x76, 0x88,
+    0x24, 0xbc, 0xb6, 0x6e, 0x71, 0x46, 0x3f, 0x69, 0x00,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ed448Error {
+    /// The public key is not the encoding of a point on the curve
+    InvalidPublicKey,
+    /// The context string is longer than 255 bytes
+    ContextTooLong,
+}
+
+impl std::fmt::Display for Ed448Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::InvalidPublicKey => "invalid public key",
+            Self::ContextTooLong => "context longer than 255 bytes",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for Ed448Error {}
+
+fn d() -> FieldElement {
+    -FieldElement::from_u64(39081)
+}
+
+/// The prime order of the base point, the same as that of the Curve448
+/// base point
+pub fn order() -> BigUint {
+    BigUint::from_hex(
+        "3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF
+         7CCA23E9C44EDB49AED63690216CC2728DC58F552378C292AB5844F3",
+    )
+    .unwrap()
+}
+
+pub fn edwards448() -> EdwardsCurve<FieldElement> {
+    EdwardsCurve::new(
+        FieldElement::ONE,
+        d(),
+        decompress(&BASEPOINT).unwrap(),
+        order(),
+        4,
+    )
+}
+
+/// The 57 byte encoding of RFC 8032 section 5.2.2, y in the first 56 bytes
+/// and the sign of x in the top bit of the last
+pub fn compress(point: &EdwardsPoint<FieldElement>) -> [u8; 57] {
+    let (x, y) = point.to_affine();
+    let mut bytes = [0u8; 57];
+    bytes[..56].copy_from_slice(&y.to_bytes());
+    bytes[56] = (x.is_negative() as u8) << 7;
+    bytes
+}
+
+/// Decoding as in RFC 8032 section 5.2.3, rejecting a y that is not fully
+/// reduced, stray bits in the last byte and x = 0 with the sign bit set
+pub fn decompress(bytes: &[u8; 57]) -> Option<EdwardsPoint<FieldElement>> {
+    if bytes[56] & 0x7f != 0 {
+        return None;
+    }
+    let sign = bytes[56] >> 7 == 1;
+    let y_bytes: [u8; 56] = bytes[..56].try_into().unwrap();
+    let y = FieldElement::from_bytes(&y_bytes);
+    if y.to_bytes() != y_bytes {
+        return None;
+    }
+
+    let y2 = y.square();
+    let u = y2 - FieldElement::ONE;
+    let v = d() * y2 - FieldElement::ONE;
+    let mut x = FieldElement::sqrt_ratio(&u, &v)?;
+    if x.is_zero() && sign {
+        return None;
+    }
+    if x.is_negative() != sign {
+        x = -x;
+    }
+    Some(EdwardsPoint::from_affine(x, y))
+}
+
+/// The dom4 prefix of RFC 8032 section 5.2, separating Ed448 from Ed448ph
+/// and one context from another
+fn dom4(prehashed: bool, context: &[u8]) -> Result<Vec<u8>, Ed448Error> {
+    let length =
+        u8::try_from(context.len()).map_err(|_| Ed448Error::ContextTooLong)?;
+    let mut dom = b"SigEd448".to_vec();
+    dom.push(prehashed as u8);
+    dom.push(length);
+    dom.extend_from_slice(context);
+    Ok(dom)
+}
+
+/// SHAKE256 of the concatenated `parts` to 114 bytes, as a little endian
+/// integer mod the group order
+fn hash_to_scalar(parts: &[&[u8]]) -> BigUint {
+    let mut shake = Shake256::new();
+    for part in parts {
+        shake.update(part);
+    }
+    BigUint::from_le_bytes(&shake.finalize(114)) % order()
+}
+
+fn scalar_bytes(scalar: &BigUint) -> [u8; 57] {
+    scalar.to_le_bytes_padded(57).try_into().unwrap()
+}
+
+/// The 64 byte SHAKE256 digest Ed448ph signs in place of the message
+fn prehash(message: &[u8]) -> Vec<u8> {
+    Shake256::digest(message, 64)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature {
+    /// The encoded commitment point R
+    pub r: [u8; 57],
+    /// The scalar S, little endian
+    pub s: [u8; 57],
+}
+
+impl Signature {
+    pub fn from_bytes(bytes: &[u8; 114]) -> Self {
+        Self {
+            r: bytes[..57].try_into().unwrap(),
+            s: bytes[57..].try_into().unwrap(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 114] {
+        let mut bytes = [0u8; 114];
+        bytes[..57].copy_from_slice(&self.r);
+        bytes[57..].copy_from_slice(&self.s);
+        bytes
+    }
+}
+
+pub struct SigningKey {
+    secret: [u8; 57],
+    /// The clamped lower half of the hashed secret
+    scalar: [u8; 57],
+    /// The upper half of the hashed secret, keys the nonce derivation
+    prefix: [u8; 57],
+    verifying_key: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Expands a 57 byte secret key as in RFC 8032 section 5.2.5
+    pub fn from_bytes(secret: &[u8; 57]) -> Self {
+        let hash = Shake256::digest(secret, 114);
+        let mut scalar: [u8; 57] = hash[..57].try_into().unwrap();
+        scalar[0] &= 252;
+        scalar[55] |= 128;
+        scalar[56] = 0;
+
+        let curve = edwards448();
+        let point = curve.scalar_mult(&scalar, &curve.base);
+        Self {
+            secret: *secret,
+            scalar,
+            prefix: hash[57..].try_into().unwrap(),
+            verifying_key: VerifyingKey {
+                bytes: compress(&point),
+                point,
+            },
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 57] {
+        self.secret
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.verifying_key
+    }
+
+    /// Ed448 signature of `message` under `context`, which is empty unless
+    /// the protocol calls for one
+    pub fn sign(
+        &self,
+        message: &[u8],
+        context: &[u8],
+    ) -> Result<Signature, Ed448Error> {
+        Ok(self.sign_with_dom(&dom4(false, context)?, message))
+    }
+
+    /// Ed448ph signature, the message is hashed first so it can be
+    /// streamed through SHAKE256 by the caller's side of the protocol
+    pub fn sign_ph(
+        &self,
+        message: &[u8],
+        context: &[u8],
+    ) -> Result<Signature, Ed448Error> {
+        Ok(self.sign_with_dom(&dom4(true, context)?, &prehash(message)))
+    }
+
+    /// r from the prefix and message, R = [r]B and S = r + k * s where k
+    /// is the hash of R, A and the message
+    fn sign_with_dom(&self, dom: &[u8], message: &[u8]) -> Signature {
+        let curve = edwards448();
+        let r = hash_to_scalar(&[dom, &self.prefix, message]);
+        let big_r =
+            compress(&curve.scalar_mult(&scalar_bytes(&r), &curve.base));
+        let k =
+            hash_to_scalar(&[dom, &big_r, &self.verifying_key.bytes, message]);
+        let s = BigUint::from_le_bytes(&self.scalar);
+        let big_s = (&r + &(&k * &s)) % order();
+        Signature {
+            r: big_r,
+            s: scalar_bytes(&big_s),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    bytes: [u8; 57],
+    point: EdwardsPoint<FieldElement>,
+}
+
+impl VerifyingKey {
+    pub fn from_bytes(bytes: &[u8; 57]) -> Result<Self, Ed448Error> {
+        let point = decompress(bytes).ok_or(Ed448Error::InvalidPublicKey)?;
+        Ok(Self {
+            bytes: *bytes,
+            point,
+        })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 57] {
+        self.bytes
+    }
+
+    /// Checks an Ed448 signature with the cofactored equation
+    /// `[4][S]B = [4]R + [4][k]A`. A context over 255 bytes never verifies
+    pub fn verify(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        match dom4(false, context) {
+            Ok(dom) => self.check(&dom, message, signature),
+            Err(_) => false,
+        }
+    }
+
+    /// Checks an Ed448ph signature, see [`VerifyingKey::verify`]
+    pub fn verify_ph(
+        &self,
+        message: &[u8],
+        context: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        match dom4(true, context) {
+            Ok(dom) => self.check(&dom, &prehash(message), signature),
+            Err(_) => false,
+        }
+    }
+
+    fn check(&self, dom: &[u8], message: &[u8], signature: &Signature) -> bool {
+        let curve = edwards448();
+        let s = BigUint::from_le_bytes(&signature.s);
+        if s >= curve.order {
+            return false;
+        }
+        let Some(r) = decompress(&signature.r) else {
+            return false;
+        };
+        let k = hash_to_scalar(&[dom, &signature.r, &self.bytes, message]);
+
+        let lhs = curve.scalar_mult(&signature.s, &curve.base);
+        let ka = curve.scalar_mult(&scalar_bytes(&k), &self.point);
+        let rhs = curve.add(&r, &ka);
+        curve.mul_by_cofactor(&lhs) == curve.mul_by_cofactor(&rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hex;
+
+    /// RFC 8032 section 7.4, the empty, 1 octet, 1 octet with context and
+    /// 11 octet tests
+    const VECTORS: [(&str, &str, &str, &str, &str); 4] = [
+        (
+            "6c82a562cb808d10d632be89c8513ebf6c929f34ddfa8c9f63c9960ef6e348a3
+             528c8a3fcc2f044e39a3fc5b94492f8f032e7549a20098f95b",
+            "5fd7449b59b461fd2ce787ec616ad46a1da1342485a70e1f8a0ea75d80e96778
+             edf124769b46c7061bd6783df1e50f6cd1fa1abeafe8256180",
+            "",
+            "",
+            "533a37f6bbe457251f023c0d88f976ae2dfb504a843e34d2074fd823d41a591f
+             2b233f034f628281f2fd7a22ddd47d7828c59bd0a21bfd3980ff0d2028d4b18a
+             9df63e006c5d1c2d345b925d8dc00b4104852db99ac5c7cdda8530a113a0f4db
+             b61149f05a7363268c71d95808ff2e652600",
+        ),
+        (
+            "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463a
+             fbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+            "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c086
+             6aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+            "03",
+            "",
+            "26b8f91727bd62897af15e41eb43c377efb9c610d48f2335cb0bd0087810f435
+             2541b143c4b981b7e18f62de8ccdf633fc1bf037ab7cd779805e0dbcc0aae1cb
+             cee1afb2e027df36bc04dcecbf154336c19f0af7e0a6472905e799f1953d2a0f
+             f3348ab21aa4adafd1d234441cf807c03a00",
+        ),
+        (
+            "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463a
+             fbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+            "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c086
+             6aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+            "03",
+            "666f6f",
+            "d4f8f6131770dd46f40867d6fd5d5055de43541f8c5e35abbcd001b32a89f7d2
+             151f7647f11d8ca2ae279fb842d607217fce6e042f6815ea000c85741de5c8da
+             1144a6a1aba7f96de42505d7a7298524fda538fccbbb754f578c1cad10d54d0d
+             5428407e85dcbc98a49155c13764e66c3c00",
+        ),
+        (
+            "cd23d24f714274e744343237b93290f511f6425f98e64459ff203e8985083ffd
+             f60500553abc0e05cd02184bdb89c4ccd67e187951267eb328",
+            "dcea9e78f35a1bf3499a831b10b86c90aac01cd84b67a0109b55a36e9328b1e3
+             65fce161d71ce7131a543ea4cb5f7e9f1d8b00696447001400",
+            "0c3e544074ec63b0265e0c",
+            "",
+            "1f0a8888ce25e8d458a21130879b840a9089d999aaba039eaf3e3afa090a09d3
+             89dba82c4ff2ae8ac5cdfb7c55e94d5d961a29fe0109941e00b8dbdeea6d3b05
+             1068df7254c0cdc129cbe62db2dc957dbb47b51fd3f213fb8698f064774250a5
+             028961c9bf8ffd973fe5d5c206492b140e00",
+        ),
+    ];
+
+    #[test]
+    fn test_rfc8032_vectors() {
+        for (secret, public, message, context, signature) in VECTORS {
+            let key = SigningKey::from_bytes(&hex(secret).try_into().unwrap());
+            assert_eq!(key.verifying_key().to_bytes().to_vec(), hex(public));
+
+            let (message, context) = (hex(message), hex(context));
+            let signed = key.sign(&message, &context).unwrap();
+            assert_eq!(signed.to_bytes().to_vec(), hex(signature));
+
+            let public =
+                VerifyingKey::from_bytes(&hex(public).try_into().unwrap())
+                    .unwrap();
+            assert!(public.verify(&message, &context, &signed));
+            assert!(!public.verify(b"something else", &context, &signed));
+            assert!(!public.verify(&message, b"bar", &signed));
+            assert!(!public.verify_ph(&message, &context, &signed));
+        }
+    }
+
+    /// RFC 8032 section 7.5, "abc" without and with the context "foo"
+    #[test]
+    fn test_rfc8032_prehashed_vectors() {
+        let key = SigningKey::from_bytes(
+            &hex(
+                "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42
+                 ef7822e0d5104127dc05d6dbefde69e3ab2cec7c867c6e2c49",
+            )
+            .try_into()
+            .unwrap(),
+        );
+        assert_eq!(
+            key.verifying_key().to_bytes().to_vec(),
+            hex(
+                "259b71c19f83ef77a7abd26524cbdb3161b590a48f7d17de3ee0ba9c52beb743
+                 c09428a131d6b1b57303d90d8132c276d5ed3d5d01c0f53880"
+            )
+        );
+
+        let cases = [
+            (
+                &b""[..],
+                "822f6901f7480f3d5f562c592994d9693602875614483256505600bbc281ae38
+                 1f54d6bce2ea911574932f52a4e6cadd78769375ec3ffd1b801a0d9b3f4030cd
+                 433964b6457ea39476511214f97469b57dd32dbc560a9a94d00bff07620464a3
+                 ad203df7dc7ce360c3cd3696d9d9fab90f00",
+            ),
+            (
+                &b"foo"[..],
+                "c32299d46ec8ff02b54540982814dce9a05812f81962b649d528095916a2aa48
+                 1065b1580423ef927ecf0af5888f90da0f6a9a85ad5dc3f280d91224ba9911a3
+                 653d00e484e2ce232521481c8658df304bb7745a73514cdb9bf3e15784ab7128
+                 4f8d0704a608c54a6b62d97beb511d132100",
+            ),
+        ];
+        for (context, signature) in cases {
+            let signed = key.sign_ph(b"abc", context).unwrap();
+            assert_eq!(signed.to_bytes().to_vec(), hex(signature));
+            let public = key.verifying_key();
+            assert!(public.verify_ph(b"abc", context, &signed));
+            assert!(!public.verify(b"abc", context, &signed));
+        }
+    }
+
+    #[test]
+    fn test_curve_parameters() {
+        assert_eq!(edwards448().validate(), Ok(()));
+        assert_eq!(compress(&edwards448().base), BASEPOINT);
+    }
+
+    #[test]
+    fn test_context_too_long() {
+        let key = SigningKey::from_bytes(&[1; 57]);
+        assert_eq!(
+            key.sign(b"message", &[0; 256]),
+            Err(Ed448Error::ContextTooLong)
+        );
+        assert_eq!(
+            key.sign_ph(b"message", &[0; 256]),
+            Err(Ed448Error::ContextTooLong)
+        );
+        let signature = key.sign(b"message", &[0; 255]).unwrap();
+        assert!(key
+            .verifying_key()
+            .verify(b"message", &[0; 255], &signature));
+        assert!(!key
+            .verifying_key()
+            .verify(b"message", &[0; 256], &signature));
+    }
+
+    #[test]
+    fn test_rejects_malleated_s() {
+        let key = SigningKey::from_bytes(&[7; 57]);
+        let mut signature = key.sign(b"message", b"").unwrap();
+        let s = BigUint::from_le_bytes(&signature.s) + order();
+        signature.s = scalar_bytes(&s);
+        assert!(!key.verifying_key().verify(b"message", b"", &signature));
+    }
+
+    #[test]
+    fn test_rejects_bad_encodings() {
+        // y = p is not reduced
+        let mut p = [0xff; 57];
+        p[28] = 0xfe;
+        p[56] = 0;
+        assert_eq!(
+            VerifyingKey::from_bytes(&p),
+            Err(Ed448Error::InvalidPublicKey)
+        );
+        // y = 1 is the identity, the last byte may only hold the sign bit
+        // and x = 0 may not carry it
+        let mut identity = [0; 57];
+        identity[0] = 1;
+        assert!(decompress(&identity).unwrap().is_identity());
+        identity[56] = 0x01;
+        assert!(decompress(&identity).is_none());
+        identity[56] = 0x80;
+        assert!(decompress(&identity).is_none());
+    }
+}
@@ -0,0 +1,281 @@
+//! Twisted Edwards curves `ax^2 + y^2 = 1 + dx^2y^2` over any [`Field`],
+//! the form Ed25519 and Ed448 sign on
+//!
+//! Points are kept in extended coordinates (X:Y:Z:T) with x = X/Z,
+//! y = Y/Z and xy = T/Z. With `a` a square and `d` a non-square, as for
+//! both standard curves, the addition law is complete: the same formula
+//! handles doubling, the identity and every other pair of points
+
+use crate::bigint::BigUint;
+use crate::field::Field;
+use crate::montgomery::{within_hasse_bound, CurveError};
+use std::ops::Neg;
+
+#[derive(Clone, Copy, Debug)]
+pub struct EdwardsPoint<F: Field> {
+    pub x: F,
+    pub y: F,
+    pub z: F,
+    pub t: F,
+}
+
+impl<F: Field> EdwardsPoint<F> {
+    pub const IDENTITY: Self = Self {
+        x: F::ZERO,
+        y: F::ONE,
+        z: F::ONE,
+        t: F::ZERO,
+    };
+
+    pub fn from_affine(x: F, y: F) -> Self {
+        Self {
+            x,
+            y,
+            z: F::ONE,
+            t: x * y,
+        }
+    }
+
+    pub fn to_affine(&self) -> (F, F) {
+        let z_inv = self.z.invert();
+        (self.x * z_inv, self.y * z_inv)
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.x.is_zero() && self.y == self.z
+    }
+
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        F::conditional_swap(&mut a.x, &mut b.x, choice);
+        F::conditional_swap(&mut a.y, &mut b.y, choice);
+        F::conditional_swap(&mut a.z, &mut b.z, choice);
+        F::conditional_swap(&mut a.t, &mut b.t, choice);
+    }
+}
+
+/// Projective equality, `X1 Z2 = X2 Z1` and `Y1 Z2 = Y2 Z1`
+impl<F: Field> PartialEq for EdwardsPoint<F> {
+    fn eq(&self, other: &Self) -> bool {
+        self.x * other.z == other.x * self.z
+            && self.y * other.z == other.y * self.z
+    }
+}
+
+impl<F: Field> Eq for EdwardsPoint<F> {}
+
+impl<F: Field> Neg for EdwardsPoint<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            x: -self.x,
+            t: -self.t,
+            ..self
+        }
+    }
+}
+
+/// The curve `ax^2 + y^2 = 1 + dx^2y^2` with a base point of order `order`
+/// generating a subgroup of index `cofactor`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdwardsCurve<F: Field> {
+    pub a: F,
+    pub d: F,
+    pub base: EdwardsPoint<F>,
+    pub order: BigUint,
+    /// A power of two for every curve in use, see
+    /// [`EdwardsCurve::mul_by_cofactor`]
+    pub cofactor: u64,
+}
+
+impl<F: Field> EdwardsCurve<F> {
+    pub fn new(
+        a: F,
+        d: F,
+        base: EdwardsPoint<F>,
+        order: BigUint,
+        cofactor: u64,
+    ) -> Self {
+        Self {
+            a,
+            d,
+            base,
+            order,
+            cofactor,
+        }
+    }
+
+    /// Checks the curve is non-singular, the base point is on it and has
+    /// the stated order, and the implied group order is within the Hasse
+    /// bound
+    pub fn validate(&self) -> Result<(), CurveError> {
+        if self.a.is_zero() || self.d.is_zero() || self.a == self.d {
+            return Err(CurveError::Singular);
+        }
+        let (x, y) = self.base.to_affine();
+        if !self.is_on_curve(x, y) {
+            return Err(CurveError::BaseNotOnCurve);
+        }
+
+        let scalar = self.order.to_le_bytes_padded(self.order.bits() / 8 + 1);
+        let points = &self.order * &BigUint::from(self.cofactor);
+        if self.order.is_zero()
+            || !self.scalar_mult(&scalar, &self.base).is_identity()
+            || !within_hasse_bound::<F>(&points)
+        {
+            return Err(CurveError::InvalidOrder);
+        }
+        Ok(())
+    }
+
+    pub fn is_on_curve(&self, x: F, y: F) -> bool {
+        let (x2, y2) = (x.square(), y.square());
+        self.a * x2 + y2 == F::ONE + self.d * x2 * y2
+    }
+
+    /// The unified addition formula of Hisil et al., complete for the
+    /// curves described above
+    pub fn add(
+        &self,
+        p: &EdwardsPoint<F>,
+        q: &EdwardsPoint<F>,
+    ) -> EdwardsPoint<F> {
+        let a = p.x * q.x;
+        let b = p.y * q.y;
+        let c = self.d * p.t * q.t;
+        let d = p.z * q.z;
+        let e = (p.x + p.y) * (q.x + q.y) - a - b;
+        let f = d - c;
+        let g = d + c;
+        let h = b - self.a * a;
+        EdwardsPoint {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    pub fn double(&self, p: &EdwardsPoint<F>) -> EdwardsPoint<F> {
+        let a = p.x.square();
+        let b = p.y.square();
+        let c = p.z.square() + p.z.square();
+        let d = self.a * a;
+        let e = (p.x + p.y).square() - a - b;
+        let g = d + b;
+        let f = g - c;
+        let h = d - b;
+        EdwardsPoint {
+            x: e * f,
+            y: g * h,
+            z: f * g,
+            t: e * h,
+        }
+    }
+
+    /// Montgomery ladder over every bit of the little endian `scalar`. As
+    /// addition is complete the ladder needs no special cases and its
+    /// running time depends on the scalar's length but not its value
+    pub fn scalar_mult(
+        &self,
+        scalar: &[u8],
+        point: &EdwardsPoint<F>,
+    ) -> EdwardsPoint<F> {
+        let mut r0 = EdwardsPoint::IDENTITY;
+        let mut r1 = *point;
+        let mut swap = 0;
+
+        for i in (0..scalar.len() * 8).rev() {
+            let bit = (scalar[i / 8] >> (i % 8)) & 1;
+            swap ^= bit;
+            EdwardsPoint::conditional_swap(&mut r0, &mut r1, swap);
+            swap = bit;
+
+            r1 = self.add(&r0, &r1);
+            r0 = self.double(&r0);
+        }
+        EdwardsPoint::conditional_swap(&mut r0, &mut r1, swap);
+        r0
+    }
+
+    /// Multiplies by the cofactor with repeated doubling, which is why it
+    /// must be a power of two
+    pub fn mul_by_cofactor(&self, point: &EdwardsPoint<F>) -> EdwardsPoint<F> {
+        assert!(self.cofactor.is_power_of_two());
+        let mut result = *point;
+        for _ in 0..self.cofactor.trailing_zeros() {
+            result = self.double(&result);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::SmallField;
+
+    type F13 = SmallField<13>;
+
+    /// `x^2 + y^2 = 1 + 2x^2y^2` over GF(13), 2 is not a square mod 13 so
+    /// the addition law is complete. It has 8 points and (1, 0) has order 4
+    fn toy_curve() -> EdwardsCurve<F13> {
+        EdwardsCurve::new(
+            F13::ONE,
+            F13::from_u64(2),
+            EdwardsPoint::from_affine(F13::ONE, F13::ZERO),
+            BigUint::from(4u64),
+            2,
+        )
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(toy_curve().validate(), Ok(()));
+
+        let mut singular = toy_curve();
+        singular.d = F13::ONE;
+        assert_eq!(singular.validate(), Err(CurveError::Singular));
+
+        let mut off_curve = toy_curve();
+        off_curve.base = EdwardsPoint::from_affine(F13::ONE, F13::ONE);
+        assert_eq!(off_curve.validate(), Err(CurveError::BaseNotOnCurve));
+
+        let mut wrong_order = toy_curve();
+        wrong_order.order = BigUint::from(2u64);
+        wrong_order.cofactor = 4;
+        assert_eq!(wrong_order.validate(), Err(CurveError::InvalidOrder));
+    }
+
+    #[test]
+    fn test_group_law() {
+        let curve = toy_curve();
+        let (x, y) = (F13::ONE, F13::ZERO);
+        let p = curve.base;
+
+        // (1, 0) doubles to (0, -1), the point of order 2
+        let doubled = curve.double(&p);
+        assert_eq!(doubled.to_affine(), (F13::ZERO, -F13::ONE));
+        assert_eq!(curve.add(&p, &p), doubled);
+        assert!(curve.add(&p, &-p).is_identity());
+        assert_eq!(curve.add(&p, &EdwardsPoint::IDENTITY), p);
+        assert_eq!(curve.scalar_mult(&[3], &p).to_affine(), (-x, y));
+        assert!(curve.scalar_mult(&[4], &p).is_identity());
+        assert_eq!(curve.mul_by_cofactor(&p), doubled);
+
+        // every point found by search sums to the identity with its
+        // negation and lies on the curve after doubling
+        let points: Vec<_> = (0..13)
+            .flat_map(|x| (0..13).map(move |y| (x, y)))
+            .map(|(x, y)| (F13::from_u64(x), F13::from_u64(y)))
+            .filter(|&(x, y)| curve.is_on_curve(x, y))
+            .collect();
+        assert_eq!(points.len(), 8);
+        for (x, y) in points {
+            let point = EdwardsPoint::from_affine(x, y);
+            let (dx, dy) = curve.double(&point).to_affine();
+            assert!(curve.is_on_curve(dx, dy));
+            assert!(curve.add(&point, &-point).is_identity());
+        }
+    }
+}
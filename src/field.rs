@@ -0,0 +1,203 @@
+//! The prime field interface the curve code is generic over, and a toy
+//! implementation for fields small enough to check by hand
+
+use crate::bigint::BigUint;
+use crate::math::PowMod;
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Arithmetic in GF(p). Implementations are expected to keep
+/// `conditional_swap` free of branches on secret data
+pub trait Field:
+    Copy
+    + Eq
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// The characteristic p
+    fn modulus() -> BigUint;
+
+    fn from_u64(value: u64) -> Self;
+
+    /// The inverse of a non-zero element, zero maps to zero
+    fn invert(&self) -> Self;
+
+    /// Swaps `a` and `b` if `choice` is 1 and leaves them if it is 0
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8);
+
+    fn square(&self) -> Self {
+        *self * *self
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == Self::ZERO
+    }
+
+    /// Square and multiply, variable time in `exponent`
+    fn pow(&self, exponent: &BigUint) -> Self {
+        let mut result = Self::ONE;
+        for i in (0..exponent.bits()).rev() {
+            result = result.square();
+            if exponent.bit(i) {
+                result = result * *self;
+            }
+        }
+        result
+    }
+
+    /// Euler's criterion, zero counts as a square
+    fn is_square(&self) -> bool {
+        let exponent = (Self::modulus() - BigUint::one()) >> 1;
+        let symbol = self.pow(&exponent);
+        symbol == Self::ZERO || symbol == Self::ONE
+    }
+
+    /// A square root by Tonelli-Shanks, or `None` if there is none. Either
+    /// root may come back. Variable time, though for p = 3 (mod 4) it
+    /// reduces to the single exponentiation `self^((p + 1) / 4)`
+    fn sqrt(&self) -> Option<Self> {
+        if !self.is_square() {
+            return None;
+        }
+        let p_minus_one = Self::modulus() - BigUint::one();
+        let two_adicity = (0..).find(|&i| p_minus_one.bit(i)).unwrap();
+        let odd = &p_minus_one >> two_adicity;
+
+        let mut non_square = Self::from_u64(2);
+        while non_square.is_square() {
+            non_square = non_square + Self::ONE;
+        }
+
+        let mut m = two_adicity;
+        let mut c = non_square.pow(&odd);
+        let mut t = self.pow(&odd);
+        let mut root = self.pow(&(&(&odd + &BigUint::one()) >> 1));
+        while !t.is_zero() && t != Self::ONE {
+            // the least i with t^(2^i) = 1, always below m
+            let mut i = 0;
+            let mut t_pow = t;
+            while t_pow != Self::ONE {
+                t_pow = t_pow.square();
+                i += 1;
+            }
+            let mut b = c;
+            for _ in 0..m - i - 1 {
+                b = b.square();
+            }
+            m = i;
+            c = b.square();
+            t = t * c;
+            root = root * b;
+        }
+        Some(root)
+    }
+}
+
+/// GF(P) for a prime `P` below 2^32, for toy curves
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SmallField<const P: u64>(u64);
+
+impl<const P: u64> SmallField<P> {
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
+
+impl<const P: u64> Field for SmallField<P> {
+    const ZERO: Self = Self(0);
+    const ONE: Self = Self(1);
+
+    fn modulus() -> BigUint {
+        BigUint::from(P)
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Self(value % P)
+    }
+
+    fn invert(&self) -> Self {
+        Self(self.0.pow_mod(P - 2, P))
+    }
+
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        let t = 0u64.wrapping_sub(choice as u64) & (a.0 ^ b.0);
+        a.0 ^= t;
+        b.0 ^= t;
+    }
+}
+
+impl<const P: u64> Add for SmallField<P> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self((self.0 + other.0) % P)
+    }
+}
+
+impl<const P: u64> Sub for SmallField<P> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self((self.0 + P - other.0) % P)
+    }
+}
+
+impl<const P: u64> Mul for SmallField<P> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self(self.0 * other.0 % P)
+    }
+}
+
+impl<const P: u64> Neg for SmallField<P> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self((P - self.0) % P)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type F97 = SmallField<97>;
+
+    #[test]
+    fn test_small_field() {
+        let x = F97::from_u64(200);
+        assert_eq!(x.value(), 6);
+        assert_eq!((x * x.invert()).value(), 1);
+        assert_eq!((-x + x), F97::ZERO);
+        assert_eq!((F97::ZERO - F97::ONE).value(), 96);
+
+        // the squares mod 97 are exactly the values whose Legendre symbol
+        // is not -1
+        let squares: Vec<u64> = (0..97).map(|y| y * y % 97).collect();
+        for v in 0..97 {
+            assert_eq!(F97::from_u64(v).is_square(), squares.contains(&v));
+        }
+    }
+
+    /// 97 - 1 = 3 * 2^5 exercises the full Tonelli-Shanks loop, 103 is
+    /// 3 (mod 4)
+    #[test]
+    fn test_sqrt() {
+        fn check<const P: u64>() {
+            for v in 0..P {
+                let x = SmallField::<P>::from_u64(v);
+                let squared = x.sqrt().map(|root| root.square());
+                assert_eq!(squared, x.is_square().then_some(x));
+            }
+        }
+        check::<97>();
+        check::<103>();
+    }
+}
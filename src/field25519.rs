@@ -0,0 +1,381 @@
+//! Arithmetic in GF(2^255 - 19), the field under Curve25519 and Ed25519
+//!
+//! Nothing here branches on or indexes by the value of an element, so the
+//! timing of an operation does not depend on the secrets it handles
+
+use crate::bigint::BigUint;
+use crate::field::Field;
+use std::ops::{Add, Mul, Neg, Sub};
+
+const MASK: u64 = (1 << 51) - 1;
+
+/// An element as five 51 bit limbs, least significant first. Limbs may
+/// run a few bits over 51 between operations, [`FieldElement::to_bytes`]
+/// gives the canonical form
+#[derive(Clone, Copy, Debug)]
+pub struct FieldElement([u64; 5]);
+
+impl FieldElement {
+    pub const ZERO: Self = Self([0; 5]);
+    pub const ONE: Self = Self([1, 0, 0, 0, 0]);
+    /// 2^((p - 1) / 4), a square root of -1
+    pub const SQRT_M1: Self = Self([
+        1718705420411056,
+        234908883556509,
+        2233514472574048,
+        2117202627021982,
+        765476049583133,
+    ]);
+
+    pub fn from_u64(value: u64) -> Self {
+        Self::carry([value & MASK, value >> 51, 0, 0, 0])
+    }
+
+    /// Decodes a little endian element, ignoring the top bit as RFC 7748
+    /// asks. Values from p to 2^255 - 1 are accepted and reduced
+    pub fn from_bytes(bytes: &[u8; 32]) -> Self {
+        let load =
+            |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+        Self([
+            load(0) & MASK,
+            (load(6) >> 3) & MASK,
+            (load(12) >> 6) & MASK,
+            (load(19) >> 1) & MASK,
+            (load(24) >> 12) & MASK,
+        ])
+    }
+
+    /// The canonical little endian encoding, fully reduced mod p
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut limbs = Self::carry(self.0).0;
+
+        // limbs < 2^255 + small, subtract p once if the value is >= p by
+        // checking whether adding 19 carries out of bit 255
+        let mut q = (limbs[0] + 19) >> 51;
+        for limb in &limbs[1..] {
+            q = (limb + q) >> 51;
+        }
+        limbs[0] += 19 * q;
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= MASK;
+        }
+        limbs[4] &= MASK;
+
+        let mut bytes = [0u8; 32];
+        let mut acc: u128 = 0;
+        let mut acc_bits = 0;
+        let mut out = 0;
+        for limb in limbs {
+            acc |= (limb as u128) << acc_bits;
+            acc_bits += 51;
+            while acc_bits >= 8 && out < 32 {
+                bytes[out] = acc as u8;
+                acc >>= 8;
+                acc_bits -= 8;
+                out += 1;
+            }
+        }
+        bytes[31] = acc as u8;
+        bytes
+    }
+
+    /// Propagates the carries so that every limb fits in 51 bits, bar a
+    /// small excess in the lowest from folding the top carry back as 19
+    fn carry(mut limbs: [u64; 5]) -> Self {
+        for i in 0..4 {
+            limbs[i + 1] += limbs[i] >> 51;
+            limbs[i] &= MASK;
+        }
+        limbs[0] += 19 * (limbs[4] >> 51);
+        limbs[4] &= MASK;
+        Self(limbs)
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// `self^(2^k)`
+    pub fn pow2k(&self, k: u32) -> Self {
+        let mut result = *self;
+        for _ in 0..k {
+            result = result.square();
+        }
+        result
+    }
+
+    /// Multiplication by a small constant such as (A - 2) / 4
+    pub fn mul_small(&self, scalar: u32) -> Self {
+        let mut wide = [0u128; 5];
+        for (w, limb) in wide.iter_mut().zip(self.0) {
+            *w = limb as u128 * scalar as u128;
+        }
+        Self::carry_wide(wide)
+    }
+
+    fn carry_wide(mut wide: [u128; 5]) -> Self {
+        for i in 0..4 {
+            wide[i + 1] += wide[i] >> 51;
+            wide[i] &= MASK as u128;
+        }
+        let top = wide[4] >> 51;
+        wide[4] &= MASK as u128;
+        let mut limbs = wide.map(|w| w as u64);
+        // 19 * top can itself run past 51 bits, carry it on before the
+        // final pass
+        let folded = limbs[0] as u128 + 19 * top;
+        limbs[0] = folded as u64 & MASK;
+        limbs[1] += (folded >> 51) as u64;
+        Self::carry(limbs)
+    }
+
+    /// `(self^(2^250 - 1), self^11)`, the shared prefix of the
+    /// exponentiations for inversion and square roots
+    fn pow22501(&self) -> (Self, Self) {
+        let t0 = self.square();
+        let t1 = t0.pow2k(2);
+        let t2 = *self * t1;
+        let t3 = t0 * t2;
+        let t4 = t3.square();
+        let t5 = t2 * t4;
+        let t7 = t5.pow2k(5) * t5;
+        let t9 = t7.pow2k(10) * t7;
+        let t11 = t9.pow2k(20) * t9;
+        let t13 = t11.pow2k(10) * t7;
+        let t15 = t13.pow2k(50) * t13;
+        let t17 = t15.pow2k(100) * t15;
+        let t19 = t17.pow2k(50) * t13;
+        (t19, t3)
+    }
+
+    /// `self^(p - 2)`, the inverse for anything but zero, which maps to
+    /// zero
+    pub fn invert(&self) -> Self {
+        let (t19, t3) = self.pow22501();
+        t19.pow2k(5) * t3
+    }
+
+    /// `self^((p - 5) / 8)`, the exponentiation at the heart of square
+    /// roots
+    pub fn pow_p58(&self) -> Self {
+        let (t19, _) = self.pow22501();
+        t19.pow2k(2) * *self
+    }
+
+    /// A square root of `u / v` found as in RFC 8032 section 5.1.3, or
+    /// `None` if it is not a square. Either of the two roots may come back
+    pub fn sqrt_ratio(u: &Self, v: &Self) -> Option<Self> {
+        let v3 = v.square() * *v;
+        let v7 = v3.square() * *v;
+        let x = *u * v3 * (*u * v7).pow_p58();
+        let check = *v * x.square();
+        if check == *u {
+            Some(x)
+        } else if check == -*u {
+            Some(x * Self::SQRT_M1)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.to_bytes() == [0; 32]
+    }
+
+    /// Whether the canonical encoding is odd, the sign bit of RFC 8032
+    pub fn is_negative(&self) -> bool {
+        self.to_bytes()[0] & 1 == 1
+    }
+
+    /// Swaps `a` and `b` if `choice` is 1 and leaves them if it is 0,
+    /// without branching on it
+    pub fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        let mask = 0u64.wrapping_sub(choice as u64);
+        for (x, y) in a.0.iter_mut().zip(b.0.iter_mut()) {
+            let t = mask & (*x ^ *y);
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+}
+
+impl Field for FieldElement {
+    const ZERO: Self = Self::ZERO;
+    const ONE: Self = Self::ONE;
+
+    fn modulus() -> BigUint {
+        (BigUint::one() << 255) - BigUint::from(19u64)
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Self::from_u64(value)
+    }
+
+    fn invert(&self) -> Self {
+        Self::invert(self)
+    }
+
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        Self::conditional_swap(a, b, choice)
+    }
+
+    fn square(&self) -> Self {
+        Self::square(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        Self::is_zero(self)
+    }
+}
+
+impl PartialEq for FieldElement {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for FieldElement {}
+
+impl Add for FieldElement {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut limbs = self.0;
+        for (limb, o) in limbs.iter_mut().zip(other.0) {
+            *limb += o;
+        }
+        Self::carry(limbs)
+    }
+}
+
+impl Sub for FieldElement {
+    type Output = Self;
+
+    /// Adds 16p first so that no limb underflows
+    fn sub(self, other: Self) -> Self {
+        const SIXTEEN_P: [u64; 5] = [
+            36028797018963664,
+            36028797018963952,
+            36028797018963952,
+            36028797018963952,
+            36028797018963952,
+        ];
+        let mut limbs = self.0;
+        for i in 0..5 {
+            limbs[i] = limbs[i] + SIXTEEN_P[i] - other.0[i];
+        }
+        Self::carry(limbs)
+    }
+}
+
+impl Neg for FieldElement {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl Mul for FieldElement {
+    type Output = Self;
+
+    /// Schoolbook multiplication, the limbs past 2^255 fold back in times
+    /// 19 since 2^255 = 19 (mod p)
+    fn mul(self, other: Self) -> Self {
+        let m = |x: u64, y: u64| x as u128 * y as u128;
+        let [a0, a1, a2, a3, a4] = self.0;
+        let [b0, b1, b2, b3, b4] = other.0;
+        let (b1_19, b2_19, b3_19, b4_19) = (b1 * 19, b2 * 19, b3 * 19, b4 * 19);
+
+        Self::carry_wide([
+            m(a0, b0)
+                + m(a4, b1_19)
+                + m(a3, b2_19)
+                + m(a2, b3_19)
+                + m(a1, b4_19),
+            m(a1, b0) + m(a0, b1) + m(a4, b2_19) + m(a3, b3_19) + m(a2, b4_19),
+            m(a2, b0) + m(a1, b1) + m(a0, b2) + m(a4, b3_19) + m(a3, b4_19),
+            m(a3, b0) + m(a2, b1) + m(a1, b2) + m(a0, b3) + m(a4, b4_19),
+            m(a4, b0) + m(a3, b1) + m(a2, b2) + m(a1, b3) + m(a0, b4),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// p - 1, the largest canonical element
+    const P_MINUS_ONE: [u8; 32] = {
+        let mut bytes = [0xff; 32];
+        bytes[0] = 0xec;
+        bytes[31] = 0x7f;
+        bytes
+    };
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let mut bytes = [0u8; 32];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (i * 7 + 3) as u8;
+        }
+        bytes[31] &= 0x7f;
+        assert_eq!(FieldElement::from_bytes(&bytes).to_bytes(), bytes);
+        assert_eq!(
+            FieldElement::from_bytes(&P_MINUS_ONE).to_bytes(),
+            P_MINUS_ONE
+        );
+    }
+
+    #[test]
+    fn test_non_canonical_reduced() {
+        // p itself and p + 1 reduce to 0 and 1, the top bit is ignored
+        let mut p = P_MINUS_ONE;
+        p[0] += 1;
+        assert!(FieldElement::from_bytes(&p).is_zero());
+        p[0] += 1;
+        p[31] |= 0x80;
+        assert_eq!(FieldElement::from_bytes(&p), FieldElement::ONE);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let minus_one = FieldElement::from_bytes(&P_MINUS_ONE);
+        assert_eq!(-FieldElement::ONE, minus_one);
+        assert_eq!(minus_one * minus_one, FieldElement::ONE);
+        assert_eq!(minus_one + FieldElement::ONE, FieldElement::ZERO);
+        assert_eq!(
+            FieldElement::from_u64(121665).mul_small(4),
+            FieldElement::from_u64(486660)
+        );
+
+        let x = FieldElement::from_bytes(&[0xab; 32]);
+        assert_eq!(x * x.invert(), FieldElement::ONE);
+        assert_eq!((x + minus_one) - x, minus_one);
+        assert!(FieldElement::ZERO.invert().is_zero());
+        assert_eq!(FieldElement::SQRT_M1.square(), minus_one);
+    }
+
+    #[test]
+    fn test_sqrt_ratio() {
+        let (u, v) = (FieldElement::from_u64(9), FieldElement::from_u64(4));
+        let root = FieldElement::sqrt_ratio(&u, &v).unwrap();
+        assert_eq!(root.square() * v, u);
+        // 2 is not a square mod p as p = 5 (mod 8)
+        let two = FieldElement::from_u64(2);
+        assert_eq!(FieldElement::sqrt_ratio(&two, &FieldElement::ONE), None);
+        assert!(!two.is_square());
+        assert!(FieldElement::from_u64(9).is_square());
+    }
+
+    #[test]
+    fn test_conditional_swap() {
+        let (one, two) = (FieldElement::ONE, FieldElement::from_u64(2));
+        let (mut a, mut b) = (one, two);
+        FieldElement::conditional_swap(&mut a, &mut b, 0);
+        assert_eq!((a, b), (one, two));
+        FieldElement::conditional_swap(&mut a, &mut b, 1);
+        assert_eq!((a, b), (two, one));
+    }
+}
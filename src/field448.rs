@@ -0,0 +1,343 @@
+//! Arithmetic in GF(2^448 - 2^224 - 1), the field under Curve448 and
+//! Edwards448
+//!
+//! As with [`crate::field25519`] nothing branches on the value of an
+//! element
+
+use crate::bigint::BigUint;
+use crate::field::Field;
+use std::ops::{Add, Mul, Neg, Sub};
+
+const MASK: u64 = (1 << 56) - 1;
+
+/// An element as eight 56 bit limbs, least significant first. Limbs may
+/// run a bit over 56 bits between operations, [`FieldElement::to_bytes`]
+/// gives the canonical form
+#[derive(Clone, Copy, Debug)]
+pub struct FieldElement([u64; 8]);
+
+impl FieldElement {
+    pub const ZERO: Self = Self([0; 8]);
+    pub const ONE: Self = Self([1, 0, 0, 0, 0, 0, 0, 0]);
+
+    pub fn from_u64(value: u64) -> Self {
+        Self([value & MASK, value >> 56, 0, 0, 0, 0, 0, 0])
+    }
+
+    /// Decodes a little endian element, values from p to 2^448 - 1 are
+    /// accepted and reduced
+    pub fn from_bytes(bytes: &[u8; 56]) -> Self {
+        let mut limbs = [0u64; 8];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(7)) {
+            let mut word = [0u8; 8];
+            word[..7].copy_from_slice(chunk);
+            *limb = u64::from_le_bytes(word);
+        }
+        Self(limbs)
+    }
+
+    /// The canonical little endian encoding, fully reduced mod p
+    pub fn to_bytes(&self) -> [u8; 56] {
+        let mut limbs = Self::carry(Self::carry(self.0).0).0;
+        Self::propagate(&mut limbs);
+        let top = limbs[7] >> 56;
+        limbs[7] &= MASK;
+        limbs[0] += top;
+        limbs[4] += top;
+        Self::propagate(&mut limbs);
+
+        // now below 2^448, subtract p once if the value is >= p, which is
+        // when adding 2^224 + 1 carries out of bit 448
+        let mut reduced = limbs;
+        reduced[0] += 1;
+        reduced[4] += 1;
+        Self::propagate(&mut reduced);
+        let mask = 0u64.wrapping_sub(reduced[7] >> 56);
+        reduced[7] &= MASK;
+        for (limb, r) in limbs.iter_mut().zip(reduced) {
+            *limb = (*limb & !mask) | (r & mask);
+        }
+
+        let mut bytes = [0u8; 56];
+        for (chunk, limb) in bytes.chunks_exact_mut(7).zip(limbs) {
+            chunk.copy_from_slice(&limb.to_le_bytes()[..7]);
+        }
+        bytes
+    }
+
+    /// Carries each limb into the next, leaving any excess in the top limb
+    fn propagate(limbs: &mut [u64; 8]) {
+        for i in 0..7 {
+            limbs[i + 1] += limbs[i] >> 56;
+            limbs[i] &= MASK;
+        }
+    }
+
+    /// Brings every limb down to about 56 bits, folding the carry out of
+    /// the top limb back in at bits 0 and 224 as 2^448 = 2^224 + 1 (mod p)
+    fn carry(mut limbs: [u64; 8]) -> Self {
+        Self::propagate(&mut limbs);
+        let top = limbs[7] >> 56;
+        limbs[7] &= MASK;
+        limbs[0] += top;
+        limbs[4] += top;
+        Self(limbs)
+    }
+
+    pub fn square(&self) -> Self {
+        *self * *self
+    }
+
+    /// `self^(2^k)`
+    pub fn pow2k(&self, k: u32) -> Self {
+        let mut result = *self;
+        for _ in 0..k {
+            result = result.square();
+        }
+        result
+    }
+
+    /// Multiplication by a small constant such as (A - 2) / 4
+    pub fn mul_small(&self, scalar: u32) -> Self {
+        *self * Self::from_u64(scalar as u64)
+    }
+
+    /// `self^(2^222 - 1)`, the run of ones both the inverse and the square
+    /// root exponents are made of
+    fn pow_ones_222(&self) -> Self {
+        let x2 = self.square() * *self;
+        let x3 = x2.square() * *self;
+        let x6 = x3.pow2k(3) * x3;
+        let x12 = x6.pow2k(6) * x6;
+        let x24 = x12.pow2k(12) * x12;
+        let x30 = x24.pow2k(6) * x6;
+        let x48 = x24.pow2k(24) * x24;
+        let x96 = x48.pow2k(48) * x48;
+        let x192 = x96.pow2k(96) * x96;
+        x192.pow2k(30) * x30
+    }
+
+    /// `self^(p - 2)`, the inverse for anything but zero, which maps to
+    /// zero. In binary p - 2 is 223 ones, a zero, 222 ones, 0 and 1
+    pub fn invert(&self) -> Self {
+        let x222 = self.pow_ones_222();
+        let x223 = x222.square() * *self;
+        (x223.pow2k(223) * x222).pow2k(2) * *self
+    }
+
+    /// `self^((p - 3) / 4)`, 223 ones, a zero and 222 ones in binary
+    pub fn pow_p34(&self) -> Self {
+        let x222 = self.pow_ones_222();
+        let x223 = x222.square() * *self;
+        x223.pow2k(223) * x222
+    }
+
+    /// A square root of `u / v` found as in RFC 8032 section 5.2.3, or
+    /// `None` if it is not a square. Either of the two roots may come back
+    pub fn sqrt_ratio(u: &Self, v: &Self) -> Option<Self> {
+        let u3v = u.square() * *u * *v;
+        let u5v3 = u3v * u.square() * v.square();
+        let x = u3v * u5v3.pow_p34();
+        if *v * x.square() == *u {
+            Some(x)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.to_bytes() == [0; 56]
+    }
+
+    /// Whether the canonical encoding is odd, the sign bit of RFC 8032
+    pub fn is_negative(&self) -> bool {
+        self.to_bytes()[0] & 1 == 1
+    }
+
+    /// Swaps `a` and `b` if `choice` is 1 and leaves them if it is 0,
+    /// without branching on it
+    pub fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        let mask = 0u64.wrapping_sub(choice as u64);
+        for (x, y) in a.0.iter_mut().zip(b.0.iter_mut()) {
+            let t = mask & (*x ^ *y);
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+}
+
+impl Field for FieldElement {
+    const ZERO: Self = Self::ZERO;
+    const ONE: Self = Self::ONE;
+
+    fn modulus() -> BigUint {
+        (BigUint::one() << 448) - (BigUint::one() << 224) - BigUint::one()
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Self::from_u64(value)
+    }
+
+    fn invert(&self) -> Self {
+        Self::invert(self)
+    }
+
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        Self::conditional_swap(a, b, choice)
+    }
+
+    fn square(&self) -> Self {
+        Self::square(self)
+    }
+
+    fn is_zero(&self) -> bool {
+        Self::is_zero(self)
+    }
+}
+
+impl PartialEq for FieldElement {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for FieldElement {}
+
+impl Add for FieldElement {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut limbs = self.0;
+        for (limb, o) in limbs.iter_mut().zip(other.0) {
+            *limb += o;
+        }
+        Self::carry(limbs)
+    }
+}
+
+impl Sub for FieldElement {
+    type Output = Self;
+
+    /// Adds 4p first so that no limb underflows
+    fn sub(self, other: Self) -> Self {
+        let mut limbs = self.0;
+        for (i, (limb, o)) in limbs.iter_mut().zip(other.0).enumerate() {
+            let four_p = if i == 4 { (1 << 58) - 8 } else { (1 << 58) - 4 };
+            *limb = *limb + four_p - o;
+        }
+        Self::carry(limbs)
+    }
+}
+
+impl Neg for FieldElement {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl Mul for FieldElement {
+    type Output = Self;
+
+    /// Schoolbook multiplication into 15 columns, then each column past
+    /// 2^448 folds back onto the columns 8 and 4 below it
+    fn mul(self, other: Self) -> Self {
+        let mut wide = [0u128; 15];
+        for (i, a) in self.0.iter().enumerate() {
+            for (j, b) in other.0.iter().enumerate() {
+                wide[i + j] += *a as u128 * *b as u128;
+            }
+        }
+        for k in (8..15).rev() {
+            let column = wide[k];
+            wide[k - 4] += column;
+            wide[k - 8] += column;
+        }
+
+        for i in 0..7 {
+            wide[i + 1] += wide[i] >> 56;
+            wide[i] &= MASK as u128;
+        }
+        let top = wide[7] >> 56;
+        wide[7] &= MASK as u128;
+        wide[0] += top;
+        wide[4] += top;
+        for i in 0..7 {
+            wide[i + 1] += wide[i] >> 56;
+            wide[i] &= MASK as u128;
+        }
+
+        let mut limbs = [0u64; 8];
+        for (limb, w) in limbs.iter_mut().zip(wide) {
+            *limb = w as u64;
+        }
+        Self::carry(limbs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// p - 1, the largest canonical element
+    const P_MINUS_ONE: [u8; 56] = {
+        let mut bytes = [0xff; 56];
+        bytes[0] = 0xfe;
+        bytes[28] = 0xfe;
+        bytes
+    };
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let mut bytes = [0u8; 56];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (i * 7 + 3) as u8;
+        }
+        assert_eq!(FieldElement::from_bytes(&bytes).to_bytes(), bytes);
+        assert_eq!(
+            FieldElement::from_bytes(&P_MINUS_ONE).to_bytes(),
+            P_MINUS_ONE
+        );
+    }
+
+    #[test]
+    fn test_non_canonical_reduced() {
+        let mut p = P_MINUS_ONE;
+        p[0] += 1;
+        assert!(FieldElement::from_bytes(&p).is_zero());
+        // 2^448 - 1 = p + 2^224
+        let mut two_224 = [0u8; 56];
+        two_224[28] = 1;
+        assert_eq!(FieldElement::from_bytes(&[0xff; 56]).to_bytes(), two_224);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let minus_one = FieldElement::from_bytes(&P_MINUS_ONE);
+        assert_eq!(-FieldElement::ONE, minus_one);
+        assert_eq!(minus_one * minus_one, FieldElement::ONE);
+        assert_eq!(minus_one + FieldElement::ONE, FieldElement::ZERO);
+
+        let x = FieldElement::from_bytes(&[0xab; 56]);
+        assert_eq!(x * x.invert(), FieldElement::ONE);
+        assert_eq!((x + minus_one) - x, minus_one);
+        assert_eq!(x.pow(&FieldElement::modulus()), x);
+        assert!(FieldElement::ZERO.invert().is_zero());
+    }
+
+    #[test]
+    fn test_sqrt_ratio() {
+        let (u, v) = (FieldElement::from_u64(9), FieldElement::from_u64(4));
+        let root = FieldElement::sqrt_ratio(&u, &v).unwrap();
+        assert_eq!(root.square() * v, u);
+        // -1 is not a square mod p as p = 3 (mod 4)
+        let minus_one = -FieldElement::ONE;
+        assert_eq!(
+            FieldElement::sqrt_ratio(&minus_one, &FieldElement::ONE),
+            None
+        );
+        assert!(!minus_one.is_square());
+        assert!(FieldElement::from_u64(2).is_square());
+    }
+}
@@ -0,0 +1,163 @@
+//! Common interface for the hash functions and the constructions built from
+//! them
+
+/// A Merkle–Damgård style hash function
+pub trait Digest: Sized {
+    /// Size in bytes of the blocks fed to the compression function
+    const BLOCK_SIZE: usize;
+    /// Size in bytes of the finished hash
+    const OUTPUT_SIZE: usize;
+
+    fn new() -> Self;
+
+    fn update(&mut self, data: &[u8]);
+
+    fn finalize(self) -> Vec<u8>;
+
+    fn digest(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
+}
+
+/// Buffers input until a whole block is available for the compression
+/// function and applies the Merkle–Damgård length padding at the end
+#[derive(Clone)]
+pub(crate) struct BlockBuffer<const BLOCK: usize> {
+    block: [u8; BLOCK],
+    filled: usize,
+    /// Total message length in bytes
+    length: u128,
+}
+
+impl<const BLOCK: usize> BlockBuffer<BLOCK> {
+    pub(crate) fn new() -> Self {
+        Self {
+            block: [0; BLOCK],
+            filled: 0,
+            length: 0,
+        }
+    }
+
+    pub(crate) fn update(
+        &mut self,
+        mut data: &[u8],
+        mut compress: impl FnMut(&[u8; BLOCK]),
+    ) {
+        self.length += data.len() as u128;
+        while !data.is_empty() {
+            let take = (BLOCK - self.filled).min(data.len());
+            self.block[self.filled..self.filled + take]
+                .copy_from_slice(&data[..take]);
+            self.filled += take;
+            data = &data[take..];
+            if self.filled == BLOCK {
+                compress(&self.block);
+                self.filled = 0;
+            }
+        }
+    }
+
+    /// Appends `0x80`, zeros and the big endian bit length in the last
+    /// `length_size` bytes of the final block
+    pub(crate) fn finalize(
+        mut self,
+        length_size: usize,
+        mut compress: impl FnMut(&[u8; BLOCK]),
+    ) {
+        let bit_length = (self.length * 8).to_be_bytes();
+
+        self.block[self.filled] = 0x80;
+        self.block[self.filled + 1..].fill(0);
+        if self.filled + 1 > BLOCK - length_size {
+            compress(&self.block);
+            self.block.fill(0);
+        }
+        self.block[BLOCK - length_size..]
+            .copy_from_slice(&bit_length[16 - length_size..]);
+        compress(&self.block);
+    }
+}
+
+/// HMAC (RFC 2104) keyed with `key` over any [`Digest`]
+pub struct Hmac<D: Digest> {
+    inner: D,
+    outer_key: Vec<u8>,
+}
+
+impl<D: Digest> Hmac<D> {
+    pub fn new(key: &[u8]) -> Self {
+        let mut key = if key.len() > D::BLOCK_SIZE {
+            D::digest(key)
+        } else {
+            key.to_vec()
+        };
+        key.resize(D::BLOCK_SIZE, 0);
+
+        let inner_key: Vec<u8> = key.iter().map(|b| b ^ 0x36).collect();
+        let outer_key = key.iter().map(|b| b ^ 0x5c).collect();
+
+        let mut inner = D::new();
+        inner.update(&inner_key);
+        Self { inner, outer_key }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    pub fn finalize(self) -> Vec<u8> {
+        let mut outer = D::new();
+        outer.update(&self.outer_key);
+        outer.update(&self.inner.finalize());
+        outer.finalize()
+    }
+}
+
+pub fn hmac<D: Digest>(key: &[u8], message: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<D>::new(key);
+    mac.update(message);
+    mac.finalize()
+}
+
+#[cfg(test)]
+pub(crate) fn hex(s: &str) -> Vec<u8> {
+    let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha1::Sha1;
+    use crate::sha2::{Sha256, Sha512};
+
+    /// RFC 4231 test cases 1, 2 and 6 and RFC 2202 test case 2
+    #[test]
+    fn test_hmac() {
+        assert_eq!(
+            hmac::<Sha256>(&[0x0b; 20], b"Hi There"),
+            hex("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7")
+        );
+        assert_eq!(
+            hmac::<Sha512>(b"Jefe", b"what do ya want for nothing?"),
+            hex("164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea250554
+                 9758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737")
+        );
+        assert_eq!(
+            hmac::<Sha256>(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            ),
+            hex("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54")
+        );
+        assert_eq!(
+            hmac::<Sha1>(b"Jefe", b"what do ya want for nothing?"),
+            hex("effcdf6ae5eb2fa2d27416d5f184df9c259a7c79")
+        );
+    }
+}
@@ -0,0 +1,466 @@
+//! Hashing to elliptic curves (RFC 9380) for the suites
+//! `P256_XMD:SHA-256_SSWU_*`, `edwards25519_XMD:SHA-512_ELL2_*` and
+//! `curve25519_XMD:SHA-512_ELL2_*`
+//!
+//! `hash_to_curve` maps two field elements and adds the points, giving an
+//! output indistinguishable from a random oracle. `encode_to_curve` maps
+//! one, which is cheaper but only reaches a fraction of the points. The
+//! maps take square roots in variable time, so the message should not be
+//! secret
+
+use crate::ed25519::edwards25519;
+use crate::edwards::EdwardsPoint;
+use crate::field::Field;
+use crate::field25519::FieldElement;
+use crate::hash::Digest;
+use crate::nist::{p256, P256};
+use crate::primefield::{self, Modulus};
+use crate::sha2::{Sha256, Sha512};
+use crate::weierstrass::{AffinePoint, JacobianPoint, WeierstrassCurve};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashToCurveError {
+    /// The domain separation tag is empty
+    EmptyDst,
+    /// The requested length is zero, above 65535 bytes or more than 255
+    /// hash outputs
+    InvalidLength,
+}
+
+impl std::fmt::Display for HashToCurveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::EmptyDst => "empty domain separation tag",
+            Self::InvalidLength => "invalid output length",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for HashToCurveError {}
+
+/// expand_message_xmd of RFC 9380 section 5.3.1, `len_in_bytes` uniform
+/// bytes from a hash with a block size. A tag longer than 255 bytes is
+/// replaced by its hash as section 5.3.3 describes
+pub fn expand_message_xmd<D: Digest>(
+    msg: &[u8],
+    dst: &[u8],
+    len_in_bytes: usize,
+) -> Result<Vec<u8>, HashToCurveError> {
+    if dst.is_empty() {
+        return Err(HashToCurveError::EmptyDst);
+    }
+    let ell = len_in_bytes.div_ceil(D::OUTPUT_SIZE);
+    if len_in_bytes == 0 || len_in_bytes > 65535 || ell > 255 {
+        return Err(HashToCurveError::InvalidLength);
+    }
+    let mut dst_prime = if dst.len() > 255 {
+        D::digest(&[b"H2C-OVERSIZE-DST-", dst].concat())
+    } else {
+        dst.to_vec()
+    };
+    dst_prime.push(dst_prime.len() as u8);
+
+    let mut hasher = D::new();
+    hasher.update(&vec![0; D::BLOCK_SIZE]);
+    hasher.update(msg);
+    hasher.update(&(len_in_bytes as u16).to_be_bytes());
+    hasher.update(&[0]);
+    hasher.update(&dst_prime);
+    let b0 = hasher.finalize();
+
+    // b_1 hashes b_0 itself, each later block b_0 xor the one before
+    let mut output = Vec::with_capacity(ell * D::OUTPUT_SIZE);
+    let mut previous = vec![0; D::OUTPUT_SIZE];
+    for i in 1..=ell {
+        let chained: Vec<u8> =
+            b0.iter().zip(&previous).map(|(a, b)| a ^ b).collect();
+        let mut hasher = D::new();
+        hasher.update(&chained);
+        hasher.update(&[i as u8]);
+        hasher.update(&dst_prime);
+        previous = hasher.finalize();
+        output.extend_from_slice(&previous);
+    }
+    output.truncate(len_in_bytes);
+    Ok(output)
+}
+
+/// hash_to_field of RFC 9380 section 5.2 for a prime field. Each element
+/// takes `L = ceil((ceil(log2 p) + 128) / 8)` bytes, enough that reducing
+/// them mod p leaves a bias below 2^-128
+pub fn hash_to_field<D: Digest, F: Field>(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+) -> Result<Vec<F>, HashToCurveError> {
+    let length = (F::modulus().bits() + 128).div_ceil(8);
+    let bytes = expand_message_xmd::<D>(msg, dst, count * length)?;
+    let radix = F::from_u64(256);
+    Ok(bytes
+        .chunks_exact(length)
+        .map(|chunk| {
+            chunk.iter().fold(F::ZERO, |acc, &byte| {
+                acc * radix + F::from_u64(byte as u64)
+            })
+        })
+        .collect())
+}
+
+/// The simplified SWU map of RFC 9380 section 6.6.2 for a curve with `a`
+/// and `b` both non-zero. `z` is the suite's non-square, chosen so that
+/// `g(b / (z a))` is a square
+pub fn map_to_curve_sswu<M: Modulus>(
+    curve: &WeierstrassCurve<primefield::FieldElement<M>>,
+    z: primefield::FieldElement<M>,
+    u: primefield::FieldElement<M>,
+) -> AffinePoint<primefield::FieldElement<M>> {
+    let (a, b) = (curve.a, curve.b);
+    let g = |x: primefield::FieldElement<M>| (x.square() + a) * x + b;
+
+    let z_u2 = z * u.square();
+    let tv1 = (z_u2.square() + z_u2).invert();
+    let x1 = if tv1.is_zero() {
+        b * (z * a).invert()
+    } else {
+        -b * a.invert() * (primefield::FieldElement::ONE + tv1)
+    };
+    let x2 = z_u2 * x1;
+    let (x, y) = match g(x1).sqrt() {
+        Some(y) => (x1, y),
+        None => (
+            x2,
+            g(x2).sqrt().expect("g(x2) is a square when g(x1) is not"),
+        ),
+    };
+    let y = if u.is_odd() != y.is_odd() { -y } else { y };
+    AffinePoint { x, y }
+}
+
+/// The Montgomery coefficient J of Curve25519, with K = 1
+const J: u64 = 486662;
+
+/// sqrt(-486664) with sgn0 equal to 0, the scale in the map between
+/// Curve25519 and edwards25519
+fn sqrt_minus_486664() -> FieldElement {
+    let root = FieldElement::sqrt_ratio(
+        &-FieldElement::from_u64(J + 2),
+        &FieldElement::ONE,
+    )
+    .unwrap();
+    if root.is_negative() {
+        -root
+    } else {
+        root
+    }
+}
+
+fn sqrt(x: &FieldElement) -> Option<FieldElement> {
+    FieldElement::sqrt_ratio(x, &FieldElement::ONE)
+}
+
+/// Elligator 2 of RFC 9380 section 6.7.1 onto Curve25519 with Z = 2,
+/// returning the Montgomery point (s, t)
+fn map_to_curve_elligator2(u: FieldElement) -> (FieldElement, FieldElement) {
+    let j = FieldElement::from_u64(J);
+    let g = |x: FieldElement| ((x + j) * x + FieldElement::ONE) * x;
+
+    let tv1 =
+        (FieldElement::ONE + FieldElement::from_u64(2) * u.square()).invert();
+    let x1 = if tv1.is_zero() { -j } else { -j * tv1 };
+    let x2 = -x1 - j;
+    // the root is taken odd for x1 and even for x2
+    let (x, y, odd) = match sqrt(&g(x1)) {
+        Some(y) => (x1, y, true),
+        None => (x2, sqrt(&g(x2)).expect("g(x2) is a square"), false),
+    };
+    let y = if y.is_negative() != odd { -y } else { y };
+    (x, y)
+}
+
+/// The rational map of RFC 9380 appendix D.1 from Curve25519 to
+/// edwards25519, sending its exceptional points to the identity
+fn montgomery_to_edwards(
+    s: FieldElement,
+    t: FieldElement,
+) -> EdwardsPoint<FieldElement> {
+    let one = FieldElement::ONE;
+    let denominator = ((s + one) * t).invert();
+    if denominator.is_zero() {
+        return EdwardsPoint::IDENTITY;
+    }
+    let x = sqrt_minus_486664() * s * (s + one) * denominator;
+    let y = (s - one) * t * denominator;
+    EdwardsPoint::from_affine(x, y)
+}
+
+/// The inverse map, `s = (1 + y) / (1 - y)` and `t = sqrt(-486664) s / x`.
+/// The identity, which has no affine image, comes back as (0, 0)
+fn edwards_to_montgomery(
+    point: &EdwardsPoint<FieldElement>,
+) -> (FieldElement, FieldElement) {
+    let (x, y) = point.to_affine();
+    let s = (FieldElement::ONE + y) * (FieldElement::ONE - y).invert();
+    (s, sqrt_minus_486664() * s * x.invert())
+}
+
+fn edwards25519_map(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+) -> Result<EdwardsPoint<FieldElement>, HashToCurveError> {
+    let curve = edwards25519();
+    let point = hash_to_field::<Sha512, FieldElement>(msg, dst, count)?
+        .into_iter()
+        .map(|u| {
+            let (s, t) = map_to_curve_elligator2(u);
+            montgomery_to_edwards(s, t)
+        })
+        .fold(EdwardsPoint::IDENTITY, |sum, point| curve.add(&sum, &point));
+    Ok(curve.mul_by_cofactor(&point))
+}
+
+/// `edwards25519_XMD:SHA-512_ELL2_RO_`, a point of the prime order
+/// subgroup
+pub fn edwards25519_hash_to_curve(
+    msg: &[u8],
+    dst: &[u8],
+) -> Result<EdwardsPoint<FieldElement>, HashToCurveError> {
+    edwards25519_map(msg, dst, 2)
+}
+
+/// `edwards25519_XMD:SHA-512_ELL2_NU_`
+pub fn edwards25519_encode_to_curve(
+    msg: &[u8],
+    dst: &[u8],
+) -> Result<EdwardsPoint<FieldElement>, HashToCurveError> {
+    edwards25519_map(msg, dst, 1)
+}
+
+/// `curve25519_XMD:SHA-512_ELL2_RO_` as the Montgomery point (u, v). The
+/// arithmetic runs on edwards25519 and maps back, which gives the same
+/// point as adding on Curve25519
+pub fn curve25519_hash_to_curve(
+    msg: &[u8],
+    dst: &[u8],
+) -> Result<(FieldElement, FieldElement), HashToCurveError> {
+    Ok(edwards_to_montgomery(&edwards25519_map(msg, dst, 2)?))
+}
+
+/// `curve25519_XMD:SHA-512_ELL2_NU_` as the Montgomery point (u, v)
+pub fn curve25519_encode_to_curve(
+    msg: &[u8],
+    dst: &[u8],
+) -> Result<(FieldElement, FieldElement), HashToCurveError> {
+    Ok(edwards_to_montgomery(&edwards25519_map(msg, dst, 1)?))
+}
+
+fn p256_map(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+) -> Result<JacobianPoint<primefield::FieldElement<P256>>, HashToCurveError> {
+    let curve = p256();
+    let z = -primefield::FieldElement::from_u64(10);
+    // the cofactor is 1, so the sum needs no clearing
+    Ok(hash_to_field::<Sha256, _>(msg, dst, count)?
+        .into_iter()
+        .map(|u| JacobianPoint::from_affine(&map_to_curve_sswu(&curve, z, u)))
+        .fold(JacobianPoint::IDENTITY, |sum, point| {
+            curve.add(&sum, &point)
+        }))
+}
+
+/// `P256_XMD:SHA-256_SSWU_RO_`
+pub fn p256_hash_to_curve(
+    msg: &[u8],
+    dst: &[u8],
+) -> Result<JacobianPoint<primefield::FieldElement<P256>>, HashToCurveError> {
+    p256_map(msg, dst, 2)
+}
+
+/// `P256_XMD:SHA-256_SSWU_NU_`
+pub fn p256_encode_to_curve(
+    msg: &[u8],
+    dst: &[u8],
+) -> Result<JacobianPoint<primefield::FieldElement<P256>>, HashToCurveError> {
+    p256_map(msg, dst, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hex;
+
+    /// Appendix J, the point P for each suite and message
+    const VECTORS: &str =
+        include_str!("../test_vectors/hash_to_curve_vectors.csv");
+
+    /// A big endian hex integer as a little endian field element
+    fn element25519(hex_value: &str) -> FieldElement {
+        let mut bytes = hex(hex_value);
+        bytes.reverse();
+        FieldElement::from_bytes(&bytes.try_into().unwrap())
+    }
+
+    /// Appendix K.1 and K.3, the expander outputs for "" and "abc" at 32
+    /// and 128 bytes and for a tag long enough to be hashed
+    #[test]
+    fn test_expand_message_xmd() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        let vectors = [
+            (
+                &b""[..],
+                "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235",
+            ),
+            (
+                b"abc",
+                "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615",
+            ),
+            (
+                b"abcdef0123456789",
+                "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1",
+            ),
+        ];
+        for (msg, expected) in vectors {
+            assert_eq!(
+                expand_message_xmd::<Sha256>(msg, dst, 32),
+                Ok(hex(expected))
+            );
+        }
+        assert_eq!(
+            expand_message_xmd::<Sha256>(b"abc", dst, 128),
+            Ok(hex(
+                "abba86a6129e366fc877aab32fc4ffc70120d8996c88aee2fe4b32d6c7b643\
+                 7a647e6c3163d40b76a73cf6a5674ef1d890f95b664ee0afa5359a5c4e0798\
+                 5635bbecbac65d747d3d2da7ec2b8221b17b0ca9dc8a1ac1c07ea6a1e60583\
+                 e2cb00058e77b7b72a298425cd1b941ad4ec65e8afc50303a22c0f99b0509b\
+                 4c895f40"
+            ))
+        );
+
+        let long_dst = [
+            &b"QUUX-V01-CS02-with-expander-SHA256-128-long-DST-"[..],
+            &[b'1'; 208],
+        ]
+        .concat();
+        assert_eq!(
+            expand_message_xmd::<Sha256>(b"", &long_dst, 32),
+            Ok(hex(
+                "e8dc0c8b686b7ef2074086fbdd2f30e3f8bfbd3bdf177f73f04b97ce618a3ed3"
+            ))
+        );
+
+        let dst = b"QUUX-V01-CS02-with-expander-SHA512-256";
+        assert_eq!(
+            expand_message_xmd::<Sha512>(b"abc", dst, 32),
+            Ok(hex(
+                "0da749f12fbe5483eb066a5f595055679b976e93abe9be6f0f6318bce7aca8dc"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_expand_message_xmd_rejects() {
+        assert_eq!(
+            expand_message_xmd::<Sha256>(b"abc", b"", 32),
+            Err(HashToCurveError::EmptyDst)
+        );
+        for length in [0, 255 * 32 + 1, 65536] {
+            assert_eq!(
+                expand_message_xmd::<Sha256>(b"abc", b"tag", length),
+                Err(HashToCurveError::InvalidLength)
+            );
+        }
+        assert_eq!(
+            expand_message_xmd::<Sha512>(b"abc", b"tag", 255 * 64)
+                .map(|bytes| bytes.len()),
+            Ok(255 * 64)
+        );
+    }
+
+    #[test]
+    fn test_suite_vectors() {
+        let p256_element = |hex_value: &str| {
+            primefield::FieldElement::<P256>::from_be_bytes(&hex(hex_value))
+                .unwrap()
+        };
+        let curve = edwards25519();
+        let mut count = 0;
+        for line in VECTORS.lines().skip(1) {
+            let fields: Vec<&str> = line.split(',').collect();
+            let (suite, msg, x, y) =
+                (fields[0], fields[1], fields[2], fields[3]);
+            let dst = format!("QUUX-V01-CS02-with-{suite}");
+            let (msg, dst) = (msg.as_bytes(), dst.as_bytes());
+            match suite {
+                "P256_XMD:SHA-256_SSWU_RO_" | "P256_XMD:SHA-256_SSWU_NU_" => {
+                    let point = if suite.ends_with("RO_") {
+                        p256_hash_to_curve(msg, dst)
+                    } else {
+                        p256_encode_to_curve(msg, dst)
+                    };
+                    let point = point.unwrap().to_affine().unwrap();
+                    let expected = AffinePoint {
+                        x: p256_element(x),
+                        y: p256_element(y),
+                    };
+                    assert_eq!(point, expected, "{suite} {line}");
+                }
+                "edwards25519_XMD:SHA-512_ELL2_RO_"
+                | "edwards25519_XMD:SHA-512_ELL2_NU_" => {
+                    let point = if suite.ends_with("RO_") {
+                        edwards25519_hash_to_curve(msg, dst)
+                    } else {
+                        edwards25519_encode_to_curve(msg, dst)
+                    };
+                    let point = point.unwrap();
+                    let (px, py) = point.to_affine();
+                    assert!(curve.is_on_curve(px, py));
+                    let expected = EdwardsPoint::from_affine(
+                        element25519(x),
+                        element25519(y),
+                    );
+                    assert_eq!(point, expected, "{suite} {line}");
+                }
+                _ => {
+                    let point = if suite.ends_with("RO_") {
+                        curve25519_hash_to_curve(msg, dst)
+                    } else {
+                        curve25519_encode_to_curve(msg, dst)
+                    };
+                    let expected = (element25519(x), element25519(y));
+                    assert_eq!(point, Ok(expected), "{suite} {line}");
+                }
+            }
+            count += 1;
+        }
+        assert_eq!(count, 30);
+    }
+
+    /// Outputs of the random oracle suites have prime order
+    #[test]
+    fn test_prime_order() {
+        let curve = edwards25519();
+        let order = curve.order.to_le_bytes_padded(32);
+        let point = edwards25519_hash_to_curve(b"abc", b"tag").unwrap();
+        assert!(!point.is_identity());
+        assert!(curve.scalar_mult(&order, &point).is_identity());
+    }
+
+    /// u = 0 hits the exceptional case of both maps
+    #[test]
+    fn test_exceptional_inputs() {
+        let curve = p256();
+        let point = map_to_curve_sswu(
+            &curve,
+            -primefield::FieldElement::from_u64(10),
+            primefield::FieldElement::ZERO,
+        );
+        assert!(curve.is_on_curve(&point));
+
+        let (s, t) = map_to_curve_elligator2(FieldElement::ZERO);
+        assert_eq!((s, t), (FieldElement::ZERO, FieldElement::ZERO));
+        assert!(montgomery_to_edwards(s, t).is_identity());
+    }
+}
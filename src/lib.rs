@@ -1,6 +1,39 @@
+pub mod bigint;
+pub mod bip340;
+pub mod bitslice;
 pub mod blowfish;
-mod des;
-mod diffiehellman;
+pub mod cryptanalysis;
+pub mod des;
+pub mod diffiehellman;
+pub mod dsa;
+pub mod ecdh;
+pub mod ecdsa;
+pub mod ed25519;
+pub mod ed448;
+pub mod edwards;
+pub mod feistel;
+pub mod field;
+pub mod field25519;
+pub mod field448;
+pub mod hash;
+pub mod hash2curve;
+#[allow(dead_code)]
 mod math;
+pub mod montgomery;
+pub mod nist;
+pub mod primefield;
+pub mod rfc6979;
+pub mod ristretto255;
+#[allow(dead_code)]
 mod rsa;
-mod x25519;
+pub mod saes;
+pub mod schnorr;
+pub mod secp256k1;
+pub mod sha1;
+pub mod sha2;
+pub mod sha3;
+pub mod srp;
+pub mod toycurve;
+pub mod weierstrass;
+pub mod x25519;
+pub mod x448;
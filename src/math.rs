@@ -9,7 +9,7 @@ pub fn carmichael(n: u64) -> u64 {
         k += 1;
     }
 
-    return k;
+    k
 }
 
 pub fn phi(n: u64) -> u64 {
@@ -22,16 +22,75 @@ pub fn phi(n: u64) -> u64 {
     count
 }
 
+/// The prime factors of `n` with their multiplicities, by trial division
+pub fn factorize(mut n: u64) -> Vec<(u64, u32)> {
+    let mut factors = Vec::new();
+    let mut d = 2;
+    while d <= n / d {
+        let mut exponent = 0;
+        while n.is_multiple_of(d) {
+            n /= d;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((d, exponent));
+        }
+        // 2 is the only even prime
+        d += if d == 2 { 1 } else { 2 };
+    }
+    if n > 1 {
+        factors.push((n, 1));
+    }
+    factors
+}
+
 pub trait ModInverse {
     fn mod_inverse(self, modulus: Self) -> Self;
 }
 
+// Extended Euclidean algorithm, the coefficient of self is kept reduced mod
+// `modulus` so it never goes negative for the unsigned types. The product
+// with the quotient is taken by doubling and adding, as multiplying outright
+// overflows once `modulus` is past half the range of the type. Panics if
+// self and `modulus` are not coprime
 macro_rules! impl_mod_inverse {
     ($ty:ty) => {
         impl ModInverse for $ty {
-            /// Only works for primes
             fn mod_inverse(self, modulus: Self) -> Self {
-                self.pow_mod(modulus - 2, modulus)
+                let add_mod = |a: Self, b: Self| {
+                    if a >= modulus - b {
+                        a - (modulus - b)
+                    } else {
+                        a + b
+                    }
+                };
+                let mul_mod = |mut a: Self, mut b: Self| {
+                    let mut product = 0;
+                    while b > 0 {
+                        if b & 1 == 1 {
+                            product = add_mod(product, a);
+                        }
+                        a = add_mod(a, a);
+                        b >>= 1;
+                    }
+                    product
+                };
+
+                let (mut old_r, mut r) = (self % modulus, modulus);
+                let (mut old_s, mut s) = (1 % modulus, 0);
+                while r != 0 {
+                    let quotient = old_r / r;
+                    (old_r, r) = (r, old_r - quotient * r);
+                    let product = mul_mod(quotient % modulus, s);
+                    let next_s = if old_s >= product {
+                        old_s - product
+                    } else {
+                        modulus - (product - old_s)
+                    };
+                    (old_s, s) = (s, next_s);
+                }
+                assert!(old_r == 1, "{self} has no inverse mod {modulus}");
+                old_s
             }
         }
     };
@@ -59,7 +118,7 @@ macro_rules! impl_gcd {
                 if self == 0 {
                     return other;
                 }
-                return Self::gcd(other % self, self);
+                Self::gcd(other % self, self)
             }
         }
     };
@@ -171,6 +230,24 @@ impl_pow_mod!(u128);
 mod test {
     use super::*;
 
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(1), vec![]);
+        assert_eq!(factorize(88), vec![(2, 3), (11, 1)]);
+        assert_eq!(factorize(108), vec![(2, 2), (3, 3)]);
+        assert_eq!(factorize(4294967291), vec![(4294967291, 1)]);
+    }
+
+    #[test]
+    fn test_factorize_near_u64_max() {
+        // d reaches 2^32 for these, where d * d would overflow
+        assert_eq!(
+            factorize(18446744073709551557),
+            vec![(18446744073709551557, 1)]
+        );
+        assert_eq!(factorize(18446744030759878681), vec![(4294967291, 2)]);
+    }
+
     #[test]
     fn pow_mod_u64() {
         assert_eq!(u64::pow_mod(2, 4, 5), 1);
@@ -247,5 +324,21 @@ mod test {
         assert_eq!(3.mod_inverse(11), 4);
         assert_eq!(7.mod_inverse(13), 2);
         assert_eq!(9.mod_inverse(23), 18);
+
+        // Composite moduli, as RSA needs mod the Carmichael function
+        assert_eq!(17u64.mod_inverse(3120), 2753);
+        assert_eq!(5u128.mod_inverse(6), 5);
+        assert_eq!(7u32.mod_inverse(240), 103);
+
+        // Moduli past half the range, where the products would overflow
+        assert_eq!(200u8.mod_inverse(251), 187);
+        assert_eq!(2u64.mod_inverse(u64::MAX), 1 << 63);
+        let p = u64::MAX - 58;
+        assert_eq!(3u64.mod_inverse(p), 6148914691236517186);
+        assert_eq!(12345678901234567u64.mod_inverse(p), 8297469362529172873);
+        assert_eq!(
+            3u128.mod_inverse(u128::MAX - 158),
+            226854911280625642308916404954512140865
+        );
     }
 }
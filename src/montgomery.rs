@@ -0,0 +1,242 @@
+//! Montgomery curves `y^2 = x^3 + Ax^2 + x` over any [`Field`] and the
+//! x-only arithmetic X25519 and X448 are built from
+//!
+//! Points are handled by their x-coordinate alone, in projective (X:Z)
+//! form so that a scalar multiplication needs a single inversion
+
+use crate::bigint::BigUint;
+use crate::field::Field;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveError {
+    /// `A^2 - 4` is zero, the curve is singular
+    Singular,
+    /// The base point lies on the quadratic twist rather than the curve
+    BaseNotOnCurve,
+    /// `order` times the base point is not the point at infinity, or
+    /// `order * cofactor` is not a possible number of points
+    InvalidOrder,
+}
+
+impl std::fmt::Display for CurveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::Singular => "singular curve",
+            Self::BaseNotOnCurve => "base point not on the curve",
+            Self::InvalidOrder => "base point order does not match",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for CurveError {}
+
+/// Whether a curve over GF(p) can have `points` points, that is
+/// `|points - (p + 1)| <= 2 sqrt(p)`
+pub(crate) fn within_hasse_bound<F: Field>(points: &BigUint) -> bool {
+    let p_plus_one = F::modulus() + BigUint::one();
+    let trace = if points > &p_plus_one {
+        points - &p_plus_one
+    } else {
+        &p_plus_one - points
+    };
+    &trace * &trace <= &F::modulus() * &BigUint::from(4u64)
+}
+
+/// A point in projective x-only coordinates, x = X / Z. The point at
+/// infinity has Z = 0
+#[derive(Clone, Copy, Debug)]
+pub struct XzPoint<F: Field> {
+    pub x: F,
+    pub z: F,
+}
+
+impl<F: Field> XzPoint<F> {
+    pub const INFINITY: Self = Self {
+        x: F::ONE,
+        z: F::ZERO,
+    };
+
+    pub fn from_affine(x: F) -> Self {
+        Self { x, z: F::ONE }
+    }
+
+    /// The affine x-coordinate, with the point at infinity mapping to 0
+    pub fn to_affine(&self) -> F {
+        self.x * self.z.invert()
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        F::conditional_swap(&mut a.x, &mut b.x, choice);
+        F::conditional_swap(&mut a.z, &mut b.z, choice);
+    }
+}
+
+/// The curve `y^2 = x^3 + Ax^2 + x` with a base point of order `order`
+/// generating a subgroup of index `cofactor`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MontgomeryCurve<F: Field> {
+    pub a: F,
+    /// x-coordinate of the base point
+    pub base: F,
+    pub order: BigUint,
+    pub cofactor: u64,
+    /// (A - 2) / 4, the constant in the doubling formula
+    a24: F,
+}
+
+impl<F: Field> MontgomeryCurve<F> {
+    pub fn new(a: F, base: F, order: BigUint, cofactor: u64) -> Self {
+        let a24 = (a - F::from_u64(2)) * F::from_u64(4).invert();
+        Self {
+            a,
+            base,
+            order,
+            cofactor,
+            a24,
+        }
+    }
+
+    /// Checks the curve is non-singular, the base point is on it and has
+    /// the stated order, and that the group order this implies is within
+    /// the Hasse bound `|#E - (p + 1)| <= 2 sqrt(p)`
+    pub fn validate(&self) -> Result<(), CurveError> {
+        if (self.a.square() - F::from_u64(4)).is_zero() {
+            return Err(CurveError::Singular);
+        }
+        if !self.is_on_curve(self.base) {
+            return Err(CurveError::BaseNotOnCurve);
+        }
+
+        let scalar = self.order.to_le_bytes_padded(self.order.bits() / 8 + 1);
+        if self.order.is_zero()
+            || !self.ladder(&scalar, self.base).is_infinity()
+        {
+            return Err(CurveError::InvalidOrder);
+        }
+
+        let points = &self.order * &BigUint::from(self.cofactor);
+        if !within_hasse_bound::<F>(&points) {
+            return Err(CurveError::InvalidOrder);
+        }
+        Ok(())
+    }
+
+    /// Whether `x` is the x-coordinate of a point on the curve, that is
+    /// `x^3 + Ax^2 + x` is a square. Otherwise it is on the twist
+    pub fn is_on_curve(&self, x: F) -> bool {
+        (x.square() * x + self.a * x.square() + x).is_square()
+    }
+
+    pub fn double(&self, point: XzPoint<F>) -> XzPoint<F> {
+        let aa = (point.x + point.z).square();
+        let bb = (point.x - point.z).square();
+        let e = aa - bb;
+        XzPoint {
+            x: aa * bb,
+            z: e * (aa + self.a24 * e),
+        }
+    }
+
+    /// `p + q` given the affine x-coordinate of `p - q`, which must not be
+    /// the point at infinity
+    pub fn differential_add(
+        &self,
+        p: XzPoint<F>,
+        q: XzPoint<F>,
+        difference: F,
+    ) -> XzPoint<F> {
+        let da = (q.x - q.z) * (p.x + p.z);
+        let cb = (q.x + q.z) * (p.x - p.z);
+        XzPoint {
+            x: (da + cb).square(),
+            z: difference * (da - cb).square(),
+        }
+    }
+
+    /// Montgomery ladder over every bit of the little endian `scalar`
+    /// applied to the point with x-coordinate `x`. The running time depends
+    /// on the length of the scalar but not its value
+    pub fn ladder(&self, scalar: &[u8], x: F) -> XzPoint<F> {
+        let mut r0 = XzPoint::INFINITY;
+        let mut r1 = XzPoint::from_affine(x);
+        let mut swap = 0;
+
+        for i in (0..scalar.len() * 8).rev() {
+            let bit = (scalar[i / 8] >> (i % 8)) & 1;
+            swap ^= bit;
+            XzPoint::conditional_swap(&mut r0, &mut r1, swap);
+            swap = bit;
+
+            r1 = self.differential_add(r0, r1, x);
+            r0 = self.double(r0);
+        }
+        XzPoint::conditional_swap(&mut r0, &mut r1, swap);
+        r0
+    }
+
+    /// x-coordinate of `scalar` times the point with x-coordinate `x`
+    pub fn scalar_mult(&self, scalar: &[u8], x: F) -> F {
+        self.ladder(scalar, x).to_affine()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::SmallField;
+
+    type F97 = SmallField<97>;
+
+    fn toy_curve() -> MontgomeryCurve<F97> {
+        MontgomeryCurve::new(
+            F97::from_u64(5),
+            F97::from_u64(3),
+            BigUint::from(22u64),
+            4,
+        )
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(toy_curve().validate(), Ok(()));
+
+        let mut singular = toy_curve();
+        singular.a = F97::from_u64(2);
+        assert_eq!(singular.validate(), Err(CurveError::Singular));
+
+        // 2 is on the twist of the toy curve, 2^3 + 5 * 2^2 + 2 = 30 is not
+        // a square mod 97
+        let mut twist = toy_curve();
+        twist.base = F97::from_u64(2);
+        assert_eq!(twist.validate(), Err(CurveError::BaseNotOnCurve));
+
+        let mut wrong_order = toy_curve();
+        wrong_order.order = BigUint::from(11u64);
+        wrong_order.cofactor = 8;
+        assert_eq!(wrong_order.validate(), Err(CurveError::InvalidOrder));
+    }
+
+    /// x-coordinates of 1G to 21G worked out with affine addition, 11G is
+    /// the point (0, 0) of order 2
+    #[test]
+    fn test_ladder_multiples() {
+        let expected = [
+            3, 61, 36, 18, 89, 12, 27, 62, 35, 65, 0, 65, 35, 62, 27, 12, 89,
+            18, 36, 61, 3,
+        ];
+        let curve = toy_curve();
+        let base = XzPoint::from_affine(curve.base);
+        assert_eq!(curve.double(base).to_affine().value(), 61);
+
+        for (k, x) in (1..22u8).zip(expected) {
+            assert_eq!(curve.scalar_mult(&[k], curve.base).value(), x);
+        }
+        assert!(curve.ladder(&[22], curve.base).is_infinity());
+        assert!(!curve.ladder(&[11], curve.base).is_infinity());
+    }
+}
@@ -0,0 +1,165 @@
+//! The NIST prime curves P-256 and P-384 of FIPS 186-4 appendix D.1.2,
+//! both `y^2 = x^3 - 3x + b` with a base point of prime order
+
+use crate::bigint::BigUint;
+use crate::primefield::{FieldElement, Modulus};
+use crate::weierstrass::{AffinePoint, WeierstrassCurve};
+
+/// The P-256 prime, 2^256 - 2^224 + 2^192 + 2^96 - 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct P256;
+
+impl Modulus for P256 {
+    const LIMBS: usize = 4;
+    const P: [u64; 6] = [
+        0xffffffffffffffff,
+        0x00000000ffffffff,
+        0x0000000000000000,
+        0xffffffff00000001,
+        0,
+        0,
+    ];
+}
+
+/// The P-384 prime, 2^384 - 2^128 - 2^96 + 2^32 - 1
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct P384;
+
+impl Modulus for P384 {
+    const LIMBS: usize = 6;
+    const P: [u64; 6] = [
+        0x00000000ffffffff,
+        0xffffffff00000000,
+        0xfffffffffffffffe,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+    ];
+}
+
+/// Builds a curve with a = -3 from the big endian hex of b, the base point
+/// and the order
+fn nist_curve<M: Modulus>(
+    b: &str,
+    gx: &str,
+    gy: &str,
+    order: &str,
+) -> WeierstrassCurve<FieldElement<M>> {
+    let element = |hex: &str| {
+        let bytes = BigUint::from_hex(hex)
+            .unwrap()
+            .to_be_bytes_padded(FieldElement::<M>::BYTES);
+        FieldElement::from_be_bytes(&bytes).unwrap()
+    };
+    WeierstrassCurve::new(
+        -FieldElement::from_u64(3),
+        element(b),
+        AffinePoint {
+            x: element(gx),
+            y: element(gy),
+        },
+        BigUint::from_hex(order).unwrap(),
+        1,
+    )
+}
+
+pub fn p256() -> WeierstrassCurve<FieldElement<P256>> {
+    nist_curve(
+        "5ac635d8aa3a93e7b3ebbd55769886bc651d06b0cc53b0f63bce3c3e27d2604b",
+        "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+        "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+        "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551",
+    )
+}
+
+pub fn p384() -> WeierstrassCurve<FieldElement<P384>> {
+    nist_curve(
+        "b3312fa7e23ee7e4988e056be3f82d19181d9c6efe8141120314088f5013875a
+         c656398d8a2ed19d2a85c8edd3ec2aef",
+        "aa87ca22be8b05378eb1c71ef320ad746e1d3b628ba79b9859f741e082542a38
+         5502f25dbf55296c3a545e3872760ab7",
+        "3617de4a96262c6f5d9e98bf9292dc29f8f41dbd289a147ce9da3113b5f0b8c0
+         0a60b1ce1d7e819d7a431d7c90ea0e5f",
+        "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf
+         581a0db248b0a77aecec196accc52973",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::Field;
+    use crate::hash::hex;
+    use crate::weierstrass::{JacobianPoint, PointError};
+
+    #[test]
+    fn test_curve_parameters() {
+        assert_eq!(p256().validate(), Ok(()));
+        assert_eq!(p384().validate(), Ok(()));
+        assert_eq!(
+            FieldElement::<P256>::modulus(),
+            BigUint::from_hex(
+                "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff"
+            )
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sec1_roundtrip() {
+        let curve = p256();
+        let base = curve.base;
+        let uncompressed = curve.encode_point(&base, false);
+        assert_eq!(uncompressed.len(), 65);
+        assert_eq!(curve.decode_point(&uncompressed), Ok(base));
+        // the base point's y ends in 0xf5 so it compresses with tag 03
+        let compressed = curve.encode_point(&base, true);
+        assert_eq!(
+            compressed,
+            hex("036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296")
+        );
+        assert_eq!(curve.decode_point(&compressed), Ok(base));
+
+        let doubled = curve
+            .double(&JacobianPoint::from_affine(&base))
+            .to_affine()
+            .unwrap();
+        let compressed = curve.encode_point(&doubled, true);
+        assert_eq!(curve.decode_point(&compressed), Ok(doubled));
+
+        let curve = p384();
+        for compressed in [false, true] {
+            let encoded = curve.encode_point(&curve.base, compressed);
+            assert_eq!(curve.decode_point(&encoded), Ok(curve.base));
+        }
+    }
+
+    #[test]
+    fn test_sec1_rejects() {
+        let curve = p256();
+        let mut encoded = curve.encode_point(&curve.base, false);
+        assert_eq!(curve.decode_point(&[0]), Err(PointError::InvalidEncoding));
+        assert_eq!(
+            curve.decode_point(&encoded[..64]),
+            Err(PointError::InvalidEncoding)
+        );
+        encoded[64] ^= 1;
+        assert_eq!(curve.decode_point(&encoded), Err(PointError::NotOnCurve));
+        encoded[0] = 0x05;
+        assert_eq!(
+            curve.decode_point(&encoded),
+            Err(PointError::InvalidEncoding)
+        );
+
+        // x = p is not reduced, x = 1 gives a y^2 that is not a square
+        let mut compressed = vec![0x02];
+        compressed.extend(FieldElement::<P256>::modulus().to_be_bytes());
+        assert_eq!(
+            curve.decode_point(&compressed),
+            Err(PointError::InvalidEncoding)
+        );
+        let mut one = vec![0x02];
+        one.extend(FieldElement::<P256>::ONE.to_be_bytes());
+        assert_eq!(curve.decode_point(&one), Err(PointError::NotOnCurve));
+    }
+}
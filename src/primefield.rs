@@ -0,0 +1,379 @@
+//! GF(p) for a fixed prime of up to 384 bits, the fields under the NIST
+//! curves, with elements kept in Montgomery form
+//!
+//! Unlike [`crate::field25519`] and [`crate::field448`] nothing here uses
+//! the shape of p, so one implementation serves every [`Modulus`]. Addition,
+//! subtraction and multiplication do not branch on the values of elements
+
+use crate::bigint::BigUint;
+use crate::field::Field;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Room for the largest supported modulus
+const MAX_LIMBS: usize = 6;
+
+/// A prime p, given as little endian 64 bit limbs. The Montgomery constants
+/// are derived from it at compile time
+pub trait Modulus: Copy + Eq + Debug {
+    /// The number of limbs p occupies, at most 6. Elements are encoded in
+    /// 8 bytes per limb
+    const LIMBS: usize;
+    /// p, the limbs past [`Modulus::LIMBS`] zero
+    const P: [u64; MAX_LIMBS];
+
+    /// `-p^-1 mod 2^64`
+    const P_INV: u64 = neg_inverse(Self::P[0]);
+    /// `R mod p` with `R = 2^(64 * LIMBS)`, one in Montgomery form
+    const R: [u64; MAX_LIMBS] =
+        pow2_mod(64 * Self::LIMBS, &Self::P, Self::LIMBS);
+    /// `R^2 mod p`, which converts into Montgomery form
+    const R2: [u64; MAX_LIMBS] =
+        pow2_mod(128 * Self::LIMBS, &Self::P, Self::LIMBS);
+}
+
+/// Newton's iteration doubles the correct low bits of the inverse
+const fn neg_inverse(p0: u64) -> u64 {
+    let mut inverse: u64 = 1;
+    let mut i = 0;
+    while i < 6 {
+        inverse =
+            inverse.wrapping_mul(2u64.wrapping_sub(p0.wrapping_mul(inverse)));
+        i += 1;
+    }
+    inverse.wrapping_neg()
+}
+
+/// `2^k mod p` by repeated doubling
+const fn pow2_mod(
+    k: usize,
+    p: &[u64; MAX_LIMBS],
+    n: usize,
+) -> [u64; MAX_LIMBS] {
+    let mut value = [0u64; MAX_LIMBS];
+    value[0] = 1;
+    let mut step = 0;
+    while step < k {
+        let mut carry = 0;
+        let mut i = 0;
+        while i < n {
+            let next = value[i] >> 63;
+            value[i] = (value[i] << 1) | carry;
+            carry = next;
+            i += 1;
+        }
+        if carry == 1 || !less_than(&value, p, n) {
+            let mut borrow = 0;
+            let mut i = 0;
+            while i < n {
+                let (d, b1) = value[i].overflowing_sub(p[i]);
+                let (d, b2) = d.overflowing_sub(borrow);
+                value[i] = d;
+                borrow = (b1 | b2) as u64;
+                i += 1;
+            }
+        }
+        step += 1;
+    }
+    value
+}
+
+const fn less_than(
+    a: &[u64; MAX_LIMBS],
+    b: &[u64; MAX_LIMBS],
+    n: usize,
+) -> bool {
+    let mut i = n;
+    while i > 0 {
+        i -= 1;
+        if a[i] != b[i] {
+            return a[i] < b[i];
+        }
+    }
+    false
+}
+
+/// An element of GF(p) in Montgomery form, always fully reduced so equal
+/// elements have equal limbs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldElement<M: Modulus>([u64; MAX_LIMBS], PhantomData<M>);
+
+impl<M: Modulus> FieldElement<M> {
+    pub const ZERO: Self = Self([0; MAX_LIMBS], PhantomData);
+    pub const ONE: Self = Self(M::R, PhantomData);
+
+    /// The length of [`FieldElement::to_be_bytes`]
+    pub const BYTES: usize = 8 * M::LIMBS;
+
+    pub fn from_u64(value: u64) -> Self {
+        let mut limbs = [0; MAX_LIMBS];
+        limbs[0] = value;
+        Self(limbs, PhantomData).montgomery_mul(&M::R2)
+    }
+
+    /// Decodes a big endian element of [`FieldElement::BYTES`] bytes,
+    /// `None` for the wrong length or a value that is not below p
+    pub fn from_be_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTES {
+            return None;
+        }
+        let mut limbs = [0; MAX_LIMBS];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks_exact(8)) {
+            *limb = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        if !less_than(&limbs, &M::P, M::LIMBS) {
+            return None;
+        }
+        Some(Self(limbs, PhantomData).montgomery_mul(&M::R2))
+    }
+
+    /// The canonical big endian encoding
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let limbs = self.canonical_limbs();
+        limbs[..M::LIMBS]
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes())
+            .collect()
+    }
+
+    /// The element as an integer in `[0, p)`
+    pub fn to_biguint(&self) -> BigUint {
+        BigUint::from_be_bytes(&self.to_be_bytes())
+    }
+
+    /// Whether the canonical value is odd, the bit SEC1 compression keeps
+    pub fn is_odd(&self) -> bool {
+        self.canonical_limbs()[0] & 1 == 1
+    }
+
+    fn canonical_limbs(&self) -> [u64; MAX_LIMBS] {
+        let mut one = [0; MAX_LIMBS];
+        one[0] = 1;
+        self.montgomery_mul(&one).0
+    }
+
+    /// `self * other / R mod p` by coarsely integrated operand scanning.
+    /// The result is reduced as long as `other` is below p
+    fn montgomery_mul(&self, other: &[u64; MAX_LIMBS]) -> Self {
+        let n = M::LIMBS;
+        let p = &M::P;
+        let mut t = [0u64; MAX_LIMBS + 2];
+        for &b in &other[..n] {
+            let mut carry = 0;
+            for (limb, &a) in t[..n].iter_mut().zip(&self.0[..n]) {
+                let sum = *limb as u128 + a as u128 * b as u128 + carry;
+                *limb = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128 + carry;
+            t[n] = sum as u64;
+            t[n + 1] = (sum >> 64) as u64;
+
+            // add the multiple of p that clears the lowest limb, then drop it
+            let u = t[0].wrapping_mul(M::P_INV);
+            let mut carry = (t[0] as u128 + u as u128 * p[0] as u128) >> 64;
+            for j in 1..n {
+                let sum = t[j] as u128 + u as u128 * p[j] as u128 + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[n] as u128 + carry;
+            t[n - 1] = sum as u64;
+            t[n] = t[n + 1] + (sum >> 64) as u64;
+            t[n + 1] = 0;
+        }
+
+        let mut limbs = [0; MAX_LIMBS];
+        limbs[..n].copy_from_slice(&t[..n]);
+        Self(reduce_once::<M>(limbs, t[n]), PhantomData)
+    }
+
+    /// Swaps `a` and `b` if `choice` is 1 and leaves them if it is 0,
+    /// without branching on it
+    pub fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        let mask = 0u64.wrapping_sub(choice as u64);
+        for (x, y) in a.0.iter_mut().zip(b.0.iter_mut()) {
+            let t = mask & (*x ^ *y);
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+}
+
+/// Subtracts p from the value `limbs + 2^(64 * LIMBS) * carry`, known to
+/// be below 2p, if it is at least p. The choice is made with a mask
+fn reduce_once<M: Modulus>(
+    limbs: [u64; MAX_LIMBS],
+    carry: u64,
+) -> [u64; MAX_LIMBS] {
+    let mut reduced = [0; MAX_LIMBS];
+    let mut borrow = 0;
+    for i in 0..M::LIMBS {
+        let (d, b1) = limbs[i].overflowing_sub(M::P[i]);
+        let (d, b2) = d.overflowing_sub(borrow);
+        reduced[i] = d;
+        borrow = (b1 | b2) as u64;
+    }
+    // keep the value as it was if subtracting p borrowed past the carry
+    let keep = 0u64.wrapping_sub(borrow & !carry & 1);
+    let mut result = [0; MAX_LIMBS];
+    for i in 0..M::LIMBS {
+        result[i] = (limbs[i] & keep) | (reduced[i] & !keep);
+    }
+    result
+}
+
+impl<M: Modulus> Field for FieldElement<M> {
+    const ZERO: Self = Self::ZERO;
+    const ONE: Self = Self::ONE;
+
+    fn modulus() -> BigUint {
+        let bytes: Vec<u8> =
+            M::P.iter().flat_map(|l| l.to_le_bytes()).collect();
+        BigUint::from_le_bytes(&bytes)
+    }
+
+    fn from_u64(value: u64) -> Self {
+        Self::from_u64(value)
+    }
+
+    /// `self^(p - 2)`, zero maps to zero
+    fn invert(&self) -> Self {
+        self.pow(&(Self::modulus() - BigUint::from(2u64)))
+    }
+
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        Self::conditional_swap(a, b, choice)
+    }
+}
+
+impl<M: Modulus> Add for FieldElement<M> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut limbs = [0; MAX_LIMBS];
+        let mut carry = 0;
+        for (i, limb) in limbs.iter_mut().enumerate().take(M::LIMBS) {
+            let (s, c1) = self.0[i].overflowing_add(other.0[i]);
+            let (s, c2) = s.overflowing_add(carry);
+            *limb = s;
+            // at most one of the two additions can overflow
+            carry = c1 as u64 + c2 as u64;
+        }
+        Self(reduce_once::<M>(limbs, carry), PhantomData)
+    }
+}
+
+impl<M: Modulus> Sub for FieldElement<M> {
+    type Output = Self;
+
+    /// Subtracts and adds p back under a mask if that borrowed
+    fn sub(self, other: Self) -> Self {
+        let mut limbs = [0; MAX_LIMBS];
+        let mut borrow = 0;
+        for (i, limb) in limbs.iter_mut().enumerate().take(M::LIMBS) {
+            let (d, b1) = self.0[i].overflowing_sub(other.0[i]);
+            let (d, b2) = d.overflowing_sub(borrow);
+            *limb = d;
+            borrow = (b1 | b2) as u64;
+        }
+        let mask = 0u64.wrapping_sub(borrow);
+        let mut carry = 0;
+        for (i, limb) in limbs.iter_mut().enumerate().take(M::LIMBS) {
+            let (s, c1) = limb.overflowing_add(M::P[i] & mask);
+            let (s, c2) = s.overflowing_add(carry);
+            *limb = s;
+            // at most one of the two additions can overflow
+            carry = c1 as u64 + c2 as u64;
+        }
+        Self(limbs, PhantomData)
+    }
+}
+
+impl<M: Modulus> Neg for FieldElement<M> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::ZERO - self
+    }
+}
+
+impl<M: Modulus> Mul for FieldElement<M> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        self.montgomery_mul(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nist::P256;
+
+    /// 2^127 - 1 in two of the six limbs
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    struct Mersenne127;
+
+    impl Modulus for Mersenne127 {
+        const LIMBS: usize = 2;
+        const P: [u64; 6] = [u64::MAX, u64::MAX >> 1, 0, 0, 0, 0];
+    }
+
+    type F = FieldElement<P256>;
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let bytes: Vec<u8> = (1..=32).collect();
+        assert_eq!(F::from_be_bytes(&bytes).unwrap().to_be_bytes(), bytes);
+
+        let p = F::modulus().to_be_bytes_padded(32);
+        assert_eq!(F::from_be_bytes(&p), None);
+        let p_minus_one = (F::modulus() - BigUint::one()).to_be_bytes();
+        assert_eq!(F::from_be_bytes(&p_minus_one).unwrap(), -F::ONE);
+        assert_eq!(F::from_be_bytes(&[1; 31]), None);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let modulus = F::modulus();
+        let a = BigUint::from_hex(
+            "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+        )
+        .unwrap();
+        let b = BigUint::from_hex(
+            "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5",
+        )
+        .unwrap();
+        let (x, y) = (
+            F::from_be_bytes(&a.to_be_bytes_padded(32)).unwrap(),
+            F::from_be_bytes(&b.to_be_bytes_padded(32)).unwrap(),
+        );
+        assert_eq!((x * y).to_biguint(), a.mul_mod(&b, &modulus));
+        assert_eq!((x + y).to_biguint(), a.add_mod(&b, &modulus));
+        assert_eq!((x - y).to_biguint(), a.sub_mod(&b, &modulus));
+        assert_eq!((y - x).to_biguint(), b.sub_mod(&a, &modulus));
+        assert_eq!(x * x.invert(), F::ONE);
+        assert_eq!(F::from_u64(7).to_biguint(), BigUint::from(7u64));
+        assert!(F::ZERO.invert().is_zero());
+        assert!((x + -x).is_zero());
+
+        let root = (x * x).sqrt().unwrap();
+        assert!(root == x || root == -x);
+    }
+
+    #[test]
+    fn test_small_modulus() {
+        type G = FieldElement<Mersenne127>;
+        let modulus = G::modulus();
+        assert_eq!(modulus, (BigUint::one() << 127) - BigUint::one());
+        let x = G::from_u64(u64::MAX);
+        let expected =
+            BigUint::from(u64::MAX).mul_mod(&BigUint::from(u64::MAX), &modulus);
+        assert_eq!((x * x).to_biguint(), expected);
+        assert_eq!((-G::ONE).to_biguint(), &modulus - &BigUint::one());
+        assert_eq!(x * x.invert(), G::ONE);
+    }
+}
@@ -0,0 +1,111 @@
+//! Deterministic nonce generation for DSA and ECDSA (RFC 6979)
+
+use crate::bigint::BigUint;
+use crate::hash::{Digest, Hmac};
+
+/// HMAC_DRBG seeded with the private key and message hash, yielding the
+/// candidate nonces of RFC 6979 section 3.2 in order
+pub struct NonceGenerator<D: Digest> {
+    q: BigUint,
+    k: Vec<u8>,
+    v: Vec<u8>,
+    _digest: std::marker::PhantomData<D>,
+}
+
+impl<D: Digest> NonceGenerator<D> {
+    /// `x` is the private key and `hash` the hash of the message being
+    /// signed, both for the group of order `q`
+    pub fn new(q: &BigUint, x: &BigUint, hash: &[u8]) -> Self {
+        let mut key_material = int2octets(x, q);
+        key_material.extend(bits2octets(hash, q));
+
+        let mut generator = Self {
+            q: q.clone(),
+            k: vec![0x00; D::OUTPUT_SIZE],
+            v: vec![0x01; D::OUTPUT_SIZE],
+            _digest: std::marker::PhantomData,
+        };
+        generator.reseed(&[0x00], &key_material);
+        generator.reseed(&[0x01], &key_material);
+        generator
+    }
+
+    /// K = HMAC_K(V || marker || data), V = HMAC_K(V)
+    fn reseed(&mut self, marker: &[u8], data: &[u8]) {
+        let mut mac = Hmac::<D>::new(&self.k);
+        mac.update(&self.v);
+        mac.update(marker);
+        mac.update(data);
+        self.k = mac.finalize();
+        self.v = self.hmac_v();
+    }
+
+    fn hmac_v(&self) -> Vec<u8> {
+        let mut mac = Hmac::<D>::new(&self.k);
+        mac.update(&self.v);
+        mac.finalize()
+    }
+
+    /// The next nonce in `[1, q - 1]`, call again if the one returned
+    /// produced an invalid signature
+    pub fn next_k(&mut self) -> BigUint {
+        let qlen = self.q.bits();
+        loop {
+            let mut t = Vec::new();
+            while t.len() * 8 < qlen {
+                self.v = self.hmac_v();
+                t.extend_from_slice(&self.v);
+            }
+
+            let k = bits2int(&t, qlen);
+            self.reseed(&[0x00], &[]);
+            if !k.is_zero() && k < self.q {
+                return k;
+            }
+        }
+    }
+}
+
+/// The leftmost `qlen` bits of `bits` as an integer
+pub fn bits2int(bits: &[u8], qlen: usize) -> BigUint {
+    let value = BigUint::from_be_bytes(bits);
+    let blen = bits.len() * 8;
+    if blen > qlen {
+        value >> (blen - qlen)
+    } else {
+        value
+    }
+}
+
+fn int2octets(value: &BigUint, q: &BigUint) -> Vec<u8> {
+    value.to_be_bytes_padded(q.bits().div_ceil(8))
+}
+
+fn bits2octets(bits: &[u8], q: &BigUint) -> Vec<u8> {
+    let z = bits2int(bits, q.bits());
+    let z = if &z >= q { &z - q } else { z };
+    int2octets(&z, q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha2::Sha256;
+
+    /// RFC 6979 A.1.2, the nonce for the 163 bit toy curve order
+    #[test]
+    fn test_rfc6979_nonce() {
+        let q = BigUint::from_hex("4000000000000000000020108A2E0CC0D99F8A5EF")
+            .unwrap();
+        let x = BigUint::from_hex("09A4D6792295A7F730FC3F2B49CBC0F62E862272F")
+            .unwrap();
+        let hash = Sha256::digest(b"sample");
+
+        let mut generator = NonceGenerator::<Sha256>::new(&q, &x, &hash);
+        assert_eq!(
+            generator.next_k(),
+            BigUint::from_hex("23AF4074C90A02B3FE61D286D5C87F425E6BDD81B")
+                .unwrap()
+        );
+    }
+}
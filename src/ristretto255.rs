@@ -0,0 +1,421 @@
+//! ristretto255 (RFC 9496), a group of prime order
+//! 2^252 + 27742317777372353535851937790883648493 built on edwards25519
+//!
+//! Each element is a class of four curve points that differ by a point of
+//! order 4. Encoding picks one canonical representative, so two points in
+//! the same class encode to the same 32 bytes and compare equal, and the
+//! cofactor of the curve never shows through to protocols on top
+
+use crate::ed25519::edwards25519;
+use crate::edwards::{EdwardsCurve, EdwardsPoint};
+use crate::field25519::FieldElement;
+use std::ops::{Add, Neg, Sub};
+
+/// sqrt(a * d - 1), the negative root, little endian
+const SQRT_AD_MINUS_ONE: [u8; 32] = [
+    0x1b, 0x2e, 0x7b, 0x49, 0xa0, 0xf6, 0x97, 0x7e, 0xbd, 0x54, 0x78, 0x1b,
+    0x0c, 0x8e, 0x9d, 0xaf, 0xfd, 0xd1, 0xf5, 0x31, 0xc9, 0xfc, 0x3c, 0x0f,
+    0xac, 0x48, 0x83, 0x2b, 0xbf, 0x31, 0x69, 0x37,
+];
+
+/// 1 / sqrt(a - d), the non-negative root
+const INVSQRT_A_MINUS_D: [u8; 32] = [
+    0xea, 0x40, 0x5d, 0x80, 0xaa, 0xfd, 0xc8, 0x99, 0xbe, 0x72, 0x41, 0x5a,
+    0x17, 0x16, 0x2f, 0x9d, 0x40, 0xd8, 0x01, 0xfe, 0x91, 0x7b, 0xc2, 0x16,
+    0xa2, 0xfc, 0xaf, 0xcf, 0x05, 0x89, 0x6c, 0x78,
+];
+
+/// 1 - d^2
+const ONE_MINUS_D_SQ: [u8; 32] = [
+    0x76, 0xc1, 0x5f, 0x94, 0xc1, 0x09, 0x7c, 0xe2, 0x0f, 0x35, 0x5e, 0xcd,
+    0x38, 0xa1, 0x81, 0x2c, 0xe4, 0xdf, 0x70, 0xbe, 0xdd, 0xab, 0x94, 0x99,
+    0xd7, 0xe0, 0xb3, 0xb2, 0xa8, 0x72, 0x90, 0x02,
+];
+
+/// (d - 1)^2
+const D_MINUS_ONE_SQ: [u8; 32] = [
+    0x20, 0x4d, 0xed, 0x44, 0xaa, 0x5a, 0xad, 0x31, 0x99, 0x19, 0x1e, 0xb0,
+    0x2c, 0x4a, 0x9e, 0xd2, 0xeb, 0x4e, 0x9b, 0x52, 0x2f, 0xd3, 0xdc, 0x4c,
+    0x41, 0x22, 0x6c, 0xf6, 0x7a, 0xb3, 0x68, 0x59,
+];
+
+fn constant(bytes: &[u8; 32]) -> FieldElement {
+    FieldElement::from_bytes(bytes)
+}
+
+/// `b` if `choice` is 1, otherwise `a`
+fn select(a: FieldElement, b: FieldElement, choice: bool) -> FieldElement {
+    let (mut a, mut b) = (a, b);
+    FieldElement::conditional_swap(&mut a, &mut b, choice as u8);
+    a
+}
+
+/// The non-negative one of `x` and `-x`
+fn abs(x: FieldElement) -> FieldElement {
+    select(x, -x, x.is_negative())
+}
+
+/// SQRT_RATIO_M1 of the RFC: whether `u / v` is a square, and the
+/// non-negative root of `u / v` if so or of `SQRT_M1 * u / v` if not.
+/// Unlike [`FieldElement::sqrt_ratio`] the second case still returns a
+/// value, which the map to the group relies on
+fn sqrt_ratio_m1(u: FieldElement, v: FieldElement) -> (bool, FieldElement) {
+    let v3 = v.square() * v;
+    let v7 = v3.square() * v;
+    let r = u * v3 * (u * v7).pow_p58();
+    let check = v * r.square();
+
+    let correct = check == u;
+    let flipped = check == -u;
+    let flipped_i = check == -u * FieldElement::SQRT_M1;
+    let r = select(r, r * FieldElement::SQRT_M1, flipped || flipped_i);
+    (correct || flipped, abs(r))
+}
+
+/// An element of the group, held as any one of the four edwards25519
+/// points in its class
+#[derive(Clone, Copy, Debug)]
+pub struct RistrettoPoint(EdwardsPoint<FieldElement>);
+
+impl RistrettoPoint {
+    pub const IDENTITY: Self = Self(EdwardsPoint::IDENTITY);
+
+    /// The class of the Ed25519 base point
+    pub fn generator() -> Self {
+        Self(edwards25519().base)
+    }
+
+    /// Decoding of RFC 9496 section 4.3.1. Rejects a non-canonical or
+    /// negative field element and anything that is not the encoding of a
+    /// group element
+    pub fn decode(bytes: &[u8; 32]) -> Option<Self> {
+        let s = FieldElement::from_bytes(bytes);
+        if s.to_bytes() != *bytes || s.is_negative() {
+            return None;
+        }
+
+        let d = edwards25519().d;
+        let ss = s.square();
+        let u1 = FieldElement::ONE - ss;
+        let u2 = FieldElement::ONE + ss;
+        let u2_sqr = u2.square();
+        let v = -(d * u1.square()) - u2_sqr;
+        let (was_square, invsqrt) =
+            sqrt_ratio_m1(FieldElement::ONE, v * u2_sqr);
+
+        let den_x = invsqrt * u2;
+        let den_y = invsqrt * den_x * v;
+        let x = abs(s * den_x + s * den_x);
+        let y = u1 * den_y;
+        let t = x * y;
+        if !was_square || t.is_negative() || y.is_zero() {
+            return None;
+        }
+        Some(Self(EdwardsPoint {
+            x,
+            y,
+            z: FieldElement::ONE,
+            t,
+        }))
+    }
+
+    /// Encoding of RFC 9496 section 4.3.2, the same bytes for every point
+    /// in the class
+    pub fn encode(&self) -> [u8; 32] {
+        let EdwardsPoint {
+            x: x0,
+            y: y0,
+            z: z0,
+            t: t0,
+        } = self.0;
+        let u1 = (z0 + y0) * (z0 - y0);
+        let u2 = x0 * y0;
+        let (_, invsqrt) = sqrt_ratio_m1(FieldElement::ONE, u1 * u2.square());
+        let den1 = invsqrt * u1;
+        let den2 = invsqrt * u2;
+        let z_inv = den1 * den2 * t0;
+
+        let ix0 = x0 * FieldElement::SQRT_M1;
+        let iy0 = y0 * FieldElement::SQRT_M1;
+        let enchanted_denominator = den1 * constant(&INVSQRT_A_MINUS_D);
+        let rotate = (t0 * z_inv).is_negative();
+        let x = select(x0, iy0, rotate);
+        let y = select(y0, ix0, rotate);
+        let den_inv = select(den2, enchanted_denominator, rotate);
+
+        let y = select(y, -y, (x * z_inv).is_negative());
+        abs(den_inv * (z0 - y)).to_bytes()
+    }
+
+    /// The hash to group of RFC 9496 section 4.3.4, for 64 uniformly
+    /// random bytes such as the output of SHA-512. Each half is mapped to
+    /// a point and the two are added, so the result is close to uniform
+    pub fn from_uniform_bytes(bytes: &[u8; 64]) -> Self {
+        let half = |range: std::ops::Range<usize>| {
+            let mut half: [u8; 32] = bytes[range].try_into().unwrap();
+            half[31] &= 0x7f;
+            map(FieldElement::from_bytes(&half))
+        };
+        Self(edwards25519().add(&half(0..32), &half(32..64)))
+    }
+
+    /// `[scalar]self` for a little endian scalar of any length
+    pub fn mul(&self, scalar: &[u8]) -> Self {
+        Self(edwards25519().scalar_mult(scalar, &self.0))
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+}
+
+/// The MAP of RFC 9496 section 4.3.4, an Elligator variant from a field
+/// element to a curve point
+fn map(t: FieldElement) -> EdwardsPoint<FieldElement> {
+    let EdwardsCurve { d, .. } = edwards25519();
+    let one = FieldElement::ONE;
+    let r = FieldElement::SQRT_M1 * t.square();
+    let u = (r + one) * constant(&ONE_MINUS_D_SQ);
+    let v = (-one - r * d) * (r + d);
+    let (was_square, s) = sqrt_ratio_m1(u, v);
+
+    let s_prime = -abs(s * t);
+    let s = select(s_prime, s, was_square);
+    let c = select(r, -one, was_square);
+    let n = c * (r - one) * constant(&D_MINUS_ONE_SQ) - v;
+
+    let w0 = (s + s) * v;
+    let w1 = n * constant(&SQRT_AD_MINUS_ONE);
+    let w2 = one - s.square();
+    let w3 = one + s.square();
+    EdwardsPoint {
+        x: w0 * w3,
+        y: w2 * w1,
+        z: w1 * w3,
+        t: w0 * w2,
+    }
+}
+
+/// Equality of classes as in RFC 9496 section 4.5,
+/// `x1 y2 = y1 x2` or `y1 y2 = x1 x2`
+impl PartialEq for RistrettoPoint {
+    fn eq(&self, other: &Self) -> bool {
+        let (p, q) = (&self.0, &other.0);
+        p.x * q.y == p.y * q.x || p.y * q.y == p.x * q.x
+    }
+}
+
+impl Eq for RistrettoPoint {}
+
+impl Add for RistrettoPoint {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(edwards25519().add(&self.0, &other.0))
+    }
+}
+
+impl Sub for RistrettoPoint {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + -other
+    }
+}
+
+impl Neg for RistrettoPoint {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ed25519::order;
+    use crate::hash::{hex, Digest};
+    use crate::sha2::Sha512;
+
+    fn bytes(s: &str) -> [u8; 32] {
+        hex(s).try_into().unwrap()
+    }
+
+    /// Appendix A.1, the encodings of [0]B to [15]B
+    const MULTIPLES: [&str; 16] = [
+        "0000000000000000000000000000000000000000000000000000000000000000",
+        "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+        "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919",
+        "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259",
+        "da80862773358b466ffadfe0b3293ab3d9fd53c5ea6c955358f568322daf6a57",
+        "e882b131016b52c1d3337080187cf768423efccbb517bb495ab812c4160ff44e",
+        "f64746d3c92b13050ed8d80236a7f0007c3b3f962f5ba793d19a601ebb1df403",
+        "44f53520926ec81fbd5a387845beb7df85a96a24ece18738bdcfa6a7822a176d",
+        "903293d8f2287ebe10e2374dc1a53e0bc887e592699f02d077d5263cdd55601c",
+        "02622ace8f7303a31cafc63f8fc48fdc16e1c8c8d234b2f0d6685282a9076031",
+        "20706fd788b2720a1ed2a5dad4952b01f413bcf0e7564de8cdc816689e2db95f",
+        "bce83f8ba5dd2fa572864c24ba1810f9522bc6004afe95877ac73241cafdab42",
+        "e4549ee16b9aa03099ca208c67adafcafa4c3f3e4e5303de6026e3ca8ff84460",
+        "aa52e000df2e16f55fb1032fc33bc42742dad6bd5a8fc0be0167436c5948501f",
+        "46376b80f409b29dc2b5f6f0c52591990896e5716f41477cd30085ab7f10301e",
+        "e0c418f7c8d9c4cdd7395b93ea124f3ad99021bb681dfc3302a9d99a2e53e64e",
+    ];
+
+    #[test]
+    fn test_small_multiples() {
+        let generator = RistrettoPoint::generator();
+        let mut point = RistrettoPoint::IDENTITY;
+        for (i, expected) in MULTIPLES.iter().enumerate() {
+            assert_eq!(point.encode(), bytes(expected), "[{i}]B");
+            assert_eq!(generator.mul(&[i as u8]), point, "[{i}]B");
+            let decoded = RistrettoPoint::decode(&bytes(expected)).unwrap();
+            assert_eq!(decoded, point, "[{i}]B");
+            assert_eq!(decoded.encode(), bytes(expected), "[{i}]B");
+            point = point + generator;
+        }
+    }
+
+    /// Appendix A.2: non-canonical field elements, negative field
+    /// elements, non-square x^2, negative xy and s = -1 giving y = 0
+    #[test]
+    fn test_bad_encodings() {
+        let bad = [
+            "00ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "f3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "ed57ffd8c914fb201471d1c3d245ce3c746fcbe63a3679d51b6a516ebebe0e20",
+            "c34c4e1826e5d403b78e246e88aa051c36ccf0aafebffe137d148a2bf9104562",
+            "c940e5a4404157cfb1628b108db051a8d439e1a421394ec4ebccb9ec92a8ac78",
+            "47cfc5497c53dc8e61c91d17fd626ffb1c49e2bca94eed052281b510b1117a24",
+            "f1c6165d33367351b0da8f6e4511010c68174a03b6581212c71c0e1d026c3c72",
+            "87260f7a2f12495118360f02c26a470f450dadf34a413d21042b43b9d93e1309",
+            "26948d35ca62e643e26a83177332e6b6afeb9d08e4268b650f1f5bbd8d81d371",
+            "4eac077a713c57b4f4397629a4145982c661f48044dd3f96427d40b147d9742f",
+            "de6a7b00deadc788eb6b6c8d20c0ae96c2f2019078fa604fee5b87d6e989ad7b",
+            "bcab477be20861e01e4a0e295284146a510150d9817763caf1a6f4b422d67042",
+            "2a292df7e32cababbd9de088d1d1abec9fc0440f637ed2fba145094dc14bea08",
+            "f4a9e534fc0d216c44b218fa0c42d99635a0127ee2e53c712f70609649fdff22",
+            "8268436f8c4126196cf64b3c7ddbda90746a378625f9813dd9b8457077256731",
+            "2810e5cbc2cc4d4eece54f61c6f69758e289aa7ab440b3cbeaa21995c2f4232b",
+            "3eb858e78f5a7254d8c9731174a94f76755fd3941c0ac93735c07ba14579630e",
+            "a45fdc55c76448c049a1ab33f17023edfb2be3581e9c7aade8a6125215e04220",
+            "d483fe813c6ba647ebbfd3ec41adca1c6130c2beeee9d9bf065c8d151c5f396e",
+            "8a2e1d30050198c65a54483123960ccc38aef6848e1ec8f5f780e8523769ba32",
+            "32888462f8b486c68ad7dd9610be5192bbeaf3b443951ac1a8118419d9fa097b",
+            "227142501b9d4355ccba290404bde41575b037693cef1f438c47f8fbf35d1165",
+            "5c37cc491da847cfeb9281d407efc41e15144c876e0170b499a96a22ed31e01e",
+            "445425117cb8c90edcbc7c1cc0e74f747f2c1efa5630a967c64f287792a48a4b",
+            "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        ];
+        for encoding in bad {
+            assert_eq!(
+                RistrettoPoint::decode(&bytes(encoding)),
+                None,
+                "{encoding}"
+            );
+        }
+    }
+
+    /// Appendix A.3, the group elements for SHA-512 of each label
+    #[test]
+    fn test_hash_to_group() {
+        let vectors = [
+            (
+                "Ristretto is traditionally a short shot of espresso coffee",
+                "3066f82a1a747d45120d1740f14358531a8f04bbffe6a819f86dfe50f44a0a46",
+            ),
+            (
+                "made with the normal amount of ground coffee but extracted with",
+                "f26e5b6f7d362d2d2a94c5d0e7602cb4773c95a2e5c31a64f133189fa76ed61b",
+            ),
+            (
+                "about half the amount of water in the same amount of time",
+                "006ccd2a9e6867e6a2c5cea83d3302cc9de128dd2a9a57dd8ee7b9d7ffe02826",
+            ),
+            (
+                "by using a finer grind.",
+                "f8f0c87cf237953c5890aec3998169005dae3eca1fbb04548c635953c817f92a",
+            ),
+            (
+                "This produces a concentrated shot of coffee per volume.",
+                "ae81e7dedf20a497e10c304a765c1767a42d6e06029758d2d7e8ef7cc4c41179",
+            ),
+            (
+                "Just pulling a normal shot short will produce a weaker shot",
+                "e2705652ff9f5e44d3e841bf1c251cf7dddb77d140870d1ab2ed64f1a9ce8628",
+            ),
+            (
+                "and is not a Ristretto as some believe.",
+                "80bd07262511cdde4863f8a7434cef696750681cb9510eea557088f76d9e5065",
+            ),
+        ];
+        for (label, expected) in vectors {
+            let hash = Sha512::digest(label.as_bytes());
+            let point =
+                RistrettoPoint::from_uniform_bytes(&hash.try_into().unwrap());
+            assert_eq!(point.encode(), bytes(expected), "{label}");
+        }
+    }
+
+    /// Inputs of A.3 that differ only in the masked top bits or by p in
+    /// each half, all landing on the same element
+    #[test]
+    fn test_hash_to_group_reduces() {
+        let inputs = [
+            "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+             1200000000000000000000000000000000000000000000000000000000000000",
+            "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f\
+             ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "0000000000000000000000000000000000000000000000000000000000000080\
+             ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "0000000000000000000000000000000000000000000000000000000000000000\
+             1200000000000000000000000000000000000000000000000000000000000080",
+        ];
+        let expected = bytes(
+            "304282791023b73128d277bdcb5c7746ef2eac08dde9f2983379cb8e5ef0517f",
+        );
+        for input in inputs {
+            let point = RistrettoPoint::from_uniform_bytes(
+                &hex(input).try_into().unwrap(),
+            );
+            assert_eq!(point.encode(), expected, "{input}");
+        }
+    }
+
+    /// Adding a point of order 4 moves to another representative of the
+    /// same class, which must compare and encode the same
+    #[test]
+    fn test_torsion_is_invisible() {
+        let torsion = EdwardsPoint::from_affine(
+            FieldElement::SQRT_M1,
+            FieldElement::ZERO,
+        );
+        let curve = edwards25519();
+        let mut point = RistrettoPoint::generator().mul(&[42]);
+        let encoding = point.encode();
+        for _ in 0..4 {
+            let moved = RistrettoPoint(curve.add(&point.0, &torsion));
+            assert_ne!(moved.0, point.0);
+            assert_eq!(moved, point);
+            assert_eq!(moved.encode(), encoding);
+            point = moved;
+        }
+    }
+
+    #[test]
+    fn test_group_laws() {
+        let generator = RistrettoPoint::generator();
+        let a = generator.mul(&[7]);
+        let b = generator.mul(&[5]);
+        assert_eq!(a - b, generator.mul(&[2]));
+        assert_eq!(a + -a, RistrettoPoint::IDENTITY);
+        assert!((a - a).is_identity());
+        let n = order().to_le_bytes_padded(32);
+        assert!(generator.mul(&n).is_identity());
+        assert!(!generator.is_identity());
+    }
+}
@@ -0,0 +1,210 @@
+//! Simplified AES (Musa, Schaefer and Wedig, 2003), the 16 bit block, 16
+//! bit key, two round teaching cipher that goes with the simplified DES in
+//! [`crate::des::Sdes`]
+//!
+//! The state is a 2x2 matrix of nibbles filled column by column from the
+//! most significant nibble, so `0xABCD` has `A C` on its top row
+
+/// The nibble S-box, the inverse in GF(2^4) followed by an affine map
+const S_BOX: [u8; 16] = [
+    0x9, 0x4, 0xA, 0xB, 0xD, 0x1, 0x8, 0x5, 0x6, 0x2, 0x0, 0x3, 0xC, 0xE, 0xF,
+    0x7,
+];
+
+const INVERSE_S_BOX: [u8; 16] = [
+    0xA, 0x5, 0x9, 0xB, 0x1, 0x7, 0x8, 0xF, 0x6, 0x0, 0x2, 0x3, 0xC, 0x4, 0xD,
+    0xE,
+];
+
+/// MixColumns and its inverse as matrices over GF(2^4)
+const MIX: [[u8; 2]; 2] = [[1, 4], [4, 1]];
+const INVERSE_MIX: [[u8; 2]; 2] = [[9, 2], [2, 9]];
+
+/// The round constants of the key expansion, `x^3` and `x^4` in GF(2^4)
+/// placed in the high nibble
+const ROUND_CONSTANTS: [u8; 2] = [0x80, 0x30];
+
+/// One step of a round, as listed by [`Saes::encrypt_steps`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaesStep {
+    /// XOR with the round key of the given round, 0 being the initial one
+    AddRoundKey(usize),
+    SubstituteNibbles,
+    ShiftRows,
+    MixColumns,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Saes {
+    round_keys: [u16; 3],
+}
+
+impl Saes {
+    /// Expands the key into three round keys. The key words are bytes
+    /// `w0 .. w5`, each pair making a round key
+    pub fn new(key: u16) -> Self {
+        let mut words = [(key >> 8) as u8, key as u8, 0, 0, 0, 0];
+        for (i, rcon) in ROUND_CONSTANTS.into_iter().enumerate() {
+            let last = words[2 * i + 1];
+            let rotated = last.rotate_left(4);
+            words[2 * i + 2] =
+                words[2 * i] ^ rcon ^ substitute(rotated as u16, &S_BOX) as u8;
+            words[2 * i + 3] = words[2 * i + 2] ^ last;
+        }
+        let round_keys = std::array::from_fn(|i| {
+            (words[2 * i] as u16) << 8 | words[2 * i + 1] as u16
+        });
+        Self { round_keys }
+    }
+
+    pub fn round_keys(&self) -> [u16; 3] {
+        self.round_keys
+    }
+
+    /// Every step of an encryption with the state after it, ending with the
+    /// ciphertext
+    pub fn encrypt_steps(&self, block: u16) -> Vec<(SaesStep, u16)> {
+        use SaesStep::*;
+
+        let steps = [
+            AddRoundKey(0),
+            SubstituteNibbles,
+            ShiftRows,
+            MixColumns,
+            AddRoundKey(1),
+            SubstituteNibbles,
+            ShiftRows,
+            AddRoundKey(2),
+        ];
+        let mut state = block;
+        steps
+            .into_iter()
+            .map(|step| {
+                state = match step {
+                    AddRoundKey(round) => state ^ self.round_keys[round],
+                    SubstituteNibbles => substitute(state, &S_BOX),
+                    ShiftRows => shift_rows(state),
+                    MixColumns => mix_columns(state, &MIX),
+                };
+                (step, state)
+            })
+            .collect()
+    }
+
+    pub fn encrypt(&self, block: u16) -> u16 {
+        self.encrypt_steps(block).last().unwrap().1
+    }
+
+    /// The inverse steps in reverse order. ShiftRows is its own inverse
+    pub fn decrypt(&self, block: u16) -> u16 {
+        let mut state = block ^ self.round_keys[2];
+        state = substitute(shift_rows(state), &INVERSE_S_BOX);
+        state = mix_columns(state ^ self.round_keys[1], &INVERSE_MIX);
+        state = substitute(shift_rows(state), &INVERSE_S_BOX);
+        state ^ self.round_keys[0]
+    }
+}
+
+fn substitute(state: u16, sbox: &[u8; 16]) -> u16 {
+    (0..16).step_by(4).fold(0, |output, shift| {
+        output | (sbox[(state >> shift) as usize & 0xF] as u16) << shift
+    })
+}
+
+/// Swaps the two nibbles of the bottom row
+fn shift_rows(state: u16) -> u16 {
+    state & 0xF0F0 | (state & 0x0F00) >> 8 | (state & 0x000F) << 8
+}
+
+fn mix_columns(state: u16, matrix: &[[u8; 2]; 2]) -> u16 {
+    [12, 4].into_iter().fold(0, |output, shift| {
+        let column = [
+            (state >> shift) as u8 & 0xF,
+            (state >> (shift - 4)) as u8 & 0xF,
+        ];
+        let [top, bottom] = matrix.map(|row| {
+            gf16_mul(row[0], column[0]) ^ gf16_mul(row[1], column[1])
+        });
+        output | (top as u16) << shift | (bottom as u16) << (shift - 4)
+    })
+}
+
+/// Multiplication in GF(2^4) modulo `x^4 + x + 1`
+fn gf16_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        a <<= 1;
+        if a & 0x10 != 0 {
+            a ^= 0b10011;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_expansion() {
+        // Stallings, Cryptography and Network Security, appendix 5B
+        let saes = Saes::new(0b0100_1010_1111_0101);
+        assert_eq!(saes.round_keys(), [0x4AF5, 0xDD28, 0x87AF]);
+    }
+
+    #[test]
+    fn test_stallings_example() {
+        let saes = Saes::new(0b0100_1010_1111_0101);
+        let plaintext = 0b1101_0111_0010_1000;
+        let ciphertext = 0b0010_0100_1110_1100;
+        assert_eq!(saes.encrypt(plaintext), ciphertext);
+        assert_eq!(saes.decrypt(ciphertext), plaintext);
+    }
+
+    #[test]
+    fn test_musa_schaefer_wedig_example() {
+        let saes = Saes::new(0b1010_0111_0011_1011);
+        let plaintext = 0b0110_1111_0110_1011;
+        let ciphertext = 0b0000_0111_0011_1000;
+        assert_eq!(saes.encrypt(plaintext), ciphertext);
+        assert_eq!(saes.decrypt(ciphertext), plaintext);
+    }
+
+    #[test]
+    fn test_steps() {
+        use SaesStep::*;
+
+        let saes = Saes::new(0x4AF5);
+        let steps = saes.encrypt_steps(0xD728);
+        assert_eq!(steps.len(), 8);
+        assert_eq!(steps[0], (AddRoundKey(0), 0x9DDD));
+        assert_eq!(steps[1], (SubstituteNibbles, 0x2EEE));
+        assert_eq!(steps[2], (ShiftRows, 0x2EEE));
+        assert_eq!(steps[7], (AddRoundKey(2), 0x24EC));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for key in (0..=u16::MAX).step_by(257) {
+            let saes = Saes::new(key);
+            for block in (0..=u16::MAX).step_by(131) {
+                assert_eq!(saes.decrypt(saes.encrypt(block)), block);
+            }
+        }
+    }
+
+    #[test]
+    fn test_inverse_tables() {
+        for nibble in 0..16u8 {
+            assert_eq!(INVERSE_S_BOX[S_BOX[nibble as usize] as usize], nibble);
+        }
+        for state in (0..=u16::MAX).step_by(97) {
+            let mixed = mix_columns(state, &MIX);
+            assert_eq!(mix_columns(mixed, &INVERSE_MIX), state);
+        }
+    }
+}
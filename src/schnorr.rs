@@ -0,0 +1,399 @@
+//! Schnorr's identification protocol over a [`DhGroup`] and the signature
+//! scheme the Fiat–Shamir transform makes of it
+//!
+//! The prover knows `x` with `y = g^x`. It commits to a nonce with
+//! `t = g^r`, the verifier replies with a challenge `c` and accepts the
+//! response `s = r + cx (mod q)` if `g^s = t * y^c`. Signing replaces the
+//! verifier's challenge with a hash of the commitment and the message
+
+use crate::bigint::BigUint;
+use crate::diffiehellman::DhGroup;
+use crate::hash::Digest;
+use crate::rfc6979::{bits2int, NonceGenerator};
+
+/// Size in bits of the random weights used by [`batch_verify`], a forged
+/// signature slips through with probability at most `2^-WEIGHT_BITS`
+const WEIGHT_BITS: usize = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchnorrError {
+    /// The private key is not in `[1, q - 1]`
+    InvalidPrivateKey,
+    /// The public key is not a member of the subgroup
+    InvalidPublicKey,
+    /// The commitment nonce is not in `[1, q - 1]`
+    InvalidNonce,
+}
+
+impl std::fmt::Display for SchnorrError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::InvalidPrivateKey => "invalid private key",
+            Self::InvalidPublicKey => "invalid public key",
+            Self::InvalidNonce => "invalid commitment nonce",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for SchnorrError {}
+
+/// The prover's secret from the first move of the identification
+/// protocol. Responding consumes it, answering two challenges for the same
+/// nonce would reveal the private key
+pub struct Commitment {
+    r: BigUint,
+}
+
+/// A non-interactive proof, the commitment `t` and the response `s` to the
+/// challenge derived from it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub t: BigUint,
+    pub s: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningKey {
+    group: DhGroup,
+    x: BigUint,
+    verifying_key: VerifyingKey,
+}
+
+impl SigningKey {
+    pub fn new(group: DhGroup, x: BigUint) -> Result<Self, SchnorrError> {
+        if x.is_zero() || x >= group.q {
+            return Err(SchnorrError::InvalidPrivateKey);
+        }
+        let y = group.generate_exchange_key(&x);
+        let verifying_key = VerifyingKey::new(group.clone(), y)?;
+        Ok(Self {
+            group,
+            x,
+            verifying_key,
+        })
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.verifying_key
+    }
+
+    /// First move: commits to the secret nonce `r`, returning the
+    /// commitment `t = g^r` to send to the verifier
+    pub fn commit(
+        &self,
+        r: BigUint,
+    ) -> Result<(Commitment, BigUint), SchnorrError> {
+        if r.is_zero() || r >= self.group.q {
+            return Err(SchnorrError::InvalidNonce);
+        }
+        let t = self.group.generate_exchange_key(&r);
+        Ok((Commitment { r }, t))
+    }
+
+    /// Third move: the response `s = r + cx (mod q)` to the challenge `c`
+    pub fn respond(
+        &self,
+        commitment: Commitment,
+        challenge: &BigUint,
+    ) -> BigUint {
+        let q = &self.group.q;
+        let cx = (challenge % q).mul_mod(&self.x, q);
+        commitment.r.add_mod(&cx, q)
+    }
+
+    /// Signs `message`, the nonce is derived from the key and the hash of
+    /// the message as in RFC 6979
+    pub fn sign<D: Digest>(&self, message: &[u8]) -> Signature {
+        let mut nonces = NonceGenerator::<D>::new(
+            &self.group.q,
+            &self.x,
+            &D::digest(message),
+        );
+        let (commitment, t) = self
+            .commit(nonces.next_k())
+            .expect("nonce is in [1, q - 1]");
+        let c = challenge::<D>(&self.group, &t, &self.verifying_key.y, message);
+        let s = self.respond(commitment, &c);
+        Signature { t, s }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifyingKey {
+    group: DhGroup,
+    y: BigUint,
+}
+
+impl VerifyingKey {
+    pub fn new(group: DhGroup, y: BigUint) -> Result<Self, SchnorrError> {
+        if !group.contains(&y) {
+            return Err(SchnorrError::InvalidPublicKey);
+        }
+        Ok(Self { group, y })
+    }
+
+    pub fn y(&self) -> &BigUint {
+        &self.y
+    }
+
+    /// Whether `g^s = t * y^c`, i.e. whether the prover answered the
+    /// challenge `c` to its commitment `t` correctly
+    pub fn check_response(
+        &self,
+        t: &BigUint,
+        challenge: &BigUint,
+        response: &BigUint,
+    ) -> bool {
+        let DhGroup { p, q, g } = &self.group;
+        if !self.group.contains(t) || response >= q {
+            return false;
+        }
+        let lhs = g.pow_mod(response, p);
+        let rhs = t.mul_mod(&self.y.pow_mod(&(challenge % q), p), p);
+        lhs == rhs
+    }
+
+    pub fn verify<D: Digest>(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+    ) -> bool {
+        let c = challenge::<D>(&self.group, &signature.t, &self.y, message);
+        self.check_response(&signature.t, &c, &signature.s)
+    }
+}
+
+/// Produces an accepting transcript `(t, c, s)` for any challenge and
+/// response without the private key, by solving `g^s = t * y^c` for `t`.
+/// That such transcripts are indistinguishable from real ones is why the
+/// protocol teaches the verifier nothing about `x`
+pub fn simulate(
+    key: &VerifyingKey,
+    challenge: &BigUint,
+    response: &BigUint,
+) -> BigUint {
+    let DhGroup { p, q, g } = &key.group;
+    let y_c = key.y.pow_mod(&(challenge % q), p);
+    let y_c_inverse = y_c.mod_inverse(p).expect("p is prime");
+    g.pow_mod(response, p).mul_mod(&y_c_inverse, p)
+}
+
+/// Verifies signatures from keys in the same group with one check,
+/// `g^(sum z_i s_i) = prod t_i^z_i * y_i^(z_i c_i)`, where the weights
+/// `z_i` are derived by hashing the whole batch. Every commitment is still
+/// checked to be in the subgroup, without that the weights can be dodged
+pub fn batch_verify<D: Digest>(
+    batch: &[(&VerifyingKey, &[u8], &Signature)],
+) -> bool {
+    let Some((first, _, _)) = batch.first() else {
+        return true;
+    };
+    let DhGroup { p, q, g } = &first.group;
+    if batch.iter().any(|(key, _, signature)| {
+        key.group != first.group
+            || signature.s >= *q
+            || !key.group.contains(&signature.t)
+    }) {
+        return false;
+    }
+
+    let mut transcript = D::new();
+    for (key, message, signature) in batch {
+        for element in [&signature.t, &signature.s, &key.y] {
+            transcript
+                .update(&element.to_be_bytes_padded(p.bits().div_ceil(8)));
+        }
+        transcript.update(&D::digest(message));
+    }
+    let seed = transcript.finalize();
+
+    let mut exponent = BigUint::zero();
+    let mut product = BigUint::one();
+    for (i, (key, message, signature)) in batch.iter().enumerate() {
+        let z = batch_weight::<D>(&seed, i);
+        let c = challenge::<D>(&key.group, &signature.t, &key.y, message);
+        exponent = exponent.add_mod(&z.mul_mod(&signature.s, q), q);
+
+        let t_z = signature.t.pow_mod(&z, p);
+        let y_zc = key.y.pow_mod(&z.mul_mod(&c, q), p);
+        product = product.mul_mod(&t_z.mul_mod(&y_zc, p), p);
+    }
+    g.pow_mod(&exponent, p) == product
+}
+
+fn batch_weight<D: Digest>(seed: &[u8], index: usize) -> BigUint {
+    let mut weight = Vec::new();
+    let mut counter: u32 = 0;
+    while weight.len() * 8 < WEIGHT_BITS {
+        let mut hasher = D::new();
+        hasher.update(seed);
+        hasher.update(&(index as u64).to_be_bytes());
+        hasher.update(&counter.to_be_bytes());
+        weight.extend(hasher.finalize());
+        counter += 1;
+    }
+    BigUint::from_be_bytes(&weight).truncate(WEIGHT_BITS)
+}
+
+/// `c = H(t || y || message) mod q` with the group elements padded to the
+/// length of `p`
+fn challenge<D: Digest>(
+    group: &DhGroup,
+    t: &BigUint,
+    y: &BigUint,
+    message: &[u8],
+) -> BigUint {
+    let element_len = group.p.bits().div_ceil(8);
+    let mut hasher = D::new();
+    hasher.update(&t.to_be_bytes_padded(element_len));
+    hasher.update(&y.to_be_bytes_padded(element_len));
+    hasher.update(message);
+    &bits2int(&hasher.finalize(), group.q.bits()) % &group.q
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha2::Sha256;
+
+    /// The 1024 bit group of RFC 6979 A.2.1
+    fn group() -> DhGroup {
+        DhGroup::new(
+            BigUint::from_hex(
+                "86F5CA03DCFEB225063FF830A0C769B9DD9D6153AD91D7CE27F787C43278B447
+                 E6533B86B18BED6E8A48B784A14C252C5BE0DBF60B86D6385BD2F12FB763ED88
+                 73ABFD3F5BA2E0A8C0A59082EAC056935E529DAF7C610467899C77ADEDFC846C
+                 881870B7B19B2B58F9BE0521A17002E3BDD6B86685EE90B3D9A1B02B782B1779",
+            )
+            .unwrap(),
+            BigUint::from_hex("996F967F6C8E388D9E28D01E205FBA957A5698B1")
+                .unwrap(),
+            BigUint::from_hex(
+                "07B0F92546150B62514BB771E2A0C0CE387F03BDA6C56B505209FF25FD3C133D
+                 89BBCD97E904E09114D9A7DEFDEADFC9078EA544D2E401AEECC40BB9FBBF78FD
+                 87995A10A1C27CB7789B594BA7EFB5C4326A9FE59A070E136DB77175464ADCA4
+                 17BE5DCE2F40D10A46A3A3943F26AB7FD9C0398FF8C76EE0A56826A8A88F1DBD",
+            )
+            .unwrap(),
+        )
+    }
+
+    fn key(x: u64) -> SigningKey {
+        SigningKey::new(group(), BigUint::from(x)).unwrap()
+    }
+
+    #[test]
+    fn test_identification() {
+        let prover = key(0xdead_beef);
+        let verifier = prover.verifying_key();
+
+        let (commitment, t) = prover.commit(BigUint::from(123_456u64)).unwrap();
+        let challenge = BigUint::from(0x1234_5678_9abc_def0u64);
+        let response = prover.respond(commitment, &challenge);
+        assert!(verifier.check_response(&t, &challenge, &response));
+
+        let wrong = &response + &BigUint::one();
+        assert!(!verifier.check_response(&t, &challenge, &wrong));
+        assert!(!verifier.check_response(&t, &BigUint::from(7u64), &response));
+    }
+
+    #[test]
+    fn test_reused_nonce_leaks_key() {
+        let prover = key(0xdead_beef);
+        let r = BigUint::from(99u64);
+        let q = &prover.group.q;
+
+        let (first, _) = prover.commit(r.clone()).unwrap();
+        let (second, _) = prover.commit(r).unwrap();
+        let (c1, c2) = (BigUint::from(5u64), BigUint::from(3u64));
+        let s1 = prover.respond(first, &c1);
+        let s2 = prover.respond(second, &c2);
+
+        // x = (s1 - s2) / (c1 - c2)
+        let c_diff_inverse = (&c1 - &c2).mod_inverse(q).unwrap();
+        let x = s1.sub_mod(&s2, q).mul_mod(&c_diff_inverse, q);
+        assert_eq!(x, BigUint::from(0xdead_beefu64));
+    }
+
+    #[test]
+    fn test_simulated_transcript_verifies() {
+        let verifier = key(42).verifying_key().clone();
+        let challenge = BigUint::from(77u64);
+        let response = BigUint::from(0xabcdefu64);
+
+        let t = simulate(&verifier, &challenge, &response);
+        assert!(verifier.check_response(&t, &challenge, &response));
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = key(0x0123_4567_89ab_cdef);
+        let signature = signer.sign::<Sha256>(b"proof of possession");
+        let verifier = signer.verifying_key();
+
+        assert!(verifier.verify::<Sha256>(b"proof of possession", &signature));
+        assert!(!verifier.verify::<Sha256>(b"proof of possession!", &signature));
+        assert!(!key(1)
+            .verifying_key()
+            .verify::<Sha256>(b"proof of possession", &signature));
+        assert_eq!(signature, signer.sign::<Sha256>(b"proof of possession"));
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let signers: Vec<SigningKey> = (1..=4).map(|x| key(x * 1000)).collect();
+        let messages: Vec<Vec<u8>> = (0..4)
+            .map(|i| format!("message {i}").into_bytes())
+            .collect();
+        let signatures: Vec<Signature> = signers
+            .iter()
+            .zip(&messages)
+            .map(|(signer, message)| signer.sign::<Sha256>(message))
+            .collect();
+
+        let batch: Vec<_> = signers
+            .iter()
+            .zip(&messages)
+            .zip(&signatures)
+            .map(|((signer, message), signature)| {
+                (signer.verifying_key(), message.as_slice(), signature)
+            })
+            .collect();
+        assert!(batch_verify::<Sha256>(&batch));
+        assert!(batch_verify::<Sha256>(&[]));
+
+        let mut swapped = batch.clone();
+        swapped[1].1 = &messages[2];
+        assert!(!batch_verify::<Sha256>(&swapped));
+
+        // p - 1 has order 2, outside the subgroup
+        let group = group();
+        let forged = Signature {
+            t: &signatures[0]
+                .t
+                .mul_mod(&(&group.p - &BigUint::one()), &group.p)
+                % &group.p,
+            s: signatures[0].s.clone(),
+        };
+        let mut outside = batch.clone();
+        outside[0].2 = &forged;
+        assert!(!batch_verify::<Sha256>(&outside));
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert_eq!(
+            SigningKey::new(group(), BigUint::zero()),
+            Err(SchnorrError::InvalidPrivateKey)
+        );
+        assert_eq!(
+            VerifyingKey::new(group(), BigUint::one()),
+            Err(SchnorrError::InvalidPublicKey)
+        );
+        assert!(matches!(
+            key(5).commit(group().q),
+            Err(SchnorrError::InvalidNonce)
+        ));
+    }
+}
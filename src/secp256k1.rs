@@ -0,0 +1,173 @@
+//! secp256k1 of SEC2 section 2.4.1, `y^2 = x^3 + 7` over a prime just
+//! below 2^256, the curve Bitcoin signs with
+//!
+//! ECDSA on it is [`crate::ecdsa`] and Schnorr signatures are
+//! [`crate::bip340`]
+
+use crate::bigint::BigUint;
+use crate::primefield::{FieldElement, Modulus};
+use crate::weierstrass::{AffinePoint, WeierstrassCurve};
+
+/// The field prime, 2^256 - 2^32 - 977
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Secp256k1;
+
+impl Modulus for Secp256k1 {
+    const LIMBS: usize = 4;
+    const P: [u64; 6] = [
+        0xfffffffefffffc2f,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+        0xffffffffffffffff,
+        0,
+        0,
+    ];
+}
+
+fn element(hex: &str) -> FieldElement<Secp256k1> {
+    let bytes = BigUint::from_hex(hex).unwrap().to_be_bytes_padded(32);
+    FieldElement::from_be_bytes(&bytes).unwrap()
+}
+
+pub fn secp256k1() -> WeierstrassCurve<FieldElement<Secp256k1>> {
+    WeierstrassCurve::new(
+        FieldElement::ZERO,
+        FieldElement::from_u64(7),
+        AffinePoint {
+            x: element(
+                "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            ),
+            y: element(
+                "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+            ),
+        },
+        BigUint::from_hex(
+            "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        )
+        .unwrap(),
+        1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ecdsa::{EcdsaError, Signature, SigningKey, VerifyingKey};
+    use crate::hash::hex;
+    use crate::sha2::Sha256;
+
+    fn number(hex: &str) -> BigUint {
+        BigUint::from_hex(hex).unwrap()
+    }
+
+    #[test]
+    fn test_curve_parameters() {
+        let curve = secp256k1();
+        assert_eq!(curve.validate(), Ok(()));
+        assert_eq!(
+            curve.encode_point(&curve.base, true),
+            hex("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+        );
+    }
+
+    /// RFC 6979 signatures with SHA-256 as Bitcoin libraries test them,
+    /// after normalising to low s
+    #[test]
+    fn test_low_s_signatures() {
+        let vectors = [
+            (
+                "1",
+                "Satoshi Nakamoto",
+                "934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8",
+                "2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5",
+            ),
+            (
+                "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140",
+                "Satoshi Nakamoto",
+                "fd567d121db66e382991534ada77a6bd3106f0a1098c231e47993447cd6af2d0",
+                "6b39cd0eb1bc8603e159ef5c20a5c8ad685a45b06ce9bebed3f153d10d93bed5",
+            ),
+            (
+                "1",
+                "All those moments will be lost in time, like tears in rain. \
+                 Time to die...",
+                "8600dbd41e348fe5c9465ab92d23e3db8b98b873beecd930736488696438cb6b",
+                "547fe64427496db33bf66019dacbf0039c04199abb0122918601db38a72cfc21",
+            ),
+        ];
+        let curve = secp256k1();
+        for (d, message, r, s) in vectors {
+            let key = SigningKey::new(curve.clone(), number(d)).unwrap();
+            let expected = Signature {
+                r: number(r),
+                s: number(s),
+            };
+            let high = key.sign::<Sha256>(message.as_bytes()).unwrap();
+            assert!(!high.is_low_s(&curve.order));
+            assert_eq!(high.normalize_s(&curve.order), expected);
+
+            let (signature, recovery_id) =
+                key.sign_recoverable::<Sha256>(message.as_bytes()).unwrap();
+            assert_eq!(signature, expected);
+            assert!(signature.is_low_s(&curve.order));
+            assert_eq!(signature.normalize_s(&curve.order), expected);
+            let public = key.verifying_key();
+            assert!(public.verify::<Sha256>(message.as_bytes(), &signature));
+            assert!(public.verify::<Sha256>(message.as_bytes(), &high));
+            assert!(
+                public.verify_low_s::<Sha256>(message.as_bytes(), &signature)
+            );
+            assert!(!public.verify_low_s::<Sha256>(message.as_bytes(), &high));
+
+            let recovered = VerifyingKey::recover::<Sha256>(
+                curve.clone(),
+                message.as_bytes(),
+                &signature,
+                recovery_id,
+            );
+            assert_eq!(recovered.as_ref(), Ok(public));
+            let other = VerifyingKey::recover::<Sha256>(
+                curve.clone(),
+                message.as_bytes(),
+                &signature,
+                recovery_id ^ 1,
+            );
+            assert_ne!(other.as_ref(), Ok(public));
+        }
+    }
+
+    #[test]
+    fn test_recover_rejects() {
+        let curve = secp256k1();
+        let key = SigningKey::new(curve.clone(), number("1234")).unwrap();
+        let (signature, recovery_id) =
+            key.sign_recoverable::<Sha256>(b"abc").unwrap();
+        assert_eq!(
+            VerifyingKey::recover::<Sha256>(
+                curve.clone(),
+                b"abc",
+                &signature,
+                4
+            ),
+            Err(EcdsaError::InvalidSignature)
+        );
+        // r + n is above p for all but a tiny fraction of r
+        assert_eq!(
+            VerifyingKey::recover::<Sha256>(
+                curve.clone(),
+                b"abc",
+                &signature,
+                recovery_id | 2
+            ),
+            Err(EcdsaError::InvalidSignature)
+        );
+        let zero = Signature {
+            r: BigUint::zero(),
+            s: signature.s,
+        };
+        assert_eq!(
+            VerifyingKey::recover::<Sha256>(curve, b"abc", &zero, recovery_id),
+            Err(EcdsaError::InvalidSignature)
+        );
+    }
+}
@@ -0,0 +1,110 @@
+//! SHA-1 (FIPS 180-4), kept for the legacy protocols and test vectors that
+//! still specify it
+
+use crate::hash::{BlockBuffer, Digest};
+
+const INITIAL_STATE: [u32; 5] =
+    [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476, 0xc3d2e1f0];
+
+#[derive(Clone)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: BlockBuffer<64>,
+}
+
+impl Digest for Sha1 {
+    const BLOCK_SIZE: usize = 64;
+    const OUTPUT_SIZE: usize = 20;
+
+    fn new() -> Self {
+        Self {
+            state: INITIAL_STATE,
+            buffer: BlockBuffer::new(),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        let state = &mut self.state;
+        self.buffer.update(data, |block| compress(state, block));
+    }
+
+    fn finalize(mut self) -> Vec<u8> {
+        let state = &mut self.state;
+        self.buffer.finalize(8, |block| compress(state, block));
+        self.state
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect()
+    }
+}
+
+fn compress(state: &mut [u32; 5], block: &[u8; 64]) {
+    let mut w = [0u32; 80];
+    for (i, word) in block.chunks_exact(4).enumerate() {
+        w[i] = u32::from_be_bytes(word.try_into().unwrap());
+    }
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+    for (i, word) in w.iter().enumerate() {
+        let (f, k) = match i {
+            0..=19 => ((b & c) | (!b & d), 0x5a827999),
+            20..=39 => (b ^ c ^ d, 0x6ed9eba1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1bbcdc),
+            _ => (b ^ c ^ d, 0xca62c1d6),
+        };
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(*word);
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    for (word, value) in state.iter_mut().zip([a, b, c, d, e]) {
+        *word = word.wrapping_add(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hex;
+
+    #[test]
+    fn test_sha1() {
+        assert_eq!(
+            Sha1::digest(b""),
+            hex("da39a3ee5e6b4b0d3255bfef95601890afd80709")
+        );
+        assert_eq!(
+            Sha1::digest(b"abc"),
+            hex("a9993e364706816aba3e25717850c26c9cd0d89d")
+        );
+        assert_eq!(
+            Sha1::digest(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            ),
+            hex("84983e441c3bd26ebaae4aa1f95129e5e54670f1")
+        );
+    }
+
+    #[test]
+    fn test_sha1_incremental() {
+        let mut hasher = Sha1::new();
+        for _ in 0..1000 {
+            hasher.update(&[b'a'; 1000]);
+        }
+        assert_eq!(
+            hasher.finalize(),
+            hex("34aa973cd4c4daa4f61eeb2bdbad27316534016f")
+        );
+    }
+}
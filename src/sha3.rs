@@ -0,0 +1,201 @@
+//! The SHAKE extendable-output functions of FIPS 202, built on the
+//! Keccak-f[1600] permutation
+//!
+//! Unlike the [`crate::hash::Digest`] hashes the output length is chosen
+//! by the caller, Ed448 for instance draws 114 bytes from SHAKE256
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Rotation of each lane visited by the combined rho and pi steps
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18,
+    39, 61, 20, 44,
+];
+
+/// The order pi visits the lanes in, starting from lane 1
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14,
+    22, 9, 6, 1,
+];
+
+/// The SHAKE domain separation bits and the first bit of the padding
+const SHAKE_PADDING: u8 = 0x1f;
+
+fn keccak_f(state: &mut [u64; 25]) {
+    for round_constant in ROUND_CONSTANTS {
+        // theta
+        let mut c = [0u64; 5];
+        for (x, column) in c.iter_mut().enumerate() {
+            *column = state[x]
+                ^ state[x + 5]
+                ^ state[x + 10]
+                ^ state[x + 15]
+                ^ state[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[x + 5 * y] ^= d;
+            }
+        }
+
+        // rho and pi
+        let mut last = state[1];
+        for (&lane, rotation) in PI.iter().zip(RHO) {
+            let next = state[lane];
+            state[lane] = last.rotate_left(rotation);
+            last = next;
+        }
+
+        // chi
+        for y in 0..5 {
+            let row: [u64; 5] = state[5 * y..5 * y + 5].try_into().unwrap();
+            for x in 0..5 {
+                state[5 * y + x] =
+                    row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // iota
+        state[0] ^= round_constant;
+    }
+}
+
+/// SHAKE with a rate of `RATE` bytes, 168 for SHAKE128 and 136 for
+/// SHAKE256
+#[derive(Clone)]
+pub struct Shake<const RATE: usize> {
+    state: [u64; 25],
+    /// Bytes absorbed into the current block
+    filled: usize,
+}
+
+pub type Shake128 = Shake<168>;
+pub type Shake256 = Shake<136>;
+
+impl<const RATE: usize> Shake<RATE> {
+    pub fn new() -> Self {
+        Self {
+            state: [0; 25],
+            filled: 0,
+        }
+    }
+
+    fn xor_byte(&mut self, index: usize, byte: u8) {
+        self.state[index / 8] ^= (byte as u64) << (8 * (index % 8));
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for &byte in data {
+            self.xor_byte(self.filled, byte);
+            self.filled += 1;
+            if self.filled == RATE {
+                keccak_f(&mut self.state);
+                self.filled = 0;
+            }
+        }
+    }
+
+    /// Pads the input and squeezes out `length` bytes
+    pub fn finalize(mut self, length: usize) -> Vec<u8> {
+        self.xor_byte(self.filled, SHAKE_PADDING);
+        self.xor_byte(RATE - 1, 0x80);
+        keccak_f(&mut self.state);
+
+        let mut output = Vec::with_capacity(length);
+        loop {
+            for i in 0..RATE {
+                if output.len() == length {
+                    return output;
+                }
+                output.push((self.state[i / 8] >> (8 * (i % 8))) as u8);
+            }
+            keccak_f(&mut self.state);
+        }
+    }
+
+    pub fn digest(data: &[u8], length: usize) -> Vec<u8> {
+        let mut shake = Self::new();
+        shake.update(data);
+        shake.finalize(length)
+    }
+}
+
+impl<const RATE: usize> Default for Shake<RATE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hex;
+
+    #[test]
+    fn test_shake() {
+        assert_eq!(
+            Shake128::digest(b"", 16),
+            hex("7f9c2ba4e88f827d616045507605853e")
+        );
+        assert_eq!(
+            Shake256::digest(b"", 64),
+            hex("46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f
+                 d75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be")
+        );
+        assert_eq!(
+            Shake256::digest(b"abc", 32),
+            hex("483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739")
+        );
+    }
+
+    /// Input spanning several blocks fed in pieces, and output longer than
+    /// one block
+    #[test]
+    fn test_shake_multiple_blocks() {
+        let mut shake = Shake256::new();
+        for _ in 0..10 {
+            shake.update(&[b'a'; 100]);
+        }
+        let output = shake.finalize(200);
+        assert_eq!(
+            output[..32],
+            hex("e262331ad290c96ab1c0fa045470244b415ba6696a934d60f2999b8e92aaa24e")
+        );
+        assert_eq!(
+            output[168..],
+            hex("9d928b5b2f5ae1dcb9aacc74a668065f36be51435b2eb535aef33bfaa57ef376")
+        );
+
+        let input: Vec<u8> = (0..768).map(|i| i as u8).collect();
+        assert_eq!(
+            Shake128::digest(&input, 32),
+            hex("92b62d6682dda8ef27e599c00ce6fcd070dafa726908c07bf6c361ab7be2149f")
+        );
+    }
+}
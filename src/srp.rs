@@ -0,0 +1,695 @@
+//! SRP-6a password-authenticated key exchange (RFC 5054 / RFC 2945)
+//!
+//! The server stores a verifier `v = g^x` derived from the password instead
+//! of the password itself. Both sides exchange ephemeral public keys and
+//! end up with the same premaster secret only if the client knew the
+//! password, which the M1 and M2 proofs then confirm.
+//!
+//! ```text
+//! k = H(N | PAD(g))            x = H(s | H(I | ":" | P))
+//! A = g^a                      B = k*v + g^b
+//! u = H(PAD(A) | PAD(B))
+//! client S = (B - k*g^x)^(a + u*x)
+//! server S = (A * v^u)^b
+//! K = H(S)
+//! M1 = H(H(N) xor H(g) | H(I) | s | A | B | K)
+//! M2 = H(A | M1 | K)
+//! ```
+
+use crate::bigint::BigUint;
+use crate::diffiehellman::DhGroup;
+use crate::hash::Digest;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SrpError {
+    /// The private ephemeral value is zero
+    InvalidPrivateKey,
+    /// The peer's public value is zero or not below N, or makes the
+    /// scrambling parameter `u` zero
+    InvalidPublicKey,
+    /// The peer's M1 or M2 proof does not match
+    InvalidProof,
+}
+
+impl std::fmt::Display for SrpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::InvalidPrivateKey => "invalid private key",
+            Self::InvalidPublicKey => "invalid public key",
+            Self::InvalidProof => "proof mismatch",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for SrpError {}
+
+/// The group sizes defined in RFC 5054 appendix A
+pub const RFC5054_SIZES: [usize; 7] =
+    [1024, 1536, 2048, 3072, 4096, 6144, 8192];
+
+const N_1024: &str = "
+    EEAF0AB9ADB38DD69C33F80AFA8FC5E86072618775FF3C0B9EA2314C9C256576
+    D674DF7496EA81D3383B4813D692C6E0E0D5D8E250B98BE48E495C1D6089DAD1
+    5DC7D7B46154D6B6CE8EF4AD69B15D4982559B297BCF1885C529F566660E57EC
+    68EDBC3C05726CC02FD4CBF4976EAA9AFD5138FE8376435B9FC61D2FC0EB06E3
+";
+
+const N_1536: &str = "
+    9DEF3CAFB939277AB1F12A8617A47BBBDBA51DF499AC4C80BEEEA9614B19CC4D
+    5F4F5F556E27CBDE51C6A94BE4607A291558903BA0D0F84380B655BB9A22E8DC
+    DF028A7CEC67F0D08134B1C8B97989149B609E0BE3BAB63D47548381DBC5B1FC
+    764E3F4B53DD9DA1158BFD3E2B9C8CF56EDF019539349627DB2FD53D24B7C486
+    65772E437D6C7F8CE442734AF7CCB7AE837C264AE3A9BEB87F8A2FE9B8B5292E
+    5A021FFF5E91479E8CE7A28C2442C6F315180F93499A234DCF76E3FED135F9BB
+";
+
+const N_2048: &str = "
+    AC6BDB41324A9A9BF166DE5E1389582FAF72B6651987EE07FC3192943DB56050
+    A37329CBB4A099ED8193E0757767A13DD52312AB4B03310DCD7F48A9DA04FD50
+    E8083969EDB767B0CF6095179A163AB3661A05FBD5FAAAE82918A9962F0B93B8
+    55F97993EC975EEAA80D740ADBF4FF747359D041D5C33EA71D281E446B14773B
+    CA97B43A23FB801676BD207A436C6481F1D2B9078717461A5B9D32E688F87748
+    544523B524B0D57D5EA77A2775D2ECFA032CFBDBF52FB3786160279004E57AE6
+    AF874E7303CE53299CCC041C7BC308D82A5698F3A8D0C38271AE35F8E9DBFBB6
+    94B5C803D89F7AE435DE236D525F54759B65E372FCD68EF20FA7111F9E4AFF73
+";
+
+const N_3072: &str = "
+    FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74
+    020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437
+    4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED
+    EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05
+    98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB
+    9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B
+    E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718
+    3995497CEA956AE515D2261898FA051015728E5A8AAAC42DAD33170D04507A33
+    A85521ABDF1CBA64ECFB850458DBEF0A8AEA71575D060C7DB3970F85A6E1E4C7
+    ABF5AE8CDB0933D71E8C94E04A25619DCEE3D2261AD2EE6BF12FFA06D98A0864
+    D87602733EC86A64521F2B18177B200CBBE117577A615D6C770988C0BAD946E2
+    08E24FA074E5AB3143DB5BFCE0FD108E4B82D120A93AD2CAFFFFFFFFFFFFFFFF
+";
+
+const N_4096: &str = "
+    FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74
+    020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437
+    4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED
+    EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05
+    98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB
+    9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B
+    E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718
+    3995497CEA956AE515D2261898FA051015728E5A8AAAC42DAD33170D04507A33
+    A85521ABDF1CBA64ECFB850458DBEF0A8AEA71575D060C7DB3970F85A6E1E4C7
+    ABF5AE8CDB0933D71E8C94E04A25619DCEE3D2261AD2EE6BF12FFA06D98A0864
+    D87602733EC86A64521F2B18177B200CBBE117577A615D6C770988C0BAD946E2
+    08E24FA074E5AB3143DB5BFCE0FD108E4B82D120A92108011A723C12A787E6D7
+    88719A10BDBA5B2699C327186AF4E23C1A946834B6150BDA2583E9CA2AD44CE8
+    DBBBC2DB04DE8EF92E8EFC141FBECAA6287C59474E6BC05D99B2964FA090C3A2
+    233BA186515BE7ED1F612970CEE2D7AFB81BDD762170481CD0069127D5B05AA9
+    93B4EA988D8FDDC186FFB7DC90A6C08F4DF435C934063199FFFFFFFFFFFFFFFF
+";
+
+const N_6144: &str = "
+    FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74
+    020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437
+    4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED
+    EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05
+    98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB
+    9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B
+    E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718
+    3995497CEA956AE515D2261898FA051015728E5A8AAAC42DAD33170D04507A33
+    A85521ABDF1CBA64ECFB850458DBEF0A8AEA71575D060C7DB3970F85A6E1E4C7
+    ABF5AE8CDB0933D71E8C94E04A25619DCEE3D2261AD2EE6BF12FFA06D98A0864
+    D87602733EC86A64521F2B18177B200CBBE117577A615D6C770988C0BAD946E2
+    08E24FA074E5AB3143DB5BFCE0FD108E4B82D120A92108011A723C12A787E6D7
+    88719A10BDBA5B2699C327186AF4E23C1A946834B6150BDA2583E9CA2AD44CE8
+    DBBBC2DB04DE8EF92E8EFC141FBECAA6287C59474E6BC05D99B2964FA090C3A2
+    233BA186515BE7ED1F612970CEE2D7AFB81BDD762170481CD0069127D5B05AA9
+    93B4EA988D8FDDC186FFB7DC90A6C08F4DF435C93402849236C3FAB4D27C7026
+    C1D4DCB2602646DEC9751E763DBA37BDF8FF9406AD9E530EE5DB382F413001AE
+    B06A53ED9027D831179727B0865A8918DA3EDBEBCF9B14ED44CE6CBACED4BB1B
+    DB7F1447E6CC254B332051512BD7AF426FB8F401378CD2BF5983CA01C64B92EC
+    F032EA15D1721D03F482D7CE6E74FEF6D55E702F46980C82B5A84031900B1C9E
+    59E7C97FBEC7E8F323A97A7E36CC88BE0F1D45B7FF585AC54BD407B22B4154AA
+    CC8F6D7EBF48E1D814CC5ED20F8037E0A79715EEF29BE32806A1D58BB7C5DA76
+    F550AA3D8A1FBFF0EB19CCB1A313D55CDA56C9EC2EF29632387FE8D76E3C0468
+    043E8F663F4860EE12BF2D5B0B7474D6E694F91E6DCC4024FFFFFFFFFFFFFFFF
+";
+
+const N_8192: &str = "
+    FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74
+    020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437
+    4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED
+    EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05
+    98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB
+    9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B
+    E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718
+    3995497CEA956AE515D2261898FA051015728E5A8AAAC42DAD33170D04507A33
+    A85521ABDF1CBA64ECFB850458DBEF0A8AEA71575D060C7DB3970F85A6E1E4C7
+    ABF5AE8CDB0933D71E8C94E04A25619DCEE3D2261AD2EE6BF12FFA06D98A0864
+    D87602733EC86A64521F2B18177B200CBBE117577A615D6C770988C0BAD946E2
+    08E24FA074E5AB3143DB5BFCE0FD108E4B82D120A92108011A723C12A787E6D7
+    88719A10BDBA5B2699C327186AF4E23C1A946834B6150BDA2583E9CA2AD44CE8
+    DBBBC2DB04DE8EF92E8EFC141FBECAA6287C59474E6BC05D99B2964FA090C3A2
+    233BA186515BE7ED1F612970CEE2D7AFB81BDD762170481CD0069127D5B05AA9
+    93B4EA988D8FDDC186FFB7DC90A6C08F4DF435C93402849236C3FAB4D27C7026
+    C1D4DCB2602646DEC9751E763DBA37BDF8FF9406AD9E530EE5DB382F413001AE
+    B06A53ED9027D831179727B0865A8918DA3EDBEBCF9B14ED44CE6CBACED4BB1B
+    DB7F1447E6CC254B332051512BD7AF426FB8F401378CD2BF5983CA01C64B92EC
+    F032EA15D1721D03F482D7CE6E74FEF6D55E702F46980C82B5A84031900B1C9E
+    59E7C97FBEC7E8F323A97A7E36CC88BE0F1D45B7FF585AC54BD407B22B4154AA
+    CC8F6D7EBF48E1D814CC5ED20F8037E0A79715EEF29BE32806A1D58BB7C5DA76
+    F550AA3D8A1FBFF0EB19CCB1A313D55CDA56C9EC2EF29632387FE8D76E3C0468
+    043E8F663F4860EE12BF2D5B0B7474D6E694F91E6DBE115974A3926F12FEE5E4
+    38777CB6A932DF8CD8BEC4D073B931BA3BC832B68D9DD300741FA7BF8AFC47ED
+    2576F6936BA424663AAB639C5AE4F5683423B4742BF1C978238F16CBE39D652D
+    E3FDB8BEFC848AD922222E04A4037C0713EB57A81A23F0C73473FC646CEA306B
+    4BCBC8862F8385DDFA9D4B7FA2C087E879683303ED5BDD3A062B3CF5B3A278A6
+    6D2A13F83F44F82DDF310EE074AB6A364597E899A0255DC164F31CC50846851D
+    F9AB48195DED7EA1B1D510BD7EE74D73FAF36BC31ECFA268359046F4EB879F92
+    4009438B481C6CD7889A002ED5EE382BC9190DA6FC026E479558E4475677E9AA
+    9E3050E2765694DFC81F56E880B96E7160C980DD98EDD3DFFFFFFFFFFFFFFFFF
+";
+
+/// The RFC 5054 group with an N of `bits` bits. Every N is a safe prime,
+/// `q` is `(N - 1) / 2`
+pub fn rfc5054_group(bits: usize) -> Option<DhGroup> {
+    let (n, g) = match bits {
+        1024 => (N_1024, 2u64),
+        1536 => (N_1536, 2),
+        2048 => (N_2048, 2),
+        3072 => (N_3072, 5),
+        4096 => (N_4096, 5),
+        6144 => (N_6144, 5),
+        8192 => (N_8192, 19),
+        _ => return None,
+    };
+    let p = BigUint::from_hex(n).unwrap();
+    let q = &(&p - &BigUint::one()) >> 1;
+    Some(DhGroup::new(p, q, BigUint::from(g)))
+}
+
+/// `value` as a big endian byte string as long as N
+fn pad(group: &DhGroup, value: &BigUint) -> Vec<u8> {
+    value.to_be_bytes_padded(group.p.bits().div_ceil(8))
+}
+
+fn hash_int<D: Digest>(parts: &[&[u8]]) -> BigUint {
+    let mut hasher = D::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    BigUint::from_be_bytes(&hasher.finalize())
+}
+
+/// The multiplier parameter `k = H(N | PAD(g))`
+pub fn multiplier<D: Digest>(group: &DhGroup) -> BigUint {
+    hash_int::<D>(&[&group.p.to_be_bytes(), &pad(group, &group.g)])
+}
+
+/// The private key `x = H(s | H(I | ":" | P))`
+pub fn private_key<D: Digest>(
+    salt: &[u8],
+    username: &[u8],
+    password: &[u8],
+) -> BigUint {
+    let mut inner = D::new();
+    inner.update(username);
+    inner.update(b":");
+    inner.update(password);
+    hash_int::<D>(&[salt, &inner.finalize()])
+}
+
+/// The verifier `v = g^x` the server stores in place of the password
+pub fn generate_verifier<D: Digest>(
+    group: &DhGroup,
+    salt: &[u8],
+    username: &[u8],
+    password: &[u8],
+) -> BigUint {
+    let x = private_key::<D>(salt, username, password);
+    group.generate_exchange_key(&x)
+}
+
+/// The scrambling parameter `u = H(PAD(A) | PAD(B))`
+fn scrambler<D: Digest>(
+    group: &DhGroup,
+    a_pub: &BigUint,
+    b_pub: &BigUint,
+) -> BigUint {
+    hash_int::<D>(&[&pad(group, a_pub), &pad(group, b_pub)])
+}
+
+fn client_proof<D: Digest>(
+    group: &DhGroup,
+    username: &[u8],
+    salt: &[u8],
+    a_pub: &BigUint,
+    b_pub: &BigUint,
+    key: &[u8],
+) -> Vec<u8> {
+    let hash_n = D::digest(&group.p.to_be_bytes());
+    let hash_g = D::digest(&group.g.to_be_bytes());
+    let group_hash: Vec<u8> =
+        hash_n.iter().zip(&hash_g).map(|(n, g)| n ^ g).collect();
+
+    let mut hasher = D::new();
+    hasher.update(&group_hash);
+    hasher.update(&D::digest(username));
+    hasher.update(salt);
+    hasher.update(&a_pub.to_be_bytes());
+    hasher.update(&b_pub.to_be_bytes());
+    hasher.update(key);
+    hasher.finalize()
+}
+
+fn server_proof<D: Digest>(a_pub: &BigUint, m1: &[u8], key: &[u8]) -> Vec<u8> {
+    let mut hasher = D::new();
+    hasher.update(&a_pub.to_be_bytes());
+    hasher.update(m1);
+    hasher.update(key);
+    hasher.finalize()
+}
+
+/// Compares without exiting early so the proofs leak nothing through timing
+fn proofs_match(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// The outcome of a successful exchange, the same on both sides
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionKey {
+    premaster_secret: BigUint,
+    key: Vec<u8>,
+}
+
+impl SessionKey {
+    fn new<D: Digest>(premaster_secret: BigUint) -> Self {
+        let key = D::digest(&premaster_secret.to_be_bytes());
+        Self {
+            premaster_secret,
+            key,
+        }
+    }
+
+    /// The shared value `S`, what TLS-SRP uses as its premaster secret
+    pub fn premaster_secret(&self) -> &BigUint {
+        &self.premaster_secret
+    }
+
+    /// The session key `K = H(S)`
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+/// The client before it has seen the server's public value
+pub struct SrpClient<D: Digest> {
+    group: DhGroup,
+    a: BigUint,
+    a_pub: BigUint,
+    _digest: std::marker::PhantomData<D>,
+}
+
+impl<D: Digest> SrpClient<D> {
+    /// `a` is the random private ephemeral value, RFC 5054 asks for at
+    /// least 256 bits
+    pub fn new(group: DhGroup, a: BigUint) -> Result<Self, SrpError> {
+        if a.is_zero() {
+            return Err(SrpError::InvalidPrivateKey);
+        }
+        let a_pub = group.generate_exchange_key(&a);
+        Ok(Self {
+            group,
+            a,
+            a_pub,
+            _digest: std::marker::PhantomData,
+        })
+    }
+
+    /// The public value `A` to send to the server
+    pub fn public_key(&self) -> &BigUint {
+        &self.a_pub
+    }
+
+    /// Derives the session key from the server's salt and public value `B`
+    pub fn process_challenge(
+        self,
+        username: &[u8],
+        password: &[u8],
+        salt: &[u8],
+        b_pub: &BigUint,
+    ) -> Result<ClientSession<D>, SrpError> {
+        let group = &self.group;
+        if b_pub.is_zero() || b_pub >= &group.p {
+            return Err(SrpError::InvalidPublicKey);
+        }
+        let u = scrambler::<D>(group, &self.a_pub, b_pub);
+        if u.is_zero() {
+            return Err(SrpError::InvalidPublicKey);
+        }
+
+        let k = multiplier::<D>(group);
+        let x = private_key::<D>(salt, username, password);
+        let base = (b_pub % &group.p).sub_mod(
+            &k.mul_mod(&group.generate_exchange_key(&x), &group.p),
+            &group.p,
+        );
+        let exponent = &self.a + &(&u * &x);
+        let session = SessionKey::new::<D>(base.pow_mod(&exponent, &group.p));
+
+        let m1 = client_proof::<D>(
+            group,
+            username,
+            salt,
+            &self.a_pub,
+            b_pub,
+            session.key(),
+        );
+        let m2 = server_proof::<D>(&self.a_pub, &m1, session.key());
+        Ok(ClientSession {
+            session,
+            m1,
+            m2,
+            _digest: std::marker::PhantomData,
+        })
+    }
+}
+
+/// The client once it has derived the session key, holding the proof to
+/// send and the one it expects back
+pub struct ClientSession<D: Digest> {
+    session: SessionKey,
+    m1: Vec<u8>,
+    m2: Vec<u8>,
+    _digest: std::marker::PhantomData<D>,
+}
+
+impl<D: Digest> ClientSession<D> {
+    /// The proof M1 to send to the server
+    pub fn proof(&self) -> &[u8] {
+        &self.m1
+    }
+
+    /// Checks the server's M2, only then is the session key to be trusted
+    pub fn verify_server(self, m2: &[u8]) -> Result<SessionKey, SrpError> {
+        if proofs_match(&self.m2, m2) {
+            Ok(self.session)
+        } else {
+            Err(SrpError::InvalidProof)
+        }
+    }
+}
+
+/// The server side of the exchange for one user's stored salt and verifier
+pub struct SrpServer<D: Digest> {
+    group: DhGroup,
+    username: Vec<u8>,
+    salt: Vec<u8>,
+    verifier: BigUint,
+    b: BigUint,
+    b_pub: BigUint,
+    _digest: std::marker::PhantomData<D>,
+}
+
+impl<D: Digest> SrpServer<D> {
+    /// `b` is the random private ephemeral value, RFC 5054 asks for at
+    /// least 256 bits
+    pub fn new(
+        group: DhGroup,
+        username: &[u8],
+        salt: &[u8],
+        verifier: BigUint,
+        b: BigUint,
+    ) -> Result<Self, SrpError> {
+        if b.is_zero() {
+            return Err(SrpError::InvalidPrivateKey);
+        }
+        let k = multiplier::<D>(&group);
+        let b_pub = k
+            .mul_mod(&verifier, &group.p)
+            .add_mod(&group.generate_exchange_key(&b), &group.p);
+        Ok(Self {
+            group,
+            username: username.to_vec(),
+            salt: salt.to_vec(),
+            verifier,
+            b,
+            b_pub,
+            _digest: std::marker::PhantomData,
+        })
+    }
+
+    /// The salt and public value `B` to send to the client
+    pub fn challenge(&self) -> (&[u8], &BigUint) {
+        (&self.salt, &self.b_pub)
+    }
+
+    /// Derives the session key from the client's public value `A` and
+    /// checks its proof M1, returning the key and the proof M2 to send back
+    pub fn verify_client(
+        self,
+        a_pub: &BigUint,
+        m1: &[u8],
+    ) -> Result<(SessionKey, Vec<u8>), SrpError> {
+        let group = &self.group;
+        if a_pub.is_zero() || a_pub >= &group.p {
+            return Err(SrpError::InvalidPublicKey);
+        }
+        let u = scrambler::<D>(group, a_pub, &self.b_pub);
+        if u.is_zero() {
+            return Err(SrpError::InvalidPublicKey);
+        }
+
+        let base =
+            a_pub.mul_mod(&self.verifier.pow_mod(&u, &group.p), &group.p);
+        let session = SessionKey::new::<D>(base.pow_mod(&self.b, &group.p));
+
+        let expected = client_proof::<D>(
+            group,
+            &self.username,
+            &self.salt,
+            a_pub,
+            &self.b_pub,
+            session.key(),
+        );
+        if !proofs_match(&expected, m1) {
+            return Err(SrpError::InvalidProof);
+        }
+        let m2 = server_proof::<D>(a_pub, m1, session.key());
+        Ok((session, m2))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hex;
+    use crate::sha1::Sha1;
+    use crate::sha2::Sha256;
+
+    fn int(s: &str) -> BigUint {
+        BigUint::from_hex(s).unwrap()
+    }
+
+    const SALT: &str = "BEB25379D1A8581EB5A727673A2441EE";
+    const A: &str =
+        "60975527035CF2AD1989806F0407210BC81EDC04E2762A56AFD529DDDA2D4393";
+    const B: &str =
+        "E487CB59D31AC550471E81F00F6928E01DDA08E974A004F49E61F5D105284D20";
+
+    fn server(b: &str) -> SrpServer<Sha1> {
+        let group = rfc5054_group(1024).unwrap();
+        let salt = hex(SALT);
+        let v =
+            generate_verifier::<Sha1>(&group, &salt, b"alice", b"password123");
+        SrpServer::new(group, b"alice", &salt, v, int(b)).unwrap()
+    }
+
+    /// RFC 5054 appendix B
+    #[test]
+    fn test_rfc5054_vectors() {
+        let group = rfc5054_group(1024).unwrap();
+        let salt = hex(SALT);
+
+        assert_eq!(
+            multiplier::<Sha1>(&group),
+            int("7556AA045AEF2CDD07ABAF0F665C3E818913186F")
+        );
+        assert_eq!(
+            private_key::<Sha1>(&salt, b"alice", b"password123"),
+            int("94B7555AABE9127CC58CCF4993DB6CF84D16C124")
+        );
+        assert_eq!(
+            generate_verifier::<Sha1>(&group, &salt, b"alice", b"password123"),
+            int(
+                "7E273DE8696FFC4F4E337D05B4B375BEB0DDE1569E8FA00A9886D8129BADA1F1
+                 822223CA1A605B530E379BA4729FDC59F105B4787E5186F5C671085A1447B52A
+                 48CF1970B4FB6F8400BBF4CEBFBB168152E08AB5EA53D15C1AFF87B2B9DA6E04
+                 E058AD51CC72BFC9033B564E26480D78E955A5E29E7AB245DB2BE315E2099AFB"
+            )
+        );
+
+        let client = SrpClient::<Sha1>::new(group.clone(), int(A)).unwrap();
+        let a_pub = client.public_key().clone();
+        assert_eq!(
+            a_pub,
+            int(
+                "61D5E490F6F1B79547B0704C436F523DD0E560F0C64115BB72557EC44352E890
+                 3211C04692272D8B2D1A5358A2CF1B6E0BFCF99F921530EC8E39356179EAE45E
+                 42BA92AEACED825171E1E8B9AF6D9C03E1327F44BE087EF06530E69F66615261
+                 EEF54073CA11CF5858F0EDFDFE15EFEAB349EF5D76988A3672FAC47B0769447B"
+            )
+        );
+
+        let server = server(B);
+        let (_, b_pub) = server.challenge();
+        let b_pub = b_pub.clone();
+        assert_eq!(
+            b_pub,
+            int(
+                "BD0C61512C692C0CB6D041FA01BB152D4916A1E77AF46AE105393011BAF38964
+                 DC46A0670DD125B95A981652236F99D9B681CBF87837EC996C6DA04453728610
+                 D0C6DDB58B318885D7D82C7F8DEB75CE7BD4FBAA37089E6F9C6059F388838E7A
+                 00030B331EB76840910440B1B27AAEAEEB4012B7D7665238A8E3FB004B117B58"
+            )
+        );
+        assert_eq!(
+            scrambler::<Sha1>(&group, &a_pub, &b_pub),
+            int("CE38B9593487DA98554ED47D70A7AE5F462EF019")
+        );
+
+        let premaster = int(
+            "B0DC82BABCF30674AE450C0287745E7990A3381F63B387AAF271A10D233861E3
+             59B48220F7C4693C9AE12B0A6F67809F0876E2D013800D6C41BB59B6D5979B5C
+             00A172B4A2A5903A0BDCAF8A709585EB2AFAFA8F3499B200210DCC1F10EB3394
+             3CD67FC88A2F39A4BE5BEC4EC0A3212DC346D7E474B29EDE8A469FFECA686E5A",
+        );
+        let client = client
+            .process_challenge(b"alice", b"password123", &salt, &b_pub)
+            .unwrap();
+        let (server_key, m2) =
+            server.verify_client(&a_pub, client.proof()).unwrap();
+        let client_key = client.verify_server(&m2).unwrap();
+        assert_eq!(client_key.premaster_secret(), &premaster);
+        assert_eq!(client_key, server_key);
+    }
+
+    #[test]
+    fn test_wrong_password() {
+        let group = rfc5054_group(1024).unwrap();
+        let client = SrpClient::<Sha1>::new(group, int(A)).unwrap();
+        let a_pub = client.public_key().clone();
+        let server = server(B);
+        let b_pub = server.challenge().1.clone();
+
+        let client = client
+            .process_challenge(b"alice", b"password124", &hex(SALT), &b_pub)
+            .unwrap();
+        assert_eq!(
+            server.verify_client(&a_pub, client.proof()),
+            Err(SrpError::InvalidProof)
+        );
+    }
+
+    #[test]
+    fn test_forged_server_proof() {
+        let group = rfc5054_group(1024).unwrap();
+        let client = SrpClient::<Sha1>::new(group, int(A)).unwrap();
+        let b_pub = server(B).challenge().1.clone();
+        let client = client
+            .process_challenge(b"alice", b"password123", &hex(SALT), &b_pub)
+            .unwrap();
+        assert_eq!(
+            client.verify_server(&[0; 20]).unwrap_err(),
+            SrpError::InvalidProof
+        );
+    }
+
+    #[test]
+    fn test_rejects_zero_public_keys() {
+        let group = rfc5054_group(1024).unwrap();
+        for bad in [
+            BigUint::zero(),
+            group.p.clone(),
+            &group.p * &BigUint::from(2u64),
+        ] {
+            let client = SrpClient::<Sha1>::new(group.clone(), int(A)).unwrap();
+            assert_eq!(
+                client
+                    .process_challenge(
+                        b"alice",
+                        b"password123",
+                        &hex(SALT),
+                        &bad
+                    )
+                    .err(),
+                Some(SrpError::InvalidPublicKey)
+            );
+            // with A = 0 the server's S would be 0 whatever the password
+            assert_eq!(
+                server(B).verify_client(&bad, &[0; 20]).err(),
+                Some(SrpError::InvalidPublicKey)
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_public_keys_not_below_n() {
+        // Neither is a multiple of N, and being wider than N they would not
+        // fit the padding of u = H(PAD(A) | PAD(B))
+        let group = rfc5054_group(1024).unwrap();
+        for bad in [
+            &group.p + &BigUint::one(),
+            &(&BigUint::one() << 1024) + &BigUint::one(),
+        ] {
+            let client = SrpClient::<Sha1>::new(group.clone(), int(A)).unwrap();
+            assert_eq!(
+                client
+                    .process_challenge(
+                        b"alice",
+                        b"password123",
+                        &hex(SALT),
+                        &bad
+                    )
+                    .err(),
+                Some(SrpError::InvalidPublicKey)
+            );
+            assert_eq!(
+                server(B).verify_client(&bad, &[0; 20]).err(),
+                Some(SrpError::InvalidPublicKey)
+            );
+        }
+    }
+
+    #[test]
+    fn test_exchange_sha256_2048() {
+        let group = rfc5054_group(2048).unwrap();
+        let salt = b"some salt";
+        let v = generate_verifier::<Sha256>(&group, salt, b"bob", b"hunter2");
+        let server =
+            SrpServer::<Sha256>::new(group.clone(), b"bob", salt, v, int(B))
+                .unwrap();
+        let client = SrpClient::<Sha256>::new(group, int(A)).unwrap();
+        let a_pub = client.public_key().clone();
+
+        let (salt, b_pub) = server.challenge();
+        let client = client
+            .process_challenge(b"bob", b"hunter2", salt, b_pub)
+            .unwrap();
+        let (server_key, m2) =
+            server.verify_client(&a_pub, client.proof()).unwrap();
+        assert_eq!(client.verify_server(&m2).unwrap(), server_key);
+    }
+
+    #[test]
+    fn test_group_sizes() {
+        for bits in RFC5054_SIZES {
+            let group = rfc5054_group(bits).unwrap();
+            assert_eq!(group.p.bits(), bits);
+            assert_eq!(&(&group.q << 1) + &BigUint::one(), group.p);
+        }
+        assert!(rfc5054_group(512).is_none());
+    }
+}
@@ -0,0 +1,718 @@
+//! Tools for taking apart curves over small prime fields, such as the X97
+//! teaching curve of [`crate::x25519`]
+//!
+//! Curves are handled in short Weierstrass form `y^2 = x^3 + ax + b`,
+//! which every Montgomery curve converts to. Points are counted either by
+//! summing Legendre symbols over the whole field or by Schoof's algorithm,
+//! which finds the trace of Frobenius mod small primes l from its action on
+//! the l-torsion. The group order gives point orders, cofactors and the
+//! order of the twist, and discrete logarithms fall to baby step giant
+//! step. Everything here is variable time and meant for analysis only
+
+use crate::field::{Field, SmallField};
+use crate::math::factorize;
+use crate::montgomery::{CurveError, MontgomeryCurve};
+use std::collections::HashMap;
+
+/// A point of a [`ToyCurve`] with both coordinates
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Point<const P: u64> {
+    Infinity,
+    Affine { x: SmallField<P>, y: SmallField<P> },
+}
+
+/// `y^2 = x^3 + ax + b` over GF(P) for a prime P above 3
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ToyCurve<const P: u64> {
+    pub a: SmallField<P>,
+    pub b: SmallField<P>,
+    /// Added to a Montgomery u-coordinate to give x, zero unless the curve
+    /// came from [`ToyCurve::from_montgomery`]
+    shift: SmallField<P>,
+}
+
+impl<const P: u64> ToyCurve<P> {
+    pub fn new(a: SmallField<P>, b: SmallField<P>) -> Result<Self, CurveError> {
+        let four = SmallField::from_u64(4);
+        let twenty_seven = SmallField::from_u64(27);
+        if (four * a.square() * a + twenty_seven * b.square()).is_zero() {
+            return Err(CurveError::Singular);
+        }
+        Ok(Self {
+            a,
+            b,
+            shift: SmallField::ZERO,
+        })
+    }
+
+    /// The Weierstrass form of `v^2 = u^3 + Au^2 + u`, reached by
+    /// substituting `u = x - A/3`
+    pub fn from_montgomery(
+        curve: &MontgomeryCurve<SmallField<P>>,
+    ) -> Result<Self, CurveError> {
+        let big_a = curve.a;
+        let three = SmallField::from_u64(3);
+        let a = (three - big_a.square()) * three.invert();
+        let b = (SmallField::from_u64(2) * big_a.square() * big_a
+            - SmallField::from_u64(9) * big_a)
+            * SmallField::from_u64(27).invert();
+        Ok(Self {
+            shift: big_a * three.invert(),
+            ..Self::new(a, b)?
+        })
+    }
+
+    fn rhs(&self, x: SmallField<P>) -> SmallField<P> {
+        (x.square() + self.a) * x + self.b
+    }
+
+    pub fn is_on_curve(&self, point: &Point<P>) -> bool {
+        match *point {
+            Point::Infinity => true,
+            Point::Affine { x, y } => y.square() == self.rhs(x),
+        }
+    }
+
+    /// The point with this x and an even y, `None` if x is not on the
+    /// curve. The other point with the same x is its negation
+    pub fn lift_x(&self, x: SmallField<P>) -> Option<Point<P>> {
+        let mut y = self.rhs(x).sqrt()?;
+        if y.value() % 2 == 1 {
+            y = -y;
+        }
+        Some(Point::Affine { x, y })
+    }
+
+    /// [`ToyCurve::lift_x`] for the u-coordinate of the Montgomery curve
+    /// this one was converted from
+    pub fn lift_u(&self, u: SmallField<P>) -> Option<Point<P>> {
+        self.lift_x(u + self.shift)
+    }
+
+    /// The Montgomery u-coordinate of a point, `None` at infinity
+    pub fn to_u(&self, point: &Point<P>) -> Option<SmallField<P>> {
+        match *point {
+            Point::Infinity => None,
+            Point::Affine { x, .. } => Some(x - self.shift),
+        }
+    }
+
+    pub fn neg(&self, point: &Point<P>) -> Point<P> {
+        match *point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::Affine { x, y: -y },
+        }
+    }
+
+    /// The chord and tangent rule
+    pub fn add(&self, p: &Point<P>, q: &Point<P>) -> Point<P> {
+        let (x1, y1, x2, y2) = match (*p, *q) {
+            (Point::Infinity, _) => return *q,
+            (_, Point::Infinity) => return *p,
+            (
+                Point::Affine { x: x1, y: y1 },
+                Point::Affine { x: x2, y: y2 },
+            ) => (x1, y1, x2, y2),
+        };
+        let slope = if x1 != x2 {
+            (y2 - y1) * (x2 - x1).invert()
+        } else if y1 == -y2 {
+            return Point::Infinity;
+        } else {
+            let three = SmallField::from_u64(3);
+            (three * x1.square() + self.a) * (y1 + y1).invert()
+        };
+        let x = slope.square() - x1 - x2;
+        Point::Affine {
+            x,
+            y: slope * (x1 - x) - y1,
+        }
+    }
+
+    /// `[k]point` by double and add
+    pub fn mul(&self, k: u64, point: &Point<P>) -> Point<P> {
+        let mut result = Point::Infinity;
+        for i in (0..u64::BITS - k.leading_zeros()).rev() {
+            result = self.add(&result, &result);
+            if (k >> i) & 1 == 1 {
+                result = self.add(&result, point);
+            }
+        }
+        result
+    }
+
+    /// Every point of the curve, the point at infinity first
+    pub fn points(&self) -> Vec<Point<P>> {
+        let mut points = vec![Point::Infinity];
+        for x in 0..P {
+            if let Some(point) = self.lift_x(SmallField::from_u64(x)) {
+                points.push(point);
+                if self.neg(&point) != point {
+                    points.push(self.neg(&point));
+                }
+            }
+        }
+        points
+    }
+
+    /// The number of points by brute force: each x with `x^3 + ax + b` a
+    /// nonzero square gives two, a zero gives one
+    pub fn count_points(&self) -> u64 {
+        let mut count = 1;
+        for x in 0..P {
+            let rhs = self.rhs(SmallField::from_u64(x));
+            if rhs.is_zero() {
+                count += 1;
+            } else if rhs.is_square() {
+                count += 2;
+            }
+        }
+        count
+    }
+
+    /// The number of points by Schoof's algorithm. The count is
+    /// `P + 1 - t` with `|t| <= 2 sqrt(P)` by Hasse's theorem, so t follows
+    /// by the Chinese remainder theorem from its residues mod small primes
+    /// whose product exceeds `4 sqrt(P)`
+    pub fn count_points_schoof(&self) -> u64 {
+        let curve =
+            Poly::new(vec![self.b, self.a, SmallField::ZERO, SmallField::ONE]);
+
+        // t is even exactly when there is a point of order 2, a root of
+        // the curve polynomial
+        let x_p = Poly::x().pow_mod(P as u128, &curve);
+        let has_two_torsion = !x_p.sub(&Poly::x()).gcd(&curve).is_constant();
+        let mut trace = if has_two_torsion { 0 } else { 1 };
+        let mut modulus = 2;
+
+        let mut l = 3;
+        while (modulus as u128).pow(2) <= 16 * P as u128 {
+            if l != P && factorize(l) == [(l, 1)] {
+                let residue = self.trace_mod(l);
+                // lift the pair of residues to one mod `modulus * l`
+                while trace % l != residue {
+                    trace += modulus;
+                }
+                modulus *= l;
+            }
+            l += 2;
+        }
+        let trace = trace as i64;
+        let modulus = modulus as i64;
+        let trace = if trace > modulus / 2 {
+            trace - modulus
+        } else {
+            trace
+        };
+        (P as i64 + 1 - trace) as u64
+    }
+
+    /// The trace of Frobenius mod the odd prime l, the one τ with
+    /// `φ^2(Q) + [P]Q = [τ]φ(Q)` for the l-torsion points Q. Polynomials
+    /// are reduced mod the l-th division polynomial, and when a
+    /// denominator turns out to share a factor with it the work restarts
+    /// mod that factor, which holds a smaller set of torsion points
+    fn trace_mod(&self, l: u64) -> u64 {
+        let mut modulus = self.division_polynomial(l);
+        loop {
+            let torsion = Torsion::new(self, modulus);
+            match torsion.trace(l) {
+                Ok(trace) => return trace,
+                Err(factor) => modulus = factor,
+            }
+        }
+    }
+
+    /// ψ_n for odd n, the polynomial whose roots are the x-coordinates of
+    /// the points of order dividing n
+    fn division_polynomial(&self, n: u64) -> Poly<P> {
+        let (a, b) = (self.a, self.b);
+        let c = |value: u64| SmallField::<P>::from_u64(value);
+        let curve = Poly::new(vec![b, a, c(0), c(1)]);
+        let curve_squared = curve.mul(&curve);
+
+        // f_n with ψ_n = f_n for odd n and ψ_n = y f_n for even n
+        let mut f = vec![
+            Poly::new(vec![]),
+            Poly::new(vec![c(1)]),
+            Poly::new(vec![c(2)]),
+            Poly::new(vec![-a.square(), c(12) * b, c(6) * a, c(0), c(3)]),
+            Poly::new(vec![
+                c(4) * (-c(8) * b.square() - a.square() * a),
+                -c(16) * a * b,
+                -c(20) * a.square(),
+                c(80) * b,
+                c(20) * a,
+                c(0),
+                c(4),
+            ]),
+        ];
+        for k in 5..=n as usize {
+            let m = k / 2;
+            let next = if k % 2 == 1 {
+                let left = f[m + 2].mul(&f[m].mul(&f[m]).mul(&f[m]));
+                let right =
+                    f[m - 1].mul(&f[m + 1].mul(&f[m + 1]).mul(&f[m + 1]));
+                if m % 2 == 0 {
+                    curve_squared.mul(&left).sub(&right)
+                } else {
+                    left.sub(&curve_squared.mul(&right))
+                }
+            } else {
+                let left = f[m + 2].mul(&f[m - 1].mul(&f[m - 1]));
+                let right = f[m - 2].mul(&f[m + 1].mul(&f[m + 1]));
+                f[m].mul(&left.sub(&right)).scale(c(2).invert())
+            };
+            f.push(next);
+        }
+        f.swap_remove(n as usize)
+    }
+
+    /// The quadratic twist `y^2 = x^3 + ad^2 x + bd^3` for a non-square d,
+    /// in plain Weierstrass coordinates. Its x-coordinates are d times
+    /// those that have no point on this curve, so together the two curves
+    /// have `2P + 2` points
+    pub fn twist(&self) -> Self {
+        let d = (2..P)
+            .map(SmallField::from_u64)
+            .find(|d| !d.is_square())
+            .expect("half of GF(P) is non-square");
+        Self::new(self.a * d.square(), self.b * d.square() * d)
+            .expect("the twist of a smooth curve is smooth")
+    }
+
+    /// The least n with `[n]point` at infinity, found by dividing primes
+    /// out of the group order while the multiple stays at infinity
+    pub fn point_order(&self, point: &Point<P>) -> u64 {
+        let mut order = self.count_points_schoof();
+        for (prime, _) in factorize(order) {
+            while order.is_multiple_of(prime)
+                && self.mul(order / prime, point) == Point::Infinity
+            {
+                order /= prime;
+            }
+        }
+        order
+    }
+
+    /// The index of the subgroup the point generates, 1 if it generates
+    /// the whole group
+    pub fn cofactor(&self, point: &Point<P>) -> u64 {
+        self.count_points_schoof() / self.point_order(point)
+    }
+
+    /// A generator of the subgroup of the largest prime order dividing the
+    /// group order, with that order. `None` for the trivial group
+    pub fn prime_subgroup(&self) -> Option<(Point<P>, u64)> {
+        let count = self.count_points_schoof();
+        let &(prime, _) = factorize(count).last()?;
+        self.points()
+            .iter()
+            .map(|point| self.mul(count / prime, point))
+            .find(|point| *point != Point::Infinity)
+            .map(|generator| (generator, prime))
+    }
+
+    /// The k in `[0, n)` with `[k]base = target` for n the order of the
+    /// base, by baby step giant step. `None` if the target is not in the
+    /// subgroup the base generates
+    pub fn discrete_log(
+        &self,
+        base: &Point<P>,
+        target: &Point<P>,
+    ) -> Option<u64> {
+        let order = self.point_order(base);
+        let steps = (1..).find(|m| m * m >= order).unwrap();
+
+        let mut baby = HashMap::new();
+        let mut point = Point::Infinity;
+        for j in 0..steps {
+            baby.entry(point).or_insert(j);
+            point = self.add(&point, base);
+        }
+        let giant = self.neg(&self.mul(steps, base));
+        let mut point = *target;
+        for i in 0..steps {
+            if let Some(j) = baby.get(&point) {
+                return Some((i * steps + j) % order);
+            }
+            point = self.add(&point, &giant);
+        }
+        None
+    }
+}
+
+/// A polynomial over GF(P), lowest coefficient first without trailing
+/// zeros
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Poly<const P: u64>(Vec<SmallField<P>>);
+
+impl<const P: u64> Poly<P> {
+    fn new(mut coefficients: Vec<SmallField<P>>) -> Self {
+        while coefficients.last().is_some_and(|c| c.is_zero()) {
+            coefficients.pop();
+        }
+        Self(coefficients)
+    }
+
+    fn x() -> Self {
+        Self(vec![SmallField::ZERO, SmallField::ONE])
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn is_constant(&self) -> bool {
+        self.0.len() <= 1
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let len = self.0.len().max(other.0.len());
+        let coefficient = |p: &Self, i: usize| {
+            p.0.get(i).copied().unwrap_or(SmallField::ZERO)
+        };
+        Self::new(
+            (0..len)
+                .map(|i| coefficient(self, i) + coefficient(other, i))
+                .collect(),
+        )
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        self.add(&other.scale(-SmallField::ONE))
+    }
+
+    fn scale(&self, c: SmallField<P>) -> Self {
+        Self::new(self.0.iter().map(|&x| x * c).collect())
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        if self.is_zero() || other.is_zero() {
+            return Self(vec![]);
+        }
+        let mut product =
+            vec![SmallField::ZERO; self.0.len() + other.0.len() - 1];
+        for (i, &x) in self.0.iter().enumerate() {
+            for (j, &y) in other.0.iter().enumerate() {
+                product[i + j] = product[i + j] + x * y;
+            }
+        }
+        Self::new(product)
+    }
+
+    /// Quotient and remainder by a nonzero divisor
+    fn div_rem(&self, divisor: &Self) -> (Self, Self) {
+        let degree = divisor.0.len() - 1;
+        let lead_inverse = divisor.0[degree].invert();
+        let mut remainder = self.0.clone();
+        let mut quotient =
+            vec![SmallField::ZERO; self.0.len().saturating_sub(degree)];
+        for i in (degree..remainder.len()).rev() {
+            let factor = remainder[i] * lead_inverse;
+            quotient[i - degree] = factor;
+            for (j, &d) in divisor.0.iter().enumerate() {
+                remainder[i - degree + j] =
+                    remainder[i - degree + j] - factor * d;
+            }
+        }
+        remainder.truncate(degree);
+        (Self::new(quotient), Self::new(remainder))
+    }
+
+    fn rem(&self, modulus: &Self) -> Self {
+        self.div_rem(modulus).1
+    }
+
+    fn pow_mod(&self, mut exponent: u128, modulus: &Self) -> Self {
+        let mut result = Self(vec![SmallField::ONE]).rem(modulus);
+        let mut base = self.rem(modulus);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base).rem(modulus);
+            }
+            base = base.mul(&base).rem(modulus);
+            exponent >>= 1;
+        }
+        result
+    }
+
+    fn monic(&self) -> Self {
+        match self.0.last() {
+            Some(lead) => self.scale(lead.invert()),
+            None => self.clone(),
+        }
+    }
+
+    fn gcd(&self, other: &Self) -> Self {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            (a, b) = (b.clone(), a.rem(&b));
+        }
+        a.monic()
+    }
+
+    /// The inverse mod `modulus` by the extended Euclidean algorithm, or
+    /// the common factor that stops it existing
+    fn invert_mod(&self, modulus: &Self) -> Result<Self, Self> {
+        let (mut r0, mut r1) = (modulus.clone(), self.rem(modulus));
+        let (mut s0, mut s1) = (Self(vec![]), Self(vec![SmallField::ONE]));
+        while !r1.is_zero() {
+            let (quotient, remainder) = r0.div_rem(&r1);
+            (r0, r1) = (r1, remainder);
+            let next = s0.sub(&quotient.mul(&s1));
+            (s0, s1) = (s1, next);
+        }
+        if r0.is_constant() {
+            Ok(s0.scale(r0.0[0].invert()).rem(modulus))
+        } else {
+            Err(r0.monic())
+        }
+    }
+}
+
+/// A point `(X(x), y Y(x))` whose x is a root of the torsion modulus,
+/// which stands for all those points at once
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TorsionPoint<const P: u64> {
+    Zero,
+    Affine(Poly<P>, Poly<P>),
+}
+
+/// Arithmetic on [`TorsionPoint`]s in `GF(P)[x, y] / (h(x), y^2 - f(x))`.
+/// An `Err` carries a proper factor of h found when a denominator could
+/// not be inverted
+struct Torsion<const P: u64> {
+    modulus: Poly<P>,
+    /// The curve polynomial `x^3 + ax + b` reduced mod h
+    curve: Poly<P>,
+    a: SmallField<P>,
+}
+
+impl<const P: u64> Torsion<P> {
+    fn new(curve: &ToyCurve<P>, modulus: Poly<P>) -> Self {
+        let f = Poly::new(vec![
+            curve.b,
+            curve.a,
+            SmallField::ZERO,
+            SmallField::ONE,
+        ]);
+        Self {
+            curve: f.rem(&modulus),
+            modulus,
+            a: curve.a,
+        }
+    }
+
+    fn mul(&self, x: &Poly<P>, y: &Poly<P>) -> Poly<P> {
+        x.mul(y).rem(&self.modulus)
+    }
+
+    fn add(
+        &self,
+        p: &TorsionPoint<P>,
+        q: &TorsionPoint<P>,
+    ) -> Result<TorsionPoint<P>, Poly<P>> {
+        let ((x1, y1), (x2, y2)) = match (p, q) {
+            (TorsionPoint::Zero, _) => return Ok(q.clone()),
+            (_, TorsionPoint::Zero) => return Ok(p.clone()),
+            (TorsionPoint::Affine(x1, y1), TorsionPoint::Affine(x2, y2)) => {
+                ((x1, y1), (x2, y2))
+            }
+        };
+        let dx = x2.sub(x1).rem(&self.modulus);
+        let dy = y2.sub(y1).rem(&self.modulus);
+        // y (y2 - y1) / (x2 - x1) = y L, or for a doubling
+        // (3x^2 + a) / 2yY = y (3x^2 + a) / 2f Y
+        let slope = if !dx.is_zero() {
+            self.mul(&dy, &dx.invert_mod(&self.modulus)?)
+        } else if dy.is_zero() {
+            let three_x2 = self.mul(x1, x1).scale(SmallField::from_u64(3));
+            let numerator = three_x2.add(&Poly::new(vec![self.a]));
+            let denominator =
+                self.mul(&self.curve, y1).scale(SmallField::from_u64(2));
+            self.mul(&numerator, &denominator.invert_mod(&self.modulus)?)
+        } else if y1.add(y2).rem(&self.modulus).is_zero() {
+            return Ok(TorsionPoint::Zero);
+        } else {
+            // equal x everywhere but y equal only at some of the roots
+            return Err(dy.gcd(&self.modulus));
+        };
+        let x3 = self
+            .mul(&self.curve, &self.mul(&slope, &slope))
+            .sub(x1)
+            .sub(x2)
+            .rem(&self.modulus);
+        let y3 = self.mul(&slope, &x1.sub(&x3)).sub(y1).rem(&self.modulus);
+        Ok(TorsionPoint::Affine(x3, y3))
+    }
+
+    fn scalar_mul(
+        &self,
+        k: u64,
+        point: &TorsionPoint<P>,
+    ) -> Result<TorsionPoint<P>, Poly<P>> {
+        let mut result = TorsionPoint::Zero;
+        for i in (0..u64::BITS - k.leading_zeros()).rev() {
+            result = self.add(&result, &result)?;
+            if (k >> i) & 1 == 1 {
+                result = self.add(&result, point)?;
+            }
+        }
+        Ok(result)
+    }
+
+    fn trace(&self, l: u64) -> Result<u64, Poly<P>> {
+        let h = &self.modulus;
+        let p = P as u128;
+        // φ(x, y) = (x^p, y f^((p - 1) / 2)) since y^p = y (y^2)^((p - 1) / 2)
+        let frobenius = TorsionPoint::Affine(
+            Poly::x().pow_mod(p, h),
+            self.curve.pow_mod((p - 1) / 2, h),
+        );
+        let frobenius_squared = TorsionPoint::Affine(
+            Poly::x().pow_mod(p * p, h),
+            self.curve.pow_mod((p * p - 1) / 2, h),
+        );
+        let generic = TorsionPoint::Affine(
+            Poly::x().rem(h),
+            Poly::new(vec![SmallField::ONE]),
+        );
+        let multiple = self.scalar_mul(P % l, &generic)?;
+        let lhs = self.add(&frobenius_squared, &multiple)?;
+
+        let mut rhs = TorsionPoint::Zero;
+        for tau in 0..l {
+            if rhs == lhs {
+                return Ok(tau);
+            }
+            rhs = self.add(&rhs, &frobenius)?;
+        }
+        unreachable!("the trace satisfies the characteristic equation")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::x25519::{x97_curve, F97};
+
+    fn x97() -> ToyCurve<97> {
+        ToyCurve::from_montgomery(&x97_curve()).unwrap()
+    }
+
+    #[test]
+    fn test_x97_group() {
+        let curve = x97();
+        assert_eq!(curve.count_points(), 88);
+        assert_eq!(curve.count_points_schoof(), 88);
+        assert_eq!(curve.points().len(), 88);
+        assert!(curve.points().iter().all(|p| curve.is_on_curve(p)));
+
+        // the base point u = 3 generates a subgroup of order 22 only
+        let base = curve.lift_u(x97_curve().base).unwrap();
+        assert_eq!(curve.to_u(&base), Some(F97::from_u64(3)));
+        assert_eq!(curve.point_order(&base), 22);
+        assert_eq!(curve.cofactor(&base), 4);
+        assert_eq!(curve.prime_subgroup().map(|(_, order)| order), Some(11));
+        let (generator, _) = curve.prime_subgroup().unwrap();
+        assert_eq!(curve.mul(11, &generator), Point::Infinity);
+    }
+
+    /// The u-coordinates the ladder accepts but that have no point on the
+    /// curve land on the twist, whose order has no prime factor above 3
+    #[test]
+    fn test_x97_twist() {
+        let twist = x97().twist();
+        assert_eq!(twist.count_points(), 108);
+        assert_eq!(twist.count_points_schoof(), 108);
+        assert_eq!(factorize(108).last(), Some(&(3, 3)));
+        assert_eq!(x97().count_points() + 108, 2 * 97 + 2);
+    }
+
+    #[test]
+    fn test_lift_x() {
+        let curve = x97();
+        for x in 0..97 {
+            let x = F97::from_u64(x);
+            match curve.lift_x(x) {
+                Some(point @ Point::Affine { y, .. }) => {
+                    assert!(curve.is_on_curve(&point));
+                    assert_eq!(y.value() % 2, 0);
+                }
+                Some(Point::Infinity) => unreachable!(),
+                None => assert!(!curve.rhs(x).is_square()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_law() {
+        let curve = x97();
+        let points = curve.points();
+        for p in &points {
+            assert_eq!(curve.add(p, &curve.neg(p)), Point::Infinity);
+            assert_eq!(curve.mul(88, p), Point::Infinity);
+            for q in points.iter().step_by(7) {
+                assert_eq!(curve.add(p, q), curve.add(q, p));
+                assert!(curve.is_on_curve(&curve.add(p, q)));
+            }
+        }
+    }
+
+    fn check_schoof<const P: u64>() {
+        for (a, b) in [(1, 1), (2, 3), (0, 7), (P - 3, 5), (5, 0)] {
+            let curve = ToyCurve::<P>::new(
+                SmallField::from_u64(a),
+                SmallField::from_u64(b),
+            )
+            .unwrap();
+            assert_eq!(curve.count_points_schoof(), curve.count_points());
+            let twist = curve.twist();
+            assert_eq!(
+                curve.count_points_schoof() + twist.count_points_schoof(),
+                2 * P + 2
+            );
+        }
+    }
+
+    #[test]
+    fn test_schoof_matches_brute_force() {
+        check_schoof::<101>();
+        check_schoof::<103>();
+        check_schoof::<1009>();
+        check_schoof::<10007>();
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        let curve = x97();
+        let base = curve.lift_u(x97_curve().base).unwrap();
+        for k in 0..22 {
+            let target = curve.mul(k, &base);
+            assert_eq!(curve.discrete_log(&base, &target), Some(k));
+        }
+        // a point of order 4 or 8 cannot be in a subgroup of order 22
+        let points = curve.points();
+        let outside = points
+            .iter()
+            .find(|point| curve.point_order(point).is_multiple_of(4))
+            .unwrap();
+        assert_eq!(curve.discrete_log(&base, outside), None);
+    }
+
+    #[test]
+    fn test_singular_curve() {
+        // A = 2 makes u^3 + 2u^2 + u = u(u + 1)^2
+        let curve = MontgomeryCurve::new(
+            F97::from_u64(2),
+            F97::from_u64(3),
+            crate::bigint::BigUint::one(),
+            1,
+        );
+        assert_eq!(
+            ToyCurve::from_montgomery(&curve),
+            Err(CurveError::Singular)
+        );
+    }
+}
@@ -0,0 +1,458 @@
+//! Short Weierstrass curves `y^2 = x^3 + ax + b` over any [`Field`], the
+//! form of the NIST curves
+//!
+//! Points are added in Jacobian coordinates (X:Y:Z) with x = X/Z^2 and
+//! y = Y/Z^3. The textbook addition formula fails for doubling, inverses
+//! and the identity, so [`WeierstrassCurve::add`] computes both the general
+//! sum and the doubling and selects the right result without branching.
+//! That makes the group law complete and lets the ladder run in constant
+//! time
+
+use crate::bigint::BigUint;
+use crate::field::Field;
+use crate::montgomery::{within_hasse_bound, CurveError};
+use crate::primefield::{FieldElement, Modulus};
+use std::ops::Neg;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointError {
+    /// Not a SEC1 encoding of the right length and tag, the single zero
+    /// byte of the identity included as it has no affine form
+    InvalidEncoding,
+    /// The coordinates do not satisfy the curve equation
+    NotOnCurve,
+}
+
+impl std::fmt::Display for PointError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::InvalidEncoding => "invalid point encoding",
+            Self::NotOnCurve => "point not on curve",
+        };
+        f.write_str(message)
+    }
+}
+
+impl std::error::Error for PointError {}
+
+/// A point other than the identity
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AffinePoint<F: Field> {
+    pub x: F,
+    pub y: F,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct JacobianPoint<F: Field> {
+    pub x: F,
+    pub y: F,
+    pub z: F,
+}
+
+impl<F: Field> JacobianPoint<F> {
+    pub const IDENTITY: Self = Self {
+        x: F::ONE,
+        y: F::ONE,
+        z: F::ZERO,
+    };
+
+    pub fn from_affine(point: &AffinePoint<F>) -> Self {
+        Self {
+            x: point.x,
+            y: point.y,
+            z: F::ONE,
+        }
+    }
+
+    /// The affine coordinates, `None` for the identity
+    pub fn to_affine(&self) -> Option<AffinePoint<F>> {
+        if self.is_identity() {
+            return None;
+        }
+        let z_inv = self.z.invert();
+        let z_inv2 = z_inv.square();
+        Some(AffinePoint {
+            x: self.x * z_inv2,
+            y: self.y * z_inv2 * z_inv,
+        })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: u8) {
+        F::conditional_swap(&mut a.x, &mut b.x, choice);
+        F::conditional_swap(&mut a.y, &mut b.y, choice);
+        F::conditional_swap(&mut a.z, &mut b.z, choice);
+    }
+
+    /// `b` if `choice` is 1 and `a` if it is 0
+    fn select(a: &Self, b: &Self, choice: u8) -> Self {
+        let (mut a, mut b) = (*a, *b);
+        Self::conditional_swap(&mut a, &mut b, choice);
+        a
+    }
+}
+
+/// Projective equality, `X1 Z2^2 = X2 Z1^2` and `Y1 Z2^3 = Y2 Z1^3`
+impl<F: Field> PartialEq for JacobianPoint<F> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.is_identity() || other.is_identity() {
+            return self.is_identity() == other.is_identity();
+        }
+        let (z1z1, z2z2) = (self.z.square(), other.z.square());
+        self.x * z2z2 == other.x * z1z1
+            && self.y * z2z2 * other.z == other.y * z1z1 * self.z
+    }
+}
+
+impl<F: Field> Eq for JacobianPoint<F> {}
+
+impl<F: Field> Neg for JacobianPoint<F> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self { y: -self.y, ..self }
+    }
+}
+
+/// The curve `y^2 = x^3 + ax + b` with a base point of order `order`
+/// generating a subgroup of index `cofactor`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeierstrassCurve<F: Field> {
+    pub a: F,
+    pub b: F,
+    pub base: AffinePoint<F>,
+    pub order: BigUint,
+    pub cofactor: u64,
+}
+
+impl<F: Field> WeierstrassCurve<F> {
+    pub fn new(
+        a: F,
+        b: F,
+        base: AffinePoint<F>,
+        order: BigUint,
+        cofactor: u64,
+    ) -> Self {
+        Self {
+            a,
+            b,
+            base,
+            order,
+            cofactor,
+        }
+    }
+
+    /// Checks the curve is non-singular, the base point is on it and has
+    /// the stated order, and the implied group order is within the Hasse
+    /// bound
+    pub fn validate(&self) -> Result<(), CurveError> {
+        let discriminant = F::from_u64(4) * self.a.square() * self.a
+            + F::from_u64(27) * self.b.square();
+        if discriminant.is_zero() {
+            return Err(CurveError::Singular);
+        }
+        if !self.is_on_curve(&self.base) {
+            return Err(CurveError::BaseNotOnCurve);
+        }
+
+        let scalar = self.order.to_le_bytes_padded(self.order.bits() / 8 + 1);
+        let points = &self.order * &BigUint::from(self.cofactor);
+        let base = JacobianPoint::from_affine(&self.base);
+        if self.order.is_zero()
+            || !self.scalar_mult(&scalar, &base).is_identity()
+            || !within_hasse_bound::<F>(&points)
+        {
+            return Err(CurveError::InvalidOrder);
+        }
+        Ok(())
+    }
+
+    pub fn is_on_curve(&self, point: &AffinePoint<F>) -> bool {
+        let AffinePoint { x, y } = *point;
+        y.square() == (x.square() + self.a) * x + self.b
+    }
+
+    /// The y-coordinates of the points with this x, `None` if there are
+    /// none. The other point is the negation of the one returned
+    pub fn lift_x(&self, x: F) -> Option<AffinePoint<F>> {
+        let y = ((x.square() + self.a) * x + self.b).sqrt()?;
+        Some(AffinePoint { x, y })
+    }
+
+    /// The general Jacobian sum, the doubling and the identity computed
+    /// and selected among, so every pair of inputs is handled
+    pub fn add(
+        &self,
+        p: &JacobianPoint<F>,
+        q: &JacobianPoint<F>,
+    ) -> JacobianPoint<F> {
+        let z1z1 = p.z.square();
+        let z2z2 = q.z.square();
+        let u1 = p.x * z2z2;
+        let u2 = q.x * z1z1;
+        let s1 = p.y * q.z * z2z2;
+        let s2 = q.y * p.z * z1z1;
+        let h = u2 - u1;
+        let r = s2 - s1;
+
+        // with h = 0 and r != 0 the points are inverses and z3 is zero
+        let hh = h.square();
+        let hhh = h * hh;
+        let v = u1 * hh;
+        let x3 = r.square() - hhh - v - v;
+        let sum = JacobianPoint {
+            x: x3,
+            y: r * (v - x3) - s1 * hhh,
+            z: p.z * q.z * h,
+        };
+
+        let same = (h.is_zero() & r.is_zero()) as u8;
+        let result = JacobianPoint::select(&sum, &self.double(p), same);
+        let result = JacobianPoint::select(&result, q, p.is_identity() as u8);
+        JacobianPoint::select(&result, p, q.is_identity() as u8)
+    }
+
+    /// Doubling for any `a`, which maps the identity and the points of
+    /// order two to the identity without special cases
+    pub fn double(&self, p: &JacobianPoint<F>) -> JacobianPoint<F> {
+        let xx = p.x.square();
+        let yy = p.y.square();
+        let yyyy = yy.square();
+        let zz = p.z.square();
+        let s = (p.x + yy).square() - xx - yyyy;
+        let s = s + s;
+        let m = xx + xx + xx + self.a * zz.square();
+        let x3 = m.square() - s - s;
+        let yyyy8 = yyyy + yyyy;
+        let yyyy8 = yyyy8 + yyyy8;
+        let yyyy8 = yyyy8 + yyyy8;
+        JacobianPoint {
+            x: x3,
+            y: m * (s - x3) - yyyy8,
+            z: (p.y + p.z).square() - yy - zz,
+        }
+    }
+
+    /// Montgomery ladder over every bit of the little endian `scalar`, its
+    /// running time depends on the scalar's length but not its value
+    pub fn scalar_mult(
+        &self,
+        scalar: &[u8],
+        point: &JacobianPoint<F>,
+    ) -> JacobianPoint<F> {
+        let mut r0 = JacobianPoint::IDENTITY;
+        let mut r1 = *point;
+        let mut swap = 0;
+
+        for i in (0..scalar.len() * 8).rev() {
+            let bit = (scalar[i / 8] >> (i % 8)) & 1;
+            swap ^= bit;
+            JacobianPoint::conditional_swap(&mut r0, &mut r1, swap);
+            swap = bit;
+
+            r1 = self.add(&r0, &r1);
+            r0 = self.double(&r0);
+        }
+        JacobianPoint::conditional_swap(&mut r0, &mut r1, swap);
+        r0
+    }
+
+    /// `[scalar]G` for the base point G
+    pub fn mul_base(&self, scalar: &[u8]) -> JacobianPoint<F> {
+        self.scalar_mult(scalar, &JacobianPoint::from_affine(&self.base))
+    }
+}
+
+/// SEC1 point encodings for curves over the fields of
+/// [`crate::primefield`]
+impl<M: Modulus> WeierstrassCurve<FieldElement<M>> {
+    /// The encoding of SEC1 section 2.3.3, `04 || x || y`, or `02 || x`
+    /// and `03 || x` for even and odd y when compressed
+    pub fn encode_point(
+        &self,
+        point: &AffinePoint<FieldElement<M>>,
+        compressed: bool,
+    ) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + 2 * FieldElement::<M>::BYTES);
+        if compressed {
+            bytes.push(0x02 | point.y.is_odd() as u8);
+            bytes.extend(point.x.to_be_bytes());
+        } else {
+            bytes.push(0x04);
+            bytes.extend(point.x.to_be_bytes());
+            bytes.extend(point.y.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Decoding as in SEC1 section 2.3.4, rejecting coordinates that are
+    /// not reduced and points off the curve
+    pub fn decode_point(
+        &self,
+        bytes: &[u8],
+    ) -> Result<AffinePoint<FieldElement<M>>, PointError> {
+        let len = FieldElement::<M>::BYTES;
+        let element = |range: std::ops::Range<usize>| {
+            FieldElement::from_be_bytes(&bytes[range])
+                .ok_or(PointError::InvalidEncoding)
+        };
+        match (bytes.first(), bytes.len()) {
+            (Some(0x04), n) if n == 1 + 2 * len => {
+                let point = AffinePoint {
+                    x: element(1..1 + len)?,
+                    y: element(1 + len..n)?,
+                };
+                if !self.is_on_curve(&point) {
+                    return Err(PointError::NotOnCurve);
+                }
+                Ok(point)
+            }
+            (Some(&tag @ (0x02 | 0x03)), n) if n == 1 + len => {
+                let point = self
+                    .lift_x(element(1..n)?)
+                    .ok_or(PointError::NotOnCurve)?;
+                if point.y.is_odd() == (tag == 0x03) {
+                    Ok(point)
+                } else {
+                    Ok(AffinePoint {
+                        x: point.x,
+                        y: -point.y,
+                    })
+                }
+            }
+            _ => Err(PointError::InvalidEncoding),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::SmallField;
+
+    type F97 = SmallField<97>;
+
+    /// `y^2 = x^3 + 2x + 3` over GF(97), 100 points with (3, 6) of order 5
+    fn toy_curve() -> WeierstrassCurve<F97> {
+        WeierstrassCurve::new(
+            F97::from_u64(2),
+            F97::from_u64(3),
+            AffinePoint {
+                x: F97::from_u64(3),
+                y: F97::from_u64(6),
+            },
+            BigUint::from(5u64),
+            20,
+        )
+    }
+
+    fn point(x: u64, y: u64) -> JacobianPoint<F97> {
+        JacobianPoint::from_affine(&AffinePoint {
+            x: F97::from_u64(x),
+            y: F97::from_u64(y),
+        })
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(toy_curve().validate(), Ok(()));
+
+        let mut singular = toy_curve();
+        singular.a = -F97::from_u64(3);
+        singular.b = F97::from_u64(2);
+        assert_eq!(singular.validate(), Err(CurveError::Singular));
+
+        let mut off_curve = toy_curve();
+        off_curve.base.y = F97::ONE;
+        assert_eq!(off_curve.validate(), Err(CurveError::BaseNotOnCurve));
+
+        let mut wrong_order = toy_curve();
+        wrong_order.order = BigUint::from(4u64);
+        wrong_order.cofactor = 25;
+        assert_eq!(wrong_order.validate(), Err(CurveError::InvalidOrder));
+    }
+
+    #[test]
+    fn test_group_law() {
+        let curve = toy_curve();
+        let g = JacobianPoint::from_affine(&curve.base);
+
+        // multiples of (3, 6) worked by hand with the chord and tangent
+        // rules
+        let multiples = [
+            JacobianPoint::IDENTITY,
+            point(3, 6),
+            point(80, 10),
+            point(80, 87),
+            point(3, 91),
+            JacobianPoint::IDENTITY,
+        ];
+        for (k, expected) in multiples.iter().enumerate() {
+            assert_eq!(curve.scalar_mult(&[k as u8], &g), *expected, "[{k}]G");
+        }
+        assert_eq!(curve.double(&g), multiples[2]);
+        assert_eq!(curve.add(&g, &g), multiples[2]);
+        assert_eq!(curve.add(&multiples[2], &g), multiples[3]);
+        assert!(curve.add(&g, &-g).is_identity());
+        assert_eq!(curve.add(&g, &JacobianPoint::IDENTITY), g);
+        assert_eq!(curve.add(&JacobianPoint::IDENTITY, &g), g);
+        assert!(curve
+            .add(&JacobianPoint::IDENTITY, &JacobianPoint::IDENTITY)
+            .is_identity());
+
+        // the same point with a different Z still adds as a doubling
+        let scaled = JacobianPoint {
+            x: g.x * F97::from_u64(4),
+            y: g.y * F97::from_u64(8),
+            z: F97::from_u64(2),
+        };
+        assert_eq!(scaled, g);
+        assert_eq!(curve.add(&g, &scaled), multiples[2]);
+    }
+
+    /// Every point found by search lies on the curve after doubling and
+    /// adding, and a point of order two doubles to the identity
+    #[test]
+    fn test_all_points() {
+        let curve = toy_curve();
+        let points: Vec<_> = (0..97)
+            .flat_map(|x| (0..97).map(move |y| (x, y)))
+            .map(|(x, y)| AffinePoint {
+                x: F97::from_u64(x),
+                y: F97::from_u64(y),
+            })
+            .filter(|p| curve.is_on_curve(p))
+            .collect();
+        assert_eq!(points.len() + 1, 100);
+
+        let g = JacobianPoint::from_affine(&curve.base);
+        for p in &points {
+            let p = JacobianPoint::from_affine(p);
+            let doubled = curve.double(&p);
+            let sum = curve.add(&p, &g);
+            for q in [doubled, sum] {
+                if let Some(q) = q.to_affine() {
+                    assert!(curve.is_on_curve(&q));
+                }
+            }
+            assert!(curve.scalar_mult(&[100], &p).is_identity());
+        }
+
+        let order_two = points.iter().find(|p| p.y.is_zero()).unwrap();
+        let order_two = JacobianPoint::from_affine(order_two);
+        assert!(curve.double(&order_two).is_identity());
+        assert_eq!(curve.add(&order_two, &order_two), curve.double(&order_two));
+    }
+
+    #[test]
+    fn test_lift_x() {
+        let curve = toy_curve();
+        let lifted = curve.lift_x(F97::from_u64(3)).unwrap();
+        assert!(lifted.y == F97::from_u64(6) || lifted.y == F97::from_u64(91));
+        // x = 2 gives 15, which is not a square mod 97
+        assert_eq!(curve.lift_x(F97::from_u64(2)), None);
+    }
+}
@@ -1,89 +1,180 @@
-//! y^2 = x^3 + Ax^2 + x (mod P)
-
-use crate::math::ModInverse;
-
-/// We mod by this. Prime field (2^255 - 19 normally)
-const P: u128 = 97;
-/// Generator Point (Base Point) (x-coordinate)
-const G: u128 = 3;
-/// The curve Coeffecient non-singular if (A^2 - 4) % P != 0
-const A: u128 = 5;
-
-fn double_point(x: u128) -> u128 {
-    let x2 = (x * x) % P;
-    let numerator = ((x2 + P - 1) % P).pow(2) % P;
-    let denominator = (4 * x % P * (x2 + A * x + 1) % P) % P;
-    (numerator * denominator.mod_inverse(P)) % P
+//! X25519 key exchange on Montgomery curves `y^2 = x^3 + Ax^2 + x`
+//!
+//! [`X97`] works on a toy curve small enough to follow by hand, [`x25519`]
+//! is the real thing from RFC 7748. Both run on [`MontgomeryCurve`]
+//!
+//! The ladder takes any u-coordinate, and those that are not on the curve
+//! are on its quadratic twist. Curve25519's twist has a large prime order
+//! subgroup so RFC 7748 lets them through, while the twist of the toy curve
+//! has order 108 and leaks the private key mod 27. The checked functions
+//! take a [`Twist`] to say which applies and reject the all-zero output of
+//! a small order peer
+
+use crate::bigint::BigUint;
+use crate::field::{Field, SmallField};
+use crate::field25519::FieldElement;
+use crate::montgomery::MontgomeryCurve;
+
+/// The u-coordinate 9 of the Curve25519 base point
+pub const BASEPOINT: [u8; 32] = {
+    let mut bytes = [0u8; 32];
+    bytes[0] = 9;
+    bytes
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum X25519Error {
+    /// The peer's u-coordinate is not an element of the field
+    InvalidPublicKey,
+    /// The peer's u-coordinate is on the twist and [`Twist::Reject`] was
+    /// asked for
+    PointOnTwist,
+    /// The shared secret is zero, so the peer's point has small order
+    LowOrderPoint,
 }
 
-fn differential_add(curr: u128, next: u128, base: u128) -> u128 {
-    if curr == 0 {
-        return next;
-    }
-    if next == 0 {
-        return curr;
-    }
-    if curr == next {
-        return double_point(curr);
+impl std::fmt::Display for X25519Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let message = match self {
+            Self::InvalidPublicKey => "invalid public key",
+            Self::PointOnTwist => "public key is on the twist",
+            Self::LowOrderPoint => "public key has small order",
+        };
+        f.write_str(message)
     }
+}
 
-    let numerator = ((curr * next + P - 1) % P).pow(2) % P;
+impl std::error::Error for X25519Error {}
 
-    let x_diff = if curr > next {
-        curr - next
-    } else {
-        next - curr
-    };
-    let denominator = (base * x_diff.pow(2)) % P;
-    (numerator * denominator.mod_inverse(P)) % P
+/// Whether a peer's u-coordinate may lie on the twist of the curve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Twist {
+    Reject,
+    Allow,
 }
 
-fn scalar_mult(scalar: u128, base: u128) -> u128 {
-    assert!(scalar != 0);
-
-    let mut curr = base;
-    let mut next = double_point(base);
-
-    // Subtract 1 as we start at step 1 rather than 0
-    for bit in (0..highest_bit(scalar) - 1).rev() {
-        if (scalar >> bit) & 1 == 1 {
-            curr = differential_add(curr, next, base);
-            next = double_point(next);
-        } else {
-            next = differential_add(curr, next, base);
-            curr = double_point(curr);
-        }
-    }
+/// The field of the toy curve
+pub type F97 = SmallField<97>;
+
+/// `y^2 = x^3 + 5x^2 + x` over GF(97), 88 points with the base point 3 of
+/// order 22
+pub fn x97_curve() -> MontgomeryCurve<F97> {
+    MontgomeryCurve::new(
+        F97::from_u64(5),
+        F97::from_u64(3),
+        BigUint::from(22u64),
+        4,
+    )
+}
 
-    curr
+/// Curve25519, `y^2 = x^3 + 486662x^2 + x` over GF(2^255 - 19) with the
+/// base point 9 of prime order 2^252 + 27742317777372353535851937790883648493
+pub fn curve25519() -> MontgomeryCurve<FieldElement> {
+    MontgomeryCurve::new(
+        FieldElement::from_u64(486662),
+        FieldElement::from_u64(9),
+        BigUint::from_hex(
+            "1000000000000000000000000000000014DEF9DEA2F79CD65812631A5CF5D3ED",
+        )
+        .unwrap(),
+        8,
+    )
 }
 
-struct X97 {
-    public: u128,
+/// The ladder walks all 128 bits of the scalar whatever its value
+fn scalar_mult(scalar: u128, base: u128) -> u128 {
+    let base = F97::from_u64(base as u64);
+    x97_curve().scalar_mult(&scalar.to_le_bytes(), base).value() as u128
+}
+
+pub struct X97 {
+    pub public: u128,
     private: u128,
 }
 
 impl X97 {
     pub fn new(private: u128) -> Self {
+        let base = x97_curve().base.value() as u128;
         Self {
-            public: scalar_mult(private, G),
+            public: scalar_mult(private, base),
             private,
         }
     }
 
+    /// The shared secret without any check on `other_public`, which is
+    /// reduced mod 97. See [`X97::shared_secret`]
     pub fn generate_shared_secret(&self, other_public: u128) -> u128 {
         scalar_mult(self.private, other_public)
     }
+
+    /// The shared secret after checking `other_public` is below 97 and on
+    /// the curve, or on the twist if `twist` allows it
+    pub fn shared_secret(
+        &self,
+        other_public: u128,
+        twist: Twist,
+    ) -> Result<u128, X25519Error> {
+        if other_public >= 97 {
+            return Err(X25519Error::InvalidPublicKey);
+        }
+        let u = F97::from_u64(other_public as u64);
+        if twist == Twist::Reject && !x97_curve().is_on_curve(u) {
+            return Err(X25519Error::PointOnTwist);
+        }
+        match scalar_mult(self.private, other_public) {
+            0 => Err(X25519Error::LowOrderPoint),
+            shared => Ok(shared),
+        }
+    }
+}
+
+/// Clears the three low bits so the scalar is a multiple of the cofactor
+/// and fixes the top bit so the ladder's running time cannot depend on it
+fn clamp(mut scalar: [u8; 32]) -> [u8; 32] {
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+    scalar
+}
+
+/// The X25519 function of RFC 7748, the u-coordinate of `scalar` times the
+/// point with u-coordinate `u`. The scalar is clamped and the top bit of
+/// `u` ignored
+pub fn x25519(scalar: [u8; 32], u: [u8; 32]) -> [u8; 32] {
+    let u = FieldElement::from_bytes(&u);
+    curve25519().scalar_mult(&clamp(scalar), u).to_bytes()
 }
 
-#[inline(always)]
-fn highest_bit(scalar: u128) -> u32 {
-    128 - scalar.leading_zeros()
+/// [`x25519`] for a key exchange, rejecting an all-zero result as RFC 7748
+/// section 6.1 recommends and twist points unless `twist` allows them. As
+/// in [`x25519`] the top bit of `u` is ignored and values of p and above
+/// are reduced
+pub fn x25519_shared_secret(
+    scalar: [u8; 32],
+    u: [u8; 32],
+    twist: Twist,
+) -> Result<[u8; 32], X25519Error> {
+    let point = FieldElement::from_bytes(&u);
+    if twist == Twist::Reject && !curve25519().is_on_curve(point) {
+        return Err(X25519Error::PointOnTwist);
+    }
+    let shared = x25519(scalar, u);
+    // fold without branching so the check does not leak which byte is set
+    if shared.iter().fold(0, |acc, byte| acc | byte) == 0 {
+        return Err(X25519Error::LowOrderPoint);
+    }
+    Ok(shared)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hash::hex;
+    use crate::montgomery::XzPoint;
+
+    fn bytes(s: &str) -> [u8; 32] {
+        hex(s).try_into().unwrap()
+    }
 
     #[test]
     fn alicebob() {
@@ -99,33 +190,194 @@ mod tests {
     #[test]
     fn alicebob_raw() {
         let alice_secret = 10;
-        let alice_public = scalar_mult(alice_secret, G);
+        let alice_public = scalar_mult(alice_secret, 3);
 
         let bob_secret = 15;
-        let bob_public = scalar_mult(bob_secret, G);
+        let bob_public = scalar_mult(bob_secret, 3);
 
         let shared_by_alice = scalar_mult(alice_secret, bob_public);
         let shared_by_bob = scalar_mult(bob_secret, alice_public);
         assert_eq!(shared_by_bob, shared_by_alice)
     }
 
+    #[test]
+    fn test_scalar_mult_small() {
+        assert_eq!(scalar_mult(1, 3), 3);
+        assert_eq!(scalar_mult(2, 3), 61);
+        assert_eq!(scalar_mult(0, 3), 0);
+        assert_eq!(scalar_mult(22, 3), 0);
+        // the ladder computes k(lG) the same as (kl)G
+        assert_eq!(scalar_mult(5, scalar_mult(7, 3)), scalar_mult(35, 3));
+    }
+
     #[test]
     fn test_double_point() {
-        assert_eq!(double_point(3), 61);
-        assert_ne!(double_point(3), 60);
+        let curve = x97_curve();
+        let doubled = curve.double(XzPoint::from_affine(curve.base));
+        assert_eq!(doubled.to_affine(), F97::from_u64(61));
+        assert_ne!(doubled.to_affine(), F97::from_u64(60));
     }
 
     #[test]
     fn test_curve_non_singular() {
-        let discriminant = (A.pow(2) - 4) % P;
-        let normalised_discriminant = (discriminant + P) % P;
-        assert_ne!(normalised_discriminant, 0);
+        // A^2 - 4 != 0, or the cubic has a repeated root
+        let a = x97_curve().a.value();
+        assert_ne!((a * a + 97 - 4) % 97, 0);
+        let a = curve25519().a;
+        assert!(!(a.square() - FieldElement::from_u64(4)).is_zero());
     }
 
     #[test]
     fn test_g_on_curve() {
-        let rhs = (G.pow(3) + A * G.pow(2) + G) % P;
-        let on_curve = (0..P).any(|y| y.pow(2) % P == rhs);
-        assert!(on_curve)
+        // some y in GF(97) squares to x^3 + Ax^2 + x at the base point
+        let (g, a) = (x97_curve().base.value(), x97_curve().a.value());
+        let rhs = (g.pow(3) + a * g.pow(2) + g) % 97;
+        assert!((0..97).any(|y| y * y % 97 == rhs));
+        let curve = curve25519();
+        assert!(curve.is_on_curve(curve.base));
+    }
+
+    #[test]
+    fn test_curve_parameters() {
+        assert_eq!(x97_curve().validate(), Ok(()));
+        assert_eq!(curve25519().validate(), Ok(()));
+    }
+
+    /// RFC 7748 section 5.2
+    #[test]
+    fn test_x25519() {
+        assert_eq!(
+            x25519(
+                bytes("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4"),
+                bytes("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c"),
+            ),
+            bytes("c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552")
+        );
+        assert_eq!(
+            x25519(
+                bytes("4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d"),
+                bytes("e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493"),
+            ),
+            bytes("95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957")
+        );
+    }
+
+    /// RFC 7748 section 5.2, feeding each output back in as the scalar
+    #[test]
+    fn test_x25519_iterated() {
+        let mut k = BASEPOINT;
+        let mut u = BASEPOINT;
+        for i in 1..=1000 {
+            let result = x25519(k, u);
+            u = k;
+            k = result;
+            if i == 1 {
+                assert_eq!(
+                    k,
+                    bytes("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079")
+                );
+            }
+        }
+        assert_eq!(
+            k,
+            bytes("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51")
+        );
+    }
+
+    /// RFC 7748 section 6.1
+    #[test]
+    fn test_x25519_diffie_hellman() {
+        let alice = bytes(
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        );
+        let bob = bytes(
+            "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb",
+        );
+        let alice_public = x25519(alice, BASEPOINT);
+        let bob_public = x25519(bob, BASEPOINT);
+        assert_eq!(
+            alice_public,
+            bytes("8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a")
+        );
+        assert_eq!(
+            bob_public,
+            bytes("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+        );
+
+        let shared = bytes(
+            "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742",
+        );
+        assert_eq!(x25519(alice, bob_public), shared);
+        assert_eq!(x25519(bob, alice_public), shared);
+    }
+
+    #[test]
+    fn test_x97_peer_validation() {
+        let alice = X97::new(20);
+        let bob = X97::new(69);
+        assert_eq!(
+            alice.shared_secret(bob.public, Twist::Reject),
+            Ok(bob.generate_shared_secret(alice.public))
+        );
+        assert_eq!(
+            alice.shared_secret(97, Twist::Allow),
+            Err(X25519Error::InvalidPublicKey)
+        );
+
+        // u = 2 is on the twist, whose order 108 = 4 * 27 is all small
+        // factors
+        assert_eq!(
+            alice.shared_secret(2, Twist::Reject),
+            Err(X25519Error::PointOnTwist)
+        );
+        assert_eq!(
+            alice.shared_secret(2, Twist::Allow),
+            Ok(scalar_mult(20, 2))
+        );
+
+        // 0 has order 2, 96 order 4 and 63 and 77 order 8
+        for u in [0, 63, 77, 96] {
+            assert_eq!(
+                alice.shared_secret(u, Twist::Reject),
+                Err(X25519Error::LowOrderPoint)
+            );
+        }
+    }
+
+    #[test]
+    fn test_x25519_peer_validation() {
+        let alice = bytes(
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a",
+        );
+        let bob_public = bytes(
+            "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f",
+        );
+        assert_eq!(
+            x25519_shared_secret(alice, bob_public, Twist::Reject),
+            Ok(x25519(alice, bob_public))
+        );
+
+        let mut two = [0u8; 32];
+        two[0] = 2;
+        assert_eq!(
+            x25519_shared_secret(alice, two, Twist::Reject),
+            Err(X25519Error::PointOnTwist)
+        );
+        assert!(x25519_shared_secret(alice, two, Twist::Allow).is_ok());
+
+        // points of order 1, 2 and 8, and p + 1 which reduces to 1
+        for u in [
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "e0eb7a7c3b41b8ae1656e3faf19fc46ada098deb9c32b1fd866205165f49b800",
+            "5f9c95bca3508c24b1d0b1559c83ef5b04445cc4581c8e86d8224eddd09f1157",
+            "eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        ] {
+            assert_eq!(
+                x25519_shared_secret(alice, bytes(u), Twist::Allow),
+                Err(X25519Error::LowOrderPoint),
+                "{u}"
+            );
+        }
     }
 }
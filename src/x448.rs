@@ -0,0 +1,161 @@
+//! X448 key exchange (RFC 7748) on Curve448, `y^2 = x^3 + 156326x^2 + x`
+//! over GF(2^448 - 2^224 - 1), run on the same [`MontgomeryCurve`] ladder
+//! as [`crate::x25519`]
+
+use crate::bigint::BigUint;
+use crate::field448::FieldElement;
+use crate::montgomery::MontgomeryCurve;
+
+/// The u-coordinate 5 of the Curve448 base point
+pub const BASEPOINT: [u8; 56] = {
+    let mut bytes = [0u8; 56];
+    bytes[0] = 5;
+    bytes
+};
+
+/// Curve448 with the base point 5 of prime order
+/// 2^446 - 13818066809895115352007386748515426880336692474882178609894547503885
+pub fn curve448() -> MontgomeryCurve<FieldElement> {
+    MontgomeryCurve::new(
+        FieldElement::from_u64(156326),
+        FieldElement::from_u64(5),
+        BigUint::from_hex(
+            "3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF
+             7CCA23E9C44EDB49AED63690216CC2728DC58F552378C292AB5844F3",
+        )
+        .unwrap(),
+        4,
+    )
+}
+
+/// Clears the two low bits so the scalar is a multiple of the cofactor
+/// and sets the top bit so the ladder's running time cannot depend on it
+fn clamp(mut scalar: [u8; 56]) -> [u8; 56] {
+    scalar[0] &= 252;
+    scalar[55] |= 128;
+    scalar
+}
+
+/// The X448 function of RFC 7748, the u-coordinate of `scalar` times the
+/// point with u-coordinate `u`. The scalar is clamped, a non-canonical `u`
+/// is reduced
+pub fn x448(scalar: [u8; 56], u: [u8; 56]) -> [u8; 56] {
+    let u = FieldElement::from_bytes(&u);
+    curve448().scalar_mult(&clamp(scalar), u).to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::hex;
+
+    fn bytes(s: &str) -> [u8; 56] {
+        hex(s).try_into().unwrap()
+    }
+
+    #[test]
+    fn test_curve_parameters() {
+        assert_eq!(curve448().validate(), Ok(()));
+    }
+
+    /// RFC 7748 section 5.2
+    #[test]
+    fn test_x448() {
+        assert_eq!(
+            x448(
+                bytes(
+                    "3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121
+                     700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3"
+                ),
+                bytes(
+                    "06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9
+                     814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086"
+                ),
+            ),
+            bytes(
+                "ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239f
+                 e14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f"
+            )
+        );
+        assert_eq!(
+            x448(
+                bytes(
+                    "203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c5
+                     38345dd77c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f"
+                ),
+                bytes(
+                    "0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b
+                     165d015894e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db"
+                ),
+            ),
+            bytes(
+                "884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7
+                 ad1b3ee3a5700df34321d62077e63633c575c1c954514e99da7c179d"
+            )
+        );
+    }
+
+    /// RFC 7748 section 5.2, feeding each output back in as the scalar
+    #[test]
+    fn test_x448_iterated() {
+        let mut k = BASEPOINT;
+        let mut u = BASEPOINT;
+        for i in 1..=1000 {
+            let result = x448(k, u);
+            u = k;
+            k = result;
+            if i == 1 {
+                assert_eq!(
+                    k,
+                    bytes(
+                        "3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a
+                         4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113"
+                    )
+                );
+            }
+        }
+        assert_eq!(
+            k,
+            bytes(
+                "aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4
+                 af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38"
+            )
+        );
+    }
+
+    /// RFC 7748 section 6.2
+    #[test]
+    fn test_x448_diffie_hellman() {
+        let alice = bytes(
+            "9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28d
+             d9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b",
+        );
+        let bob = bytes(
+            "1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d
+             6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d",
+        );
+        let alice_public = x448(alice, BASEPOINT);
+        let bob_public = x448(bob, BASEPOINT);
+        assert_eq!(
+            alice_public,
+            bytes(
+                "9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c
+                 22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0"
+            )
+        );
+        assert_eq!(
+            bob_public,
+            bytes(
+                "3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b430
+                 27d8b972fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609"
+            )
+        );
+
+        let shared = bytes(
+            "07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282b
+             b60c0b56fd2464c335543936521c24403085d59a449a5037514a879d",
+        );
+        assert_eq!(x448(alice, bob_public), shared);
+        assert_eq!(x448(bob, alice_public), shared);
+    }
+}